@@ -0,0 +1,80 @@
+// Copyright (c) 2024-2025, Decisym, LLC
+// Licensed under the BSD 3-Clause License (see LICENSE file in the project root).
+
+//! End-to-end conversion throughput and triple serialization micro-benchmarks.
+//!
+//! Run with `cargo bench --bench conversion`.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use oxrdf::{Graph, Literal, NamedNode, TripleRef};
+use std::hint::black_box;
+use std::io;
+use xml2rdf::convert::{parse_xml_reader, ConversionOptions};
+use xml2rdf::vocab::HAS_VALUE;
+use xml2rdf::writer::{FileWriter, GraphWriter, RdfWriter};
+
+const NAMESPACE: &str = "https://decisym.ai/xml2rdf/data";
+
+/// Generates a catalog of `records` records, each with attributes, nested elements and text, so
+/// the fixture exercises every conversion path without a large checked-in file.
+fn catalog(records: usize) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<catalog>\n");
+    for i in 0..records {
+        xml.push_str(&format!(
+            concat!(
+                "  <record id=\"{i}\" status=\"active\">\n",
+                "    <title>Record number {i}</title>\n",
+                "    <author><name>Author {i}</name><email>author{i}@example.com</email></author>\n",
+                "    <price currency=\"EUR\">{i}.99</price>\n",
+                "    <notes><![CDATA[Free text <b>{i}</b>]]></notes>\n",
+                "  </record>\n"
+            ),
+            i = i
+        ));
+    }
+    xml.push_str("</catalog>\n");
+    xml
+}
+
+fn bench_conversion(c: &mut Criterion) {
+    let xml = catalog(2_000);
+    let options = ConversionOptions::default();
+
+    let mut group = c.benchmark_group("convert catalog (2,000 records)");
+    group.throughput(Throughput::Bytes(xml.len() as u64));
+    group.bench_function("FileWriter", |b| {
+        b.iter(|| {
+            let mut w = FileWriter::new(io::sink());
+            parse_xml_reader(black_box(xml.as_bytes()), &mut w, NAMESPACE, &options).unwrap();
+        })
+    });
+    group.bench_function("GraphWriter", |b| {
+        b.iter_batched_ref(
+            Graph::new,
+            |g| {
+                let mut w = GraphWriter::new(g);
+                parse_xml_reader(black_box(xml.as_bytes()), &mut w, NAMESPACE, &options).unwrap();
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+fn bench_write_triple(c: &mut Criterion) {
+    let subject =
+        NamedNode::new_unchecked(format!("{NAMESPACE}/0b5f8a4e-6c1d-4f5e-9a7b-3c2d1e0f9a8b"));
+    let object = Literal::new_simple_literal("Record number 42");
+    let triple = TripleRef::new(subject.as_ref(), HAS_VALUE, object.as_ref());
+
+    let mut group = c.benchmark_group("write triple");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("FileWriter", |b| {
+        let mut w = FileWriter::new(io::sink());
+        b.iter(|| w.add_triple(black_box(triple)).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_conversion, bench_write_triple);
+criterion_main!(benches);
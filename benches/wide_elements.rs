@@ -0,0 +1,53 @@
+// Copyright (c) 2024-2025, Decisym, LLC
+// Licensed under the BSD 3-Clause License (see LICENSE file in the project root).
+
+//! Conversion throughput for elements with very many attributes.
+//!
+//! Run with `cargo bench --bench wide_elements`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use oxrdf::TripleRef;
+use std::hint::black_box;
+use xml2rdf::convert::{parse_xml_reader, ConversionOptions};
+use xml2rdf::writer::RdfWriter;
+
+/// Counts triples without storing them, so the benchmark measures conversion only.
+struct CountingWriter(usize);
+
+impl RdfWriter for CountingWriter {
+    fn add_triple(&mut self, _triple: TripleRef) -> std::io::Result<()> {
+        self.0 += 1;
+        Ok(())
+    }
+}
+
+fn wide_element(attributes: usize) -> String {
+    let mut xml = String::from("<root><wide");
+    for i in 0..attributes {
+        xml.push_str(&format!(" attr{i}=\"value {i}\""));
+    }
+    xml.push_str("/></root>");
+    xml
+}
+
+fn bench_wide_element(c: &mut Criterion) {
+    let xml = wide_element(5_000);
+    let options = ConversionOptions::default();
+
+    c.bench_function("wide element (5,000 attributes)", |b| {
+        b.iter(|| {
+            let mut w = CountingWriter(0);
+            parse_xml_reader(
+                black_box(xml.as_bytes()),
+                &mut w,
+                "https://decisym.ai/xml2rdf/data",
+                &options,
+            )
+            .unwrap();
+            w.0
+        })
+    });
+}
+
+criterion_group!(benches, bench_wide_element);
+criterion_main!(benches);
@@ -0,0 +1,146 @@
+// Copyright (c) 2024-2025, Decisym, LLC
+// Licensed under the BSD 3-Clause License (see LICENSE file in the project root).
+
+//! # XML2RDF Conversion Cache
+//!
+//! Records the modification time and size of converted files, so repeated batch runs only
+//! convert the files that changed since.
+//!
+//! ## Overview
+//! - [`ConversionCache::open`] reads a cache file, starting afresh if it is missing or cannot be
+//!   read.
+//! - [`ConversionCache::changed`] picks the files that changed since they were recorded, and
+//!   records their current state.
+//! - [`ConversionCache::save`] writes the cache back once the conversion has succeeded.
+//!
+//! The output of skipped files is not written again, so the cache suits outputs that accumulate
+//! across runs, such as an output file appended to or a store, with node IDs that stay the same
+//! across runs (`--node-ids deterministic`). The earlier output of a changed file is not removed;
+//! see [`crate::convert::ConversionOptions::document_graphs`] to replace it.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+
+use crate::convert::push_json_string;
+use crate::json;
+
+/// State of a file when it was last converted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Stamp {
+    /// Modification time, in nanoseconds since the Unix epoch
+    modified: u128,
+    /// Size in bytes
+    size: u64,
+}
+
+impl Stamp {
+    /// Returns the current state of `file`, or `None` if it cannot be read.
+    fn of(file: &str) -> Option<Stamp> {
+        let metadata = fs::metadata(file).ok()?;
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(Stamp {
+            modified: modified.as_nanos(),
+            size: metadata.len(),
+        })
+    }
+}
+
+/// The files converted by earlier runs, stored as a JSON file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionCache {
+    path: PathBuf,
+    files: BTreeMap<String, Stamp>,
+    discarded: bool,
+}
+
+impl ConversionCache {
+    /// Reads the cache at `path`. A missing file yields an empty cache, as does a file that
+    /// cannot be read or parsed, so every file is converted again; see
+    /// [`ConversionCache::discarded`].
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let (files, discarded) = match fs::read_to_string(&path) {
+            Ok(text) => match parse(&text) {
+                Some(files) => (files, false),
+                None => (BTreeMap::new(), true),
+            },
+            Err(e) => (BTreeMap::new(), e.kind() != io::ErrorKind::NotFound),
+        };
+        Self {
+            path,
+            files,
+            discarded,
+        }
+    }
+
+    /// Returns whether an existing cache file was ignored because it could not be read or
+    /// parsed.
+    pub fn discarded(&self) -> bool {
+        self.discarded
+    }
+
+    /// Returns the files of `files` whose modification time or size differ from those recorded,
+    /// or that were never recorded, and records their current state. Files whose state cannot
+    /// be read are returned without being recorded, so their conversion reports why.
+    pub fn changed(&mut self, files: Vec<String>) -> Vec<String> {
+        files
+            .into_iter()
+            .filter(|file| {
+                let Some(stamp) = Stamp::of(file) else {
+                    return true;
+                };
+                self.files.insert(file.clone(), stamp) != Some(stamp)
+            })
+            .collect()
+    }
+
+    /// Writes the cache to its file.
+    pub fn save(&self) -> io::Result<()> {
+        let mut text = String::from("{\"files\": {");
+        for (i, (file, stamp)) in self.files.iter().enumerate() {
+            text.push_str(if i == 0 { "\n  " } else { ",\n  " });
+            push_json_string(&mut text, file);
+            text.push_str(&format!(
+                ": {{\"modified\": {}, \"size\": {}}}",
+                stamp.modified, stamp.size
+            ));
+        }
+        text.push_str("\n}}\n");
+        fs::write(&self.path, text)
+    }
+}
+
+/// Parses the files of a cache, or returns `None` if `text` is not a valid cache.
+fn parse(text: &str) -> Option<BTreeMap<String, Stamp>> {
+    let json::Value::Object(members) = json::parse(text)? else {
+        return None;
+    };
+    let number = |value: &json::Value| match value {
+        json::Value::Number(n) => Some(n.clone()),
+        _ => None,
+    };
+    let mut files = BTreeMap::new();
+    for (key, value) in members {
+        let json::Value::Object(entries) = value else {
+            return None;
+        };
+        if key != "files" {
+            continue;
+        }
+        for (file, stamp) in entries {
+            let json::Value::Object(fields) = stamp else {
+                return None;
+            };
+            let field = |name: &str| fields.iter().find(|(key, _)| key == name).map(|f| &f.1);
+            let stamp = Stamp {
+                modified: number(field("modified")?)?.parse().ok()?,
+                size: number(field("size")?)?.parse().ok()?,
+            };
+            files.insert(file, stamp);
+        }
+    }
+    Some(files)
+}
@@ -0,0 +1,370 @@
+// Copyright (c) 2024-2025, Decisym, LLC
+// Licensed under the BSD 3-Clause License (see LICENSE file in the project root).
+
+//! # XML2RDF Canonical Output
+//!
+//! Implements the [RDF Dataset Canonicalization](https://www.w3.org/TR/rdf-canon/) algorithm
+//! (RDFC-1.0, formerly URDNA2015) so that equal datasets serialize to identical N-Quads,
+//! regardless of how their blank nodes are labeled.
+//!
+//! ## Overview
+//! - [`canonical_nquads`] relabels the blank nodes of a dataset and returns sorted N-Quads.
+//! - [`CanonicalWriter`] buffers converted triples in memory and writes them canonicalized once
+//!   conversion finishes.
+
+use oxrdf::{
+    BlankNode, BlankNodeRef, Dataset, GraphNameRef, QuadRef, SubjectRef, TermRef, TripleRef,
+};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+
+use crate::writer::RdfWriter;
+
+/// Buffers all triples in memory and, on flush, writes them to the wrapped writer as canonical
+/// N-Quads.
+pub struct CanonicalWriter<W: Write> {
+    dataset: Dataset,
+    writer: W,
+}
+
+impl<W: Write> CanonicalWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            dataset: Dataset::new(),
+            writer,
+        }
+    }
+}
+
+impl<W: Write> RdfWriter for CanonicalWriter<W> {
+    fn add_triple(&mut self, triple: TripleRef) -> io::Result<()> {
+        self.dataset
+            .insert(triple.in_graph(GraphNameRef::DefaultGraph));
+        Ok(())
+    }
+
+    fn add_quad(&mut self, quad: QuadRef) -> io::Result<()> {
+        self.dataset.insert(quad);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer
+            .write_all(canonical_nquads(&self.dataset).as_bytes())?;
+        self.dataset.clear();
+        self.writer.flush()
+    }
+}
+
+/// Returns the canonical N-Quads serialization of `dataset`: blank nodes are relabeled
+/// `_:c14n0`, `_:c14n1`, ... following RDFC-1.0 and the lines are sorted.
+///
+/// The algorithm is exponential in the worst case, for datasets with many blank nodes that can
+/// only be told apart by their position in large symmetric structures.
+pub fn canonical_nquads(dataset: &Dataset) -> String {
+    let state = State::new(dataset);
+    let canonical = state.canonical_labels();
+
+    let mut lines: Vec<String> = dataset
+        .iter()
+        .map(|quad| nquad(quad, |b| canonical[b].clone()))
+        .collect();
+    lines.sort();
+    lines.dedup();
+    lines.concat()
+}
+
+/// Issues sequential identifiers with a fixed prefix, remembering the order of issue.
+#[derive(Clone)]
+struct IdentifierIssuer {
+    prefix: &'static str,
+    issued: HashMap<String, String>,
+    order: Vec<String>,
+}
+
+impl IdentifierIssuer {
+    fn new(prefix: &'static str) -> Self {
+        Self {
+            prefix,
+            issued: HashMap::new(),
+            order: Vec::new(),
+        }
+    }
+
+    fn get(&self, id: &str) -> Option<&str> {
+        self.issued.get(id).map(String::as_str)
+    }
+
+    fn issue(&mut self, id: &str) -> String {
+        if let Some(issued) = self.issued.get(id) {
+            return issued.clone();
+        }
+        let issued = format!("{}{}", self.prefix, self.order.len());
+        self.issued.insert(id.to_string(), issued.clone());
+        self.order.push(id.to_string());
+        issued
+    }
+}
+
+/// Canonicalization state: the quads mentioning each blank node, by blank node label.
+struct State<'a> {
+    quads: HashMap<&'a str, Vec<QuadRef<'a>>>,
+    canonical: IdentifierIssuer,
+}
+
+impl<'a> State<'a> {
+    fn new(dataset: &'a Dataset) -> Self {
+        let mut quads: HashMap<&str, Vec<QuadRef>> = HashMap::new();
+        for quad in dataset.iter() {
+            let mut labels = blank_nodes(quad);
+            labels.sort_unstable();
+            labels.dedup();
+            for label in labels {
+                quads.entry(label).or_default().push(quad);
+            }
+        }
+        Self {
+            quads,
+            canonical: IdentifierIssuer::new("c14n"),
+        }
+    }
+
+    /// Runs the canonicalization algorithm, returning the canonical label of each blank node.
+    fn canonical_labels(mut self) -> HashMap<String, String> {
+        let mut by_hash: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+        for label in self.quads.keys() {
+            by_hash
+                .entry(self.hash_first_degree(label))
+                .or_default()
+                .push(label);
+        }
+
+        // Blank nodes with a unique first degree hash are labeled in hash order
+        let mut shared = Vec::new();
+        for labels in by_hash.into_values() {
+            if let [label] = labels[..] {
+                self.canonical.issue(label);
+            } else {
+                shared.push(labels);
+            }
+        }
+
+        // The others are told apart by the paths to their neighbours
+        for labels in shared {
+            let mut paths = Vec::new();
+            for label in labels {
+                if self.canonical.get(label).is_some() {
+                    continue;
+                }
+                let mut issuer = IdentifierIssuer::new("b");
+                issuer.issue(label);
+                paths.push(self.hash_n_degree(label, issuer));
+            }
+            paths.sort_by(|a, b| a.0.cmp(&b.0));
+            for (_, issuer) in paths {
+                for label in &issuer.order {
+                    self.canonical.issue(label);
+                }
+            }
+        }
+
+        self.canonical.issued
+    }
+
+    /// Hashes the quads mentioning `label`, with `label` written `_:a` and any other blank node
+    /// `_:z`.
+    fn hash_first_degree(&self, label: &str) -> String {
+        let mut lines: Vec<String> = self.quads[label]
+            .iter()
+            .map(|quad| nquad(*quad, |b| if b == label { "a" } else { "z" }.to_string()))
+            .collect();
+        lines.sort();
+        sha256(&lines.concat())
+    }
+
+    /// Hashes the blank node `related`, found at `position` of `quad`, as seen from the node
+    /// being hashed.
+    fn hash_related(
+        &self,
+        related: &str,
+        quad: QuadRef,
+        issuer: &IdentifierIssuer,
+        position: &str,
+    ) -> String {
+        let mut input = position.to_string();
+        if position != "g" {
+            input.push_str(&quad.predicate.to_string());
+        }
+        if let Some(id) = self.canonical.get(related).or_else(|| issuer.get(related)) {
+            input.push_str("_:");
+            input.push_str(id);
+        } else {
+            input.push_str(&self.hash_first_degree(related));
+        }
+        sha256(&input)
+    }
+
+    /// Hashes `label` together with the paths to its related blank nodes, returning the hash and
+    /// the issuer holding the temporary labels chosen along the way.
+    fn hash_n_degree(
+        &self,
+        label: &str,
+        mut issuer: IdentifierIssuer,
+    ) -> (String, IdentifierIssuer) {
+        let mut by_hash: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+        for quad in &self.quads[label] {
+            let positions = [
+                (subject_label(quad.subject), "s"),
+                (term_label(quad.object), "o"),
+                (graph_label(quad.graph_name), "g"),
+            ];
+            for (related, position) in positions {
+                match related {
+                    Some(related) if related != label => {
+                        let hash = self.hash_related(related, *quad, &issuer, position);
+                        by_hash.entry(hash).or_default().push(related);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut data = String::new();
+        for (hash, related) in by_hash {
+            data.push_str(&hash);
+            let mut chosen_path = String::new();
+            let mut chosen_issuer = None;
+            for permutation in permutations(&related) {
+                let mut issuer_copy = issuer.clone();
+                let mut path = String::new();
+                let mut recursion = Vec::new();
+                let longer = |path: &str, chosen: &str| {
+                    !chosen.is_empty() && path.len() >= chosen.len() && path > chosen
+                };
+
+                let mut skipped = false;
+                for node in &permutation {
+                    if let Some(id) = self.canonical.get(node) {
+                        path.push_str("_:");
+                        path.push_str(id);
+                    } else {
+                        if issuer_copy.get(node).is_none() {
+                            recursion.push(*node);
+                        }
+                        path.push_str("_:");
+                        path.push_str(&issuer_copy.issue(node));
+                    }
+                    if longer(&path, &chosen_path) {
+                        skipped = true;
+                        break;
+                    }
+                }
+                if skipped {
+                    continue;
+                }
+
+                for node in recursion {
+                    let (result, result_issuer) = self.hash_n_degree(node, issuer_copy.clone());
+                    path.push_str("_:");
+                    path.push_str(&issuer_copy.issue(node));
+                    path.push('<');
+                    path.push_str(&result);
+                    path.push('>');
+                    issuer_copy = result_issuer;
+                    if longer(&path, &chosen_path) {
+                        skipped = true;
+                        break;
+                    }
+                }
+                if skipped {
+                    continue;
+                }
+
+                if chosen_path.is_empty() || path < chosen_path {
+                    chosen_path = path;
+                    chosen_issuer = Some(issuer_copy);
+                }
+            }
+            data.push_str(&chosen_path);
+            if let Some(chosen) = chosen_issuer {
+                issuer = chosen;
+            }
+        }
+        (sha256(&data), issuer)
+    }
+}
+
+/// Serializes `quad` as an N-Quads line, relabeling blank nodes with `label`.
+fn nquad(quad: QuadRef, label: impl Fn(&str) -> String) -> String {
+    let relabel = |b: BlankNodeRef| BlankNode::new_unchecked(label(b.as_str()));
+    let subject = match quad.subject {
+        SubjectRef::BlankNode(b) => relabel(b).to_string(),
+        s => s.to_string(),
+    };
+    let object = match quad.object {
+        TermRef::BlankNode(b) => relabel(b).to_string(),
+        o => o.to_string(),
+    };
+    match quad.graph_name {
+        GraphNameRef::DefaultGraph => format!("{subject} {} {object} .\n", quad.predicate),
+        GraphNameRef::BlankNode(b) => {
+            format!("{subject} {} {object} {} .\n", quad.predicate, relabel(b))
+        }
+        g => format!("{subject} {} {object} {g} .\n", quad.predicate),
+    }
+}
+
+/// Returns the labels of the blank nodes in `quad`, in subject, object, graph order.
+fn blank_nodes(quad: QuadRef<'_>) -> Vec<&str> {
+    [
+        subject_label(quad.subject),
+        term_label(quad.object),
+        graph_label(quad.graph_name),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+fn subject_label(subject: SubjectRef<'_>) -> Option<&str> {
+    match subject {
+        SubjectRef::BlankNode(b) => Some(b.as_str()),
+        _ => None,
+    }
+}
+
+fn term_label(term: TermRef<'_>) -> Option<&str> {
+    match term {
+        TermRef::BlankNode(b) => Some(b.as_str()),
+        _ => None,
+    }
+}
+
+fn graph_label(graph_name: GraphNameRef<'_>) -> Option<&str> {
+    match graph_name {
+        GraphNameRef::BlankNode(b) => Some(b.as_str()),
+        _ => None,
+    }
+}
+
+/// Returns every ordering of `items`.
+fn permutations<'a>(items: &[&'a str]) -> Vec<Vec<&'a str>> {
+    if items.len() <= 1 {
+        return vec![items.to_vec()];
+    }
+    let mut result = Vec::new();
+    for i in 0..items.len() {
+        let mut rest = items.to_vec();
+        let item = rest.remove(i);
+        for mut permutation in permutations(&rest) {
+            permutation.insert(0, item);
+            result.push(permutation);
+        }
+    }
+    result
+}
+
+fn sha256(data: &str) -> String {
+    format!("{:x}", Sha256::digest(data.as_bytes()))
+}
@@ -0,0 +1,80 @@
+// Copyright (c) 2024-2025, Decisym, LLC
+// Licensed under the BSD 3-Clause License (see LICENSE file in the project root).
+
+//! # XML2RDF Well-Formedness Check
+//!
+//! Checks that documents are well-formed XML without converting them, as a fast pre-flight
+//! check before a conversion.
+//!
+//! ## Overview
+//! - [`check_well_formed`] streams a document through the XML parser, keeping nothing but the
+//!   first error, if any.
+//! - [`check_file`] does the same for a file.
+//!
+//! Unlike a conversion, which keeps the triples of a document read up to a syntax error, the
+//! check fails on any error, including a missing root element and unclosed elements at the end.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use xml::common::{Position, TextPosition};
+use xml::reader::{EventReader, XmlEvent};
+
+/// The first error found in a document that is not well-formed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Malformed {
+    /// Location in the document of the error.
+    pub position: TextPosition,
+    /// What is wrong, as reported by the parser.
+    pub message: String,
+}
+
+/// Writes the 1-based `line:column` of the error followed by its message.
+impl fmt::Display for Malformed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.position, self.message)
+    }
+}
+
+impl std::error::Error for Malformed {}
+
+/// Reads the document from `reader` to its end, returning the first error that makes it not
+/// well-formed:
+///
+/// ```
+/// use xml2rdf::check::check_well_formed;
+///
+/// assert_eq!(check_well_formed("<a><b/></a>".as_bytes()), Ok(()));
+/// let e = check_well_formed("<a>\n  <b></a>".as_bytes()).unwrap_err();
+/// assert_eq!(e.to_string(), "2:9: Unexpected closing tag: a != b");
+/// ```
+///
+/// An error reading the input is reported at the position the parser had reached.
+pub fn check_well_formed(reader: impl Read) -> Result<(), Malformed> {
+    let mut parser = EventReader::new(BufReader::new(reader));
+    loop {
+        match parser.next() {
+            Ok(XmlEvent::EndDocument) => return Ok(()),
+            Ok(_) => {}
+            Err(e) => {
+                // The message follows the position
+                let position = e.position();
+                let message = e.to_string();
+                let message = message
+                    .strip_prefix(&format!("{position} "))
+                    .unwrap_or(&message);
+                return Err(Malformed {
+                    position,
+                    message: message.to_string(),
+                });
+            }
+        }
+    }
+}
+
+/// Checks the file at `path`, see [`check_well_formed`]. Fails if the file cannot be opened.
+pub fn check_file(path: impl AsRef<Path>) -> io::Result<Result<(), Malformed>> {
+    Ok(check_well_formed(File::open(path)?))
+}
@@ -13,30 +13,1096 @@
 //! - Converts nested XML Objects into RDF triples.
 //! - Allows specifying a custom RDF namespace for generated predicates and objects.
 //! - Outputs the RDF data to a specified file.
+//!
+//! ## Triple order
+//! Triples are written in a fixed order, so converting the same input with the same options and
+//! [`NodeIds::Deterministic`] yields identical output, suitable for golden files and diffs. For
+//! each element, in document order:
+//! 1. The link from its parent, `hasChild` (then `hasParent`), or for the root element its
+//!    document node.
+//! 2. Its metadata: `inDocument`, `rdf:type` with its class (preceded by the class's
+//!    `owl:equivalentClass` declaration the first time it is seen), domain classes, `hasName`,
+//!    `hasQName` and `rdfs:subClassOf` `XmlNode`.
+//! 3. Its attributes, in document order or, with [`ConversionOptions::sort_attributes`], by
+//!    namespace URI and local name. Each attribute is written in full before the next:
+//!    `hasAttribute`, `rdf:type` with its class, that class's `rdfs:subClassOf` and its value.
+//! 4. Its namespace declarations and `startOffset`.
+//! 5. Its child elements, each in this same order, recursively.
+//! 6. At its end tag: `endOffset`, its `isNil` or `isEmpty` marker, its text values and its
+//!    `hasContent` list.
+//!
+//! Triples only needed once per run, such as the ontology, come before the first element.
 
-use const_format::concatcp;
-use oxrdf::vocab::rdf::TYPE;
-use oxrdf::vocab::rdfs::SUB_CLASS_OF;
-use oxrdf::{Literal, NamedNode, NamedNodeRef, TermRef, TripleRef};
+use oxrdf::vocab::rdf::{FIRST, NIL, REST, TYPE};
+use oxrdf::vocab::rdfs::{SUB_CLASS_OF, SUB_PROPERTY_OF};
+use oxrdf::vocab::xsd;
+use oxrdf::{
+    BlankNode, Graph, Literal, NamedNode, NamedNodeRef, NamedOrBlankNode, QuadRef, Term, TermRef,
+    Triple, TripleRef,
+};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::io::BufRead;
+use std::path::Path;
+use std::time::SystemTime;
 use uuid::Uuid;
+use xml::attribute::OwnedAttribute;
+use xml::common::{Position, TextPosition};
+use xml::name::OwnedName;
+use xml::namespace::{Namespace, NamespaceStack};
 use xml::reader::{EventReader, XmlEvent};
 
-use crate::writer::RdfWriter;
+use crate::datatype::{self, BinaryEncoding};
+#[cfg(feature = "digest")]
+use crate::digest::{DigestAlgorithm, DigestReader};
+use crate::error::{ConfigError, ConvertError};
+use crate::json;
+use crate::vocab::*;
+use crate::writer::{GraphWriter, RdfWriter, StringWriter};
 
 #[derive(Debug, Clone)]
-struct Node {
+struct Node<'a> {
     path: String,
-    id: NamedNode,
+    id: NamedOrBlankNode,
+    name: String,
+    text: Vec<String>,
+    /// Number of child elements seen so far
+    children: usize,
+    /// Position of the element within its document, `{source}/{index}/{index}...` by sibling
+    /// index; only tracked for [`NodeIds::Deterministic`]
+    key: String,
+    /// Namespace mappings in scope, kept only to detect declarations on child elements
+    namespace: Option<Namespace>,
+    /// Attributes not written yet, with their indices, held back until it is known whether the
+    /// element is a leaf or has text
+    attributes: Vec<(usize, OwnedAttribute)>,
+    /// Attribute to promote to the element's value, with its index; only tracked for
+    /// [`ConversionOptions::promoted_attributes`]
+    promoted: Option<(usize, OwnedAttribute)>,
+    /// Text runs and child elements in document order; only tracked for
+    /// [`BranchTextPolicy::Mixed`]
+    content: Vec<Content>,
+    /// Text runs with their positions among the element's child nodes, text nodes included;
+    /// only tracked for [`ConversionOptions::text_as_node`]
+    runs: Vec<(usize, String)>,
+    /// Position of the element's start tag
+    position: TextPosition,
+    /// Data namespace the element, its attributes and its attribute bag are minted under
+    data_namespace: &'a str,
+    /// The path as written in class IRIs, joined with [`ConversionOptions::path_delimiter`];
+    /// only tracked when that is not `.`
+    class_path: Option<String>,
+    /// Whether the element is declared nil with `xsi:nil="true"`; only tracked for
+    /// [`ConversionOptions::empty_markers`] and [`ConversionOptions::flag_elements`]
+    nil: bool,
+}
+
+/// A child element held back by [`ConversionOptions::collapse_wrappers`] until it is known
+/// whether its parent is a wrapper to collapse into, with the events needed to convert it after
+/// all.
+struct HeldChild {
+    name: OwnedName,
+    namespace: Namespace,
+    position: TextPosition,
+    /// Offset of the start tag's `<`
+    start_offset: u64,
+    /// Text inside the child, fragment by fragment
+    text: Vec<String>,
+    /// Offset just past the end tag, once the child has ended
+    end_offset: Option<u64>,
+    /// Whitespace in the parent after the child ended
+    trailing: Vec<String>,
+}
+
+/// An item of an element's content.
+#[derive(Debug, Clone)]
+enum Content {
+    /// A run of text between tags, exactly as in the document
+    Text(String),
+    /// A child element
+    Element(NamedOrBlankNode),
+}
+
+/// Controls how an element with several text fragments (split text, CDATA sections, or text
+/// interleaved with child elements) emits its `hasValue` triples.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum MultiValuePolicy {
+    /// Join all fragments in document order into a single `hasValue` literal.
+    #[default]
+    Concatenate,
+    /// Keep only the first fragment.
+    FirstOnly,
+    /// Emit a single `hasValue` pointing at an `rdf:List` of the fragments.
+    List,
+    /// Emit one `hasValue` triple per fragment.
+    AllowMultiple,
+}
+
+/// Controls the text of branch elements, i.e. elements that also have child elements.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum BranchTextPolicy {
+    /// Emit the text with `hasValue`, like the text of leaf elements.
+    #[default]
+    Keep,
+    /// Ignore the text of branch elements, for data where text between child elements is
+    /// accidental. Leaf elements keep their text.
+    DropWhenHasChildren,
+    /// Additionally describe elements with mixed content, i.e. child elements and
+    /// non-whitespace text, with an ordered `hasContent` list of their text runs and child
+    /// elements.
+    ///
+    /// Each list item is either a child element node or a plain literal holding a run of text
+    /// exactly as it appears between two tags, untrimmed and including whitespace-only runs.
+    /// So inline markup in prose such as `<p>a <b>b</b> c</p>` keeps the spaces around `<b>`
+    /// and can be reconstructed exactly. The usual `hasValue` triples are emitted as well.
+    Mixed,
+}
+
+/// Determines how element, attribute, attribute bag and document nodes are identified.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NodeIds {
+    /// Mint a random UUID IRI under the data namespace for every node.
+    #[default]
+    Uuid,
+    /// Use a fresh blank node for every node. Useful together with canonicalization, which
+    /// relabels blank nodes so equal documents convert to identical output.
+    Blank,
+    /// Mint a name-based UUID (version 5) IRI derived from the node's position in its document:
+    /// the source file and the sibling index of each element from the root, plus the
+    /// namespace-qualified name for attribute nodes. Converting the same file again yields the
+    /// same IRIs, while documents read from different paths never share nodes.
+    Deterministic {
+        /// Hash function deriving the UUIDs from the positions. Changing it changes every IRI,
+        /// so output meant to be merged with earlier conversions must keep the same hash.
+        hash: IdHash,
+    },
+    /// Mint a random UUID URN, `urn:x2r:{uuid}`, for every node, ignoring the data namespace.
+    /// Shorter than namespace IRIs, for graphs that never leave the application.
+    Urn,
+}
+
+impl NodeIds {
+    /// Returns the command line name of the strategy, e.g. `deterministic`.
+    fn name(self) -> &'static str {
+        match self {
+            NodeIds::Uuid => "uuid",
+            NodeIds::Blank => "blank",
+            NodeIds::Deterministic { .. } => "deterministic",
+            NodeIds::Urn => "urn",
+        }
+    }
+}
+
+/// Hash function deriving the UUIDs of [`NodeIds::Deterministic`] nodes from their position
+/// key. Each yields a UUID, so external tools can
+/// reproduce the IRIs from the keys with any RFC 9562 implementation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum IdHash {
+    /// Name-based UUID version 5: SHA-1 of the URL namespace UUID
+    /// `6ba7b811-9dad-11d1-80b4-00c04fd430c8` followed by the UTF-8 key.
+    #[default]
+    Sha1,
+    /// UUID version 8 holding the first 16 bytes of the SHA-256 of the UTF-8 key, with the
+    /// version and variant bits set as RFC 9562 prescribes.
+    #[cfg(feature = "digest")]
+    Sha256,
+    /// UUID version 8 holding the 128-bit FNV-1a hash of the UTF-8 key, big-endian, with the
+    /// version and variant bits set as RFC 9562 prescribes. Not cryptographic, but faster than
+    /// SHA-1 and SHA-256 for the short keys of nodes.
+    Fnv1a,
+}
+
+impl IdHash {
+    /// Returns the UUID of `key`.
+    fn uuid(self, key: &str) -> Uuid {
+        match self {
+            IdHash::Sha1 => Uuid::new_v5(&Uuid::NAMESPACE_URL, key.as_bytes()),
+            #[cfg(feature = "digest")]
+            IdHash::Sha256 => {
+                use sha2::{Digest, Sha256};
+
+                let digest = Sha256::digest(key.as_bytes());
+                let mut bytes = [0; 16];
+                bytes.copy_from_slice(&digest[..16]);
+                uuid::Builder::from_custom_bytes(bytes).into_uuid()
+            }
+            IdHash::Fnv1a => {
+                const OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+                const PRIME: u128 = 0x0000000001000000000000000000013b;
+                let hash = key.bytes().fold(OFFSET_BASIS, |hash, byte| {
+                    (hash ^ u128::from(byte)).wrapping_mul(PRIME)
+                });
+                uuid::Builder::from_custom_bytes(hash.to_be_bytes()).into_uuid()
+            }
+        }
+    }
+}
+
+/// Determines which classes element and attribute nodes are typed with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ClassKey {
+    /// Key classes on the full element path from the document root, e.g. `x2r:people.person`.
+    /// Elements with the same name under different parents get different classes.
+    #[default]
+    Path,
+    /// Key classes on the element name alone, e.g. `x2r:person`, so equally named elements share
+    /// one class across documents regardless of their parents. Attribute classes are keyed on
+    /// the owning element's name plus the attribute name, e.g. `x2r:person.-id`. Each distinct
+    /// path class is declared `owl:equivalentClass` to its canonical class once per run.
+    Name,
+}
+
+/// Determines how the inverses of the structural links, `hasParent` for `hasChild` and
+/// `attributeOf` for `hasAttribute`, are made available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum InverseLinks {
+    /// Emit a `hasParent` triple for every `hasChild` triple and an `attributeOf` triple for
+    /// every `hasAttribute` triple. Simple to query, at the cost of one triple per link.
+    Materialized,
+    /// Emit `hasParent owl:inverseOf hasChild` and `attributeOf owl:inverseOf hasAttribute` once,
+    /// before any converted data, so an OWL reasoner can derive the inverse links.
+    Declared,
+}
+
+/// Determines how JSON embedded in the text of an element is converted, see
+/// [`ConversionOptions::embedded_json`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum EmbeddedJson {
+    /// Model the JSON with further nodes, as if it had been written as child elements.
+    ///
+    /// Each object member becomes a child node named after its key, with the member value as
+    /// its `hasValue`: a plain literal for strings, `xsd:boolean` for booleans, and
+    /// `xsd:integer`, `xsd:decimal` or `xsd:double` for numbers, depending on their form. A
+    /// `null` member has no value, an object member has its own members as children. An array
+    /// member becomes one child per item, like repeated elements; the items of a top-level
+    /// array, and of an array nested directly in an array, are named `item`. The nodes are
+    /// typed with path classes that are `rdfs:subClassOf` `JsonValue`. Keys are encoded in
+    /// class IRIs as [`ConversionOptions::name_encoding`] says, while `hasName` holds them as
+    /// written.
+    Expand,
+    /// Emit the text as a single `rdf:JSON` literal instead of a plain one.
+    Literal,
+}
+
+/// Determines how names with characters not allowed in IRIs become components of class IRIs
+/// and paths, see [`ConversionOptions::name_encoding`].
+///
+/// Letters, digits, `-`, `_` and `~` are safe; [`NameEncoding::Slug`] and
+/// [`NameEncoding::Reject`] also take non-ASCII letters and digits as safe, since IRIs allow
+/// them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum NameEncoding {
+    /// Percent-encode the UTF-8 bytes of every other character, e.g. `first%20name`. Names
+    /// stay distinct, and the encoding can be undone.
+    #[default]
+    Percent,
+    /// Replace each run of unsafe characters with `-`, e.g. `first-name`, for readable IRIs. A
+    /// name whose slug an earlier name of the conversion already has gets `-2`, `-3` and so on
+    /// appended, so names stay distinct.
+    Slug,
+    /// Fail with [`ConvertError::UnsafeName`] on a name with unsafe characters, for pipelines
+    /// that must not rename anything.
+    Reject,
+}
+
+/// Determines how source files are identified in provenance, see
+/// [`ConversionOptions::source_iris`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum SourceIris {
+    /// The `file://` IRI of the absolute path, e.g. `file:///home/me/data/a.xml`, or
+    /// `file:///C:/data/a.xml` and `file://server/share/a.xml` on Windows.
+    #[default]
+    File,
+    /// The path relative to the working directory, resolved against this base IRI, e.g.
+    /// `https://example.com/data/a.xml` for `data/a.xml` under `https://example.com/`. Files
+    /// outside the working directory are identified by their file name alone.
+    Base(String),
+    /// An opaque `urn:uuid:` IRI derived from the `file://` IRI, so the same file always maps to
+    /// the same IRI without revealing where it is stored.
+    Opaque,
+}
+
+/// Determines how a document is identified when its triples are written to a named graph of its
+/// own, see [`ConversionOptions::document_graphs`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocumentIdentity {
+    /// The IRI of the file the document was read from, as configured by
+    /// [`ConversionOptions::source_iris`], e.g. `file:///home/me/data/a.xml`.
+    Source,
+    /// The file name of the source, percent-encoded, under the data namespace, as for
+    /// [`DocumentIri::FileName`], e.g. `https://decisym.ai/xml2rdf/data/a.xml` for `data/a.xml`.
+    /// Unlike [`DocumentIdentity::Source`], it does not reveal where the file is stored, but
+    /// converting files of the same name in different directories together fails with
+    /// [`ConvertError::DuplicateGraph`].
+    FileName,
+    /// The value of this attribute of the root element, given by its qualified name as written,
+    /// appended percent-encoded to the data namespace, e.g.
+    /// `https://decisym.ai/xml2rdf/data/order-17` for `<order id="order-17">` under `id`.
+    RootAttribute(String),
+}
+
+/// Determines the IRI of document nodes, see [`ConversionOptions::document_iri`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum DocumentIri {
+    /// Minted like any other node, as configured by [`ConversionOptions::node_ids`].
+    #[default]
+    Minted,
+    /// The file name of the source, percent-encoded, under the data namespace, e.g.
+    /// `https://decisym.ai/xml2rdf/data/orders.xml` for `data/orders.xml`. An archive entry is
+    /// named by its path below the archive's file name, e.g. `.../data/batch.zip/a/orders.xml`.
+    FileName,
+    /// This IRI, for conversions of a single document.
+    Explicit(NamedNode),
+}
+
+/// Determines the conversion time recorded on document nodes, see
+/// [`ConversionOptions::conversion_time`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ConversionTime {
+    /// No conversion time, so converting the same input again yields the same output.
+    #[default]
+    Omit,
+    /// The time the conversion started, from the system clock. There is no system clock on
+    /// `wasm32-unknown-unknown`, so use [`ConversionTime::Fixed`] there.
+    Now,
+    /// A fixed `xsd:dateTime`, such as `2024-01-01T00:00:00Z`, written as given.
+    Fixed(String),
+}
+
+/// An attribute promoted to the value of its element, see
+/// [`ConversionOptions::promoted_attributes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromotedAttribute {
+    /// Name of the attribute, written like the last step of attribute paths: `id`, or
+    /// `{namespace}local` with `preserve_namespaces`.
+    pub attribute: String,
+    /// Predicate linking the element to the attribute value, instead of the one for element text.
+    pub predicate: Option<NamedNode>,
+}
+
+/// Determines how content that is not valid in its [`ConversionOptions::binary`] encoding is
+/// handled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum BinaryPolicy {
+    /// Fail the conversion with [`ConvertError::InvalidBinary`].
+    #[default]
+    Strict,
+    /// Keep the content as a plain literal and record a [`WarningKind::InvalidBinary`] warning.
+    Warn,
+}
+
+/// Determines how byte sequences that are not valid UTF-8 are handled in documents encoded in
+/// UTF-8, e.g. a file in another encoding that does not declare it.
+///
+/// Documents that declare another encoding, or start with a UTF-16 byte order mark, are decoded
+/// by the XML parser and not affected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Utf8Policy {
+    /// Fail the conversion with [`ConvertError::InvalidUtf8`], reporting the byte offset of the
+    /// first invalid sequence.
+    #[default]
+    Strict,
+    /// Replace each invalid sequence with U+FFFD REPLACEMENT CHARACTER and keep converting, so a
+    /// single bad byte does not cost the rest of a large file.
+    Lossy,
+}
+
+/// Options controlling how XML is converted to RDF.
+#[derive(Debug, Clone)]
+pub struct ConversionOptions {
+    /// How multiple text fragments on a single element are emitted.
+    pub multi_value_policy: MultiValuePolicy,
+    /// Delimiters splitting the text of elements holding value lists, e.g. `,` for
+    /// `<tags>a,b,c</tags>`, which then gets the three values `a`, `b` and `c`.
+    ///
+    /// Keys are matched like those of [`ConversionOptions::class_map`]. The element's text is
+    /// joined first and then split at each occurrence of the delimiter. Each piece is trimmed
+    /// according to `trim_text` and typed on its own; empty pieces are dropped. The pieces are
+    /// separate `hasValue` values, or a single `rdf:List` with [`MultiValuePolicy::List`]; the
+    /// other policies do not apply to split elements.
+    pub split_values: HashMap<String, String>,
+    /// Group each element's attributes under a single attribute bag node.
+    ///
+    /// When enabled, an element with attributes links to one `XmlAttributeBag` node via
+    /// `hasAttributeBag`, and the bag links to every attribute node via `hasAttribute`. The bag is
+    /// an IRI minted under the data namespace, like element and attribute nodes.
+    pub attribute_bag: bool,
+    /// Write each attribute as a property of its element, `element x2r:-name "value"`, instead
+    /// of an attribute node, for a flat shape suited to property graphs.
+    ///
+    /// The predicate is keyed by the attribute name like attribute classes are: its local name,
+    /// or `{namespace}local` with `preserve_namespaces`, encoded as in class IRIs.
+    /// Attributes of different namespaces with the same local name therefore share a predicate
+    /// unless namespaces are preserved, and the element gets a value for each. XML rules out
+    /// repeated attributes, so no other attributes share one. Values are written like attribute
+    /// values otherwise, including their datatype and whitespace handling. With attribute nodes
+    /// gone, `attribute_bag` has no effect and no `attributeIndex` is recorded. Under
+    /// `owl_safe` each predicate is declared an `owl:DatatypeProperty`, and with `embed_ontology`
+    /// an `rdfs:subPropertyOf` the attribute value predicate of `vocabulary`.
+    pub property_style: bool,
+    /// Link each element to its parent with a predicate derived from its name,
+    /// `parent x2r:has-name child`, instead of `hasChild`.
+    ///
+    /// The predicate is keyed by the element name like the predicates of `property_style`. With
+    /// `embed_ontology` each predicate is declared `rdfs:subPropertyOf hasChild` once, so
+    /// structural queries on `hasChild` still find every child through RDFS reasoning; without
+    /// reasoning, tools following `hasChild`, such as [`crate::stats`], do not see them. Text
+    /// nodes and embedded JSON members keep `hasChild`. Under `owl_safe` each predicate is
+    /// declared an `owl:ObjectProperty`.
+    pub child_predicates: bool,
+    /// Strip leading and trailing whitespace from element text and attribute values. Defaults
+    /// to `true`.
+    pub trim_text: bool,
+    /// Emit `hasValue` for text and attribute values that consist only of whitespace. Defaults
+    /// to `false`, under which a whitespace-only attribute value counts as empty: the attribute
+    /// gets no value, and a [`WarningKind::EmptyAttribute`] warning.
+    ///
+    /// This is independent of `trim_text`: with both enabled a whitespace-only element or
+    /// attribute gets an empty `hasValue` literal. Note that indentation between child elements
+    /// is whitespace-only text too.
+    pub emit_whitespace_only: bool,
+    /// How element and attribute classes are keyed.
+    pub class_key: ClassKey,
+    /// Type each element, attribute and JSON node with its class via `rdf:type`. Defaults to
+    /// `true`. Domain classes from [`ConversionOptions::class_map`] are written either way.
+    pub type_triples: bool,
+    /// Emit the `rdfs:subClassOf` triples placing nodes in the model hierarchy: each element
+    /// node `rdfs:subClassOf` `XmlNode`, and each attribute and JSON node class `XmlAttribute`
+    /// or `JsonValue`. Defaults to `true`.
+    pub class_hierarchy: bool,
+    /// Delimiter between the steps of element paths in class IRIs, e.g. `/` for
+    /// `x2r:people/person` and `x2r:people/person/-id`. Defaults to `.`.
+    ///
+    /// Only the class IRIs change: keys of [`ConversionOptions::class_map`],
+    /// [`ConversionOptions::binary`], [`ConversionOptions::namespace_overrides`] and
+    /// [`ConversionOptions::conditions`] are still dotted paths.
+    pub path_delimiter: String,
+    /// Emit the model ontology (see [`crate::vocab::ontology`]) once, before any converted data.
+    pub embed_ontology: bool,
+    /// Keep the output within OWL DL, so reasoners accept it. Defaults to `false`.
+    ///
+    /// Element classes, rather than element nodes, are placed `rdfs:subClassOf` `XmlNode`, so no
+    /// node is both an individual and a class. Every class is declared an `owl:Class`, and every
+    /// predicate an `owl:ObjectProperty` or `owl:DatatypeProperty` (see
+    /// [`crate::vocab::owl_declarations`]), including the predicates of promoted attributes and
+    /// flag elements. Cannot be combined with [`MultiValuePolicy::List`] or
+    /// [`BranchTextPolicy::Mixed`], which link values and content to `rdf:List`s.
+    pub owl_safe: bool,
+    /// Emit an `XmlDocument` node for each converted document, linked to its root element via
+    /// `hasRoot` and, for documents read from a file, to their source via `sourceFile`.
+    pub document_node: bool,
+    /// How the document node is identified: minted, named after its source file, or given.
+    /// Defaults to [`DocumentIri::Minted`].
+    ///
+    /// Named document nodes stay the same across conversions, so they can be referred to. Fails
+    /// with [`ConvertError::InvalidDocumentIri`] for a document without a source file under
+    /// [`DocumentIri::FileName`], and for a document whose IRI another document of the same
+    /// conversion already has, such as two files of the same name in different directories or
+    /// a second document under [`DocumentIri::Explicit`]. Has no effect unless `document_node`
+    /// is set.
+    pub document_iri: DocumentIri,
+    /// Node linking the root element of every converted document, so the documents merged into
+    /// one graph form a single tree below it. Not typed or described otherwise.
+    ///
+    /// The link uses [`ConversionOptions::corpus_predicate`], and with
+    /// [`InverseLinks::Materialized`] the root also gets `hasParent` back to the corpus node.
+    pub corpus_node: Option<NamedNode>,
+    /// Predicate linking the corpus node to root elements. Defaults to `hasChild`. Has no
+    /// effect unless `corpus_node` is set.
+    pub corpus_predicate: Option<NamedNode>,
+    /// Describe the source of each document node with PROV-O instead of the custom model terms:
+    /// `prov:wasDerivedFrom` pointing at the IRI of the source file, see
+    /// [`ConversionOptions::source_iris`]. Has no effect unless `document_node` is set.
+    pub prov: bool,
+    /// How source files are identified by IRI in provenance, `sourceFile` literals and the
+    /// names of [`DocumentIdentity::Source`] document graphs: by `file://` IRI, which reveals
+    /// the local directory layout, under a base IRI, or opaquely. See [`SourceIris`].
+    pub source_iris: SourceIris,
+    /// Record when each document was converted on its document node, as an `xsd:dateTime` with
+    /// `convertedAt`, or with `prov:generatedAtTime` if [`ConversionOptions::prov`] is set. All
+    /// documents of one conversion share the time. Omitted by default, see [`ConversionTime`].
+    /// Has no effect unless `document_node` is set.
+    pub conversion_time: ConversionTime,
+    /// Link each document node with `convertedBy` to a `ConversionRun` node recording how the
+    /// conversion was run: the converter's `toolVersion`, the `dataNamespace`, the `nodeIds`
+    /// strategy, the `nodeIdHash` of deterministic ones and the `classKey` by their command line
+    /// names, and an `enabledFeature` per optional feature the converter was built with.
+    ///
+    /// All documents of a conversion share one run node, written once they are converted and
+    /// outside any document graph. Under [`NodeIds::Deterministic`] its IRI is derived from
+    /// this description, so only runs recorded alike share it. Off by default, as the version
+    /// changes the output of otherwise identical conversions. Has no effect unless
+    /// `document_node` is set.
+    pub record_options: bool,
+    /// Link every element, attribute and attribute bag node to its document node with
+    /// `inDocument`, so data converted from many files into one graph can be partitioned by
+    /// source. Costs one triple per node. Has no effect unless `document_node` is set.
+    pub link_document: bool,
+    /// Record a digest of the bytes each document was converted from on its document node, as
+    /// lowercase hex with `x2r:sha256` or `x2r:md5`. The input is hashed as it is read, and read
+    /// to its end if the conversion stops early, so the digest of a file always covers all of
+    /// it. For zip archives it covers each decompressed entry. Has no effect unless
+    /// `document_node` is set.
+    #[cfg(feature = "digest")]
+    pub digest: Option<DigestAlgorithm>,
+    /// Make the inverse structural links available, see [`InverseLinks`]. Off by default.
+    pub inverse_links: Option<InverseLinks>,
+    /// Data namespaces overriding the one given to the converter for whole subtrees, e.g. to mint
+    /// everything under `<external>` in a partner namespace.
+    ///
+    /// Keys are matched like those of [`ConversionOptions::class_map`]; a path key takes
+    /// precedence over a name key matching the same element. A matching element, its attribute
+    /// and attribute bag nodes, and all its descendants are minted under the override, until a
+    /// descendant matches an override of its own. Document nodes always use the namespace given
+    /// to the converter. Overrides are rejected like that namespace if they overlap the model
+    /// namespace.
+    pub namespace_overrides: HashMap<String, String>,
+    /// Key element and attribute classes on namespace-qualified names, so equally named elements
+    /// from different namespaces get different classes.
+    ///
+    /// An element in a namespace, including a default namespace declared with `xmlns="..."`, is
+    /// keyed as `{namespace}local` (percent-encoded in the class IRI). Prefixed attributes are
+    /// keyed the same way on their namespace URI, whatever the prefix, so `xlink:href` and
+    /// `html:href` on one element get distinct classes. Following XML rules, unprefixed
+    /// attributes are in no namespace, never the default one, and keep their plain local name.
+    pub preserve_namespaces: bool,
+    /// Named graph receiving the metadata needed to reconstruct the original document order:
+    /// `siblingIndex` of each element among its parent's child elements and `attributeIndex` of
+    /// each attribute. The default graph keeps only the document content. Requires a writer that
+    /// supports named graphs, see [`RdfWriter::add_quad`].
+    pub reconstruction_graph: Option<NamedNode>,
+    /// Write the triples of each document to a named graph identified by the document, instead
+    /// of the default graph. Triples shared by all documents, such as the ontology and sample
+    /// counts, stay in the default graph. So does the document node of a document without
+    /// elements under [`DocumentIdentity::RootAttribute`].
+    ///
+    /// Re-converting a changed document then yields the same graph name, so a writer replacing
+    /// graphs it writes to (see [`crate::writer::DatasetWriter::replacing_graphs`]) keeps only
+    /// its latest version. Fails with [`ConvertError::MissingIdentity`] for a document that
+    /// cannot be identified. Requires a writer that supports named graphs.
+    pub document_graphs: Option<DocumentIdentity>,
+    /// Materialize namespace declarations (`xmlns="..."`, `xmlns:prefix="..."`) as a
+    /// `declaresNamespace` blank node on the declaring element, carrying the `namespacePrefix`
+    /// (empty for the default namespace) and `namespaceUri`. Independent of
+    /// `preserve_namespaces`.
+    pub emit_namespace_decls: bool,
+    /// How element, attribute, attribute bag and document nodes are identified.
+    pub node_ids: NodeIds,
+    /// Fail the conversion with [`ConvertError::InputTooLarge`] before more than this many bytes
+    /// of input reach the parser, so a runaway or malicious stream cannot exhaust memory or
+    /// disk. The limit covers all documents of a conversion together; for zip archives it
+    /// counts decompressed bytes. Triples written before the limit was hit are complete.
+    pub max_input_bytes: Option<u64>,
+    /// Stop converting once this many triples have been written. The output stays valid, and
+    /// [`ConversionSummary::truncated`] reports whether anything was left out.
+    pub max_triples: Option<usize>,
+    /// Stop converting each document once this many of its elements have been converted,
+    /// closing the open elements and moving on to the next document. Elements skipped by
+    /// [`ConversionOptions::conditions`] or `sample_per_path` do not count.
+    pub max_elements: Option<usize>,
+    /// Type element text and attribute values with the XML Schema datatype their lexical form
+    /// matches, e.g. `xsd:integer` for `5`, instead of emitting plain strings. See
+    /// [`crate::datatype::infer`] for the recognized forms.
+    pub infer_datatypes: bool,
+    /// Predicates used for element text and attribute values. With `embed_ontology`, predicates
+    /// other than `hasValue` are declared `rdfs:subPropertyOf hasValue` once, before any
+    /// converted data.
+    pub vocabulary: ModelVocabulary,
+    /// Represent leaf elements that have both attributes and text with a single `rdf:JSON`
+    /// literal instead of attribute nodes and a text literal.
+    ///
+    /// The literal is linked to the element with the text predicate of
+    /// [`ConversionOptions::vocabulary`] and holds a JSON object without insignificant
+    /// whitespace: first an `"@value"` member with the element text, then one member per
+    /// attribute in document order, keyed by the attribute's qualified name as written (`code`
+    /// or `x:code`). All values are JSON strings. The text fragments are concatenated regardless
+    /// of [`ConversionOptions::multi_value_policy`], text and attribute values are trimmed
+    /// according to `trim_text`, empty attribute values are left out as for attribute nodes,
+    /// and no datatypes are inferred. For example `<price currency="USD">9.99</price>` becomes
+    /// `{"@value":"9.99","currency":"USD"}`.
+    ///
+    /// Elements with child elements, without non-empty attributes or without text are converted
+    /// as usual.
+    pub json_leaves: bool,
+    /// Emit each run of text between the tags of an element as a `TextNode`, linked from the
+    /// element with `hasChild` and holding the text with `hasValue`, instead of `hasValue` on the
+    /// element itself, as in the DOM.
+    ///
+    /// The values of text nodes are written like element values, and whitespace-only runs are
+    /// skipped unless `emit_whitespace_only` is set. Text nodes count among the child nodes
+    /// whose order [`ConversionOptions::reconstruction_graph`] records with `siblingIndex`, so
+    /// the text of mixed content can be put back between the child elements. Text converted by
+    /// `json_leaves` or `embedded_json` gets no text nodes.
+    pub text_as_node: bool,
+    /// Domain classes for elements, typed with `rdf:type` in addition to their structural class.
+    ///
+    /// Keys are dotted element paths from the document root (`people.person`) or element names
+    /// (`person`), written as in class IRIs (see [`ClassKey`] and `preserve_namespaces`). An
+    /// element matching both a path key and a name key gets both classes.
+    pub class_map: HashMap<String, NamedNode>,
+    /// Attributes written as the value of their element instead of as attribute nodes, e.g.
+    /// `id` for `<ref id="x"/>`, which then gets `hasValue "x"`.
+    ///
+    /// Keys are matched like those of [`ConversionOptions::class_map`]. The value is typed like
+    /// that of the attribute would be, and linked with the predicate for element text unless
+    /// the [`PromotedAttribute`] names another. Element text takes precedence: an element with
+    /// text keeps it as its value, and its attribute is converted to an attribute node after
+    /// all, unless promoted with its own predicate. The other attributes of such elements are
+    /// written once the element ends.
+    pub promoted_attributes: HashMap<String, PromotedAttribute>,
+    /// Flag elements, whose presence means `true`, mapped to the predicate linking their parent
+    /// element to an `xsd:boolean` `true`, so `<person><premium/></person>` with `premium`
+    /// mapped to `ex:premium` adds `person ex:premium true`.
+    ///
+    /// Keys are matched like those of [`ConversionOptions::class_map`]. Only empty elements get
+    /// the flag, i.e. elements without child elements or text, as for `isEmpty` in
+    /// [`ConversionOptions::empty_markers`]; an element declared nil with `xsi:nil="true"` does
+    /// not. The element itself is converted as usual, with its attributes and, if enabled, its
+    /// `isEmpty` marker. A root element has no parent to flag.
+    pub flag_elements: HashMap<String, NamedNode>,
+    /// Elements and attributes holding binary content, typed `xsd:base64Binary` or
+    /// `xsd:hexBinary` instead of plain or inferred literals.
+    ///
+    /// Keys are matched like those of [`ConversionOptions::class_map`]; attributes are keyed as
+    /// `element.-attribute`, by path (`doc.signature.-digest`) or element name
+    /// (`signature.-digest`). Content that is not valid in the encoding is handled according to
+    /// `invalid_binary`.
+    pub binary: HashMap<String, BinaryEncoding>,
+    /// How content that is not valid in its `binary` encoding is handled, see [`BinaryPolicy`].
+    pub invalid_binary: BinaryPolicy,
+    /// Datatype hints for the values of elements and attributes, e.g. `xsd:gYear` for a `year`
+    /// element whose values would otherwise be inferred as `xsd:integer`.
+    ///
+    /// Keys are matched like those of [`ConversionOptions::binary`], which takes precedence. A
+    /// value gets its hinted datatype if it is valid for it according to
+    /// [`crate::datatype::is_valid`], and is typed as without a hint otherwise; values hinted
+    /// with a datatype that is not checked always get it. Hints apply whether or not
+    /// `infer_datatypes` is set.
+    pub datatypes: HashMap<String, NamedNode>,
+    /// Elements whose text may be JSON, converted as their [`EmbeddedJson`] mode says.
+    ///
+    /// Keys are matched like those of [`ConversionOptions::class_map`]. Only the listed
+    /// elements are considered, so arbitrary text is never mistaken for JSON. The text
+    /// fragments of a listed element are concatenated regardless of
+    /// [`ConversionOptions::multi_value_policy`]; if the result is a JSON object or array,
+    /// surrounded by optional whitespace, it replaces the element's usual text value. Other text,
+    /// including JSON scalars such as `5`, is converted as usual. The element's attributes are
+    /// converted as usual either way.
+    pub embedded_json: HashMap<String, EmbeddedJson>,
+    /// How names with characters not allowed in IRIs are encoded in class IRIs and paths.
+    /// Defaults to [`NameEncoding::Percent`].
+    ///
+    /// Applies to the member keys of [`ConversionOptions::embedded_json`], which may hold any
+    /// character. Element and attribute names follow the XML name rules, which keep them
+    /// within the characters IRIs allow, and are used as written. `hasName` holds names as
+    /// written whatever the encoding.
+    pub name_encoding: NameEncoding,
+    /// Emit the byte span of each element in its source as `startOffset` and `endOffset`
+    /// `xsd:integer` literals.
+    ///
+    /// Offsets count UTF-8 bytes from the start of the input, including any byte order mark.
+    /// The span is half-open: `startOffset` is the offset of the `<` opening the start tag and
+    /// `endOffset` the offset just past the `>` closing the end tag (or the `/>` of an empty
+    /// element tag), so `&source[start..end]` is exactly the element.
+    pub source_offsets: bool,
+    /// How the text of elements that also have child elements is converted, see
+    /// [`BranchTextPolicy`]. Child elements skipped by [`ConversionOptions::conditions`] or
+    /// `sample_per_path` do not count.
+    pub branch_text: BranchTextPolicy,
+    /// Emit each element's attributes sorted by namespace URI and local name instead of in the
+    /// order the parser reports them, for output that is stable regardless of parser internals.
+    /// Prefixes are ignored, as they can differ between documents. `attributeIndex` still
+    /// records the source order.
+    pub sort_attributes: bool,
+    /// Attribute conditions gating which subtrees are converted, see [`AttributeCondition`].
+    ///
+    /// An element is skipped, together with its whole subtree, if any condition naming it does
+    /// not hold. Elements no condition names are converted as usual, so conditions on different
+    /// elements combine with AND and select nested subtrees independently.
+    pub conditions: Vec<AttributeCondition>,
+    /// How invalid UTF-8 in the input is handled, see [`Utf8Policy`].
+    ///
+    /// With [`Utf8Policy::Lossy`], the offsets of `source_offsets` count each replaced sequence
+    /// as the three bytes of U+FFFD, so they drift from the source after the first replacement.
+    pub invalid_utf8: Utf8Policy,
+    /// Fold wrapped scalars into their wrapper, e.g. `<name><value>Alice</value></name>` into
+    /// `name hasValue "Alice"`, so verbose XML produces fewer nodes.
+    ///
+    /// An element collapses if it has exactly one child element and no text of its own other
+    /// than whitespace, and that child has no attributes, no child elements and some
+    /// non-whitespace text. The child then gets no node; its text becomes the element's text,
+    /// converted as if it had been written directly inside the element. The element keeps its
+    /// own attributes. A collapsed element is still a node, so it never collapses into its own
+    /// parent in turn.
+    pub collapse_wrappers: bool,
+    /// Convert only the first element of each distinct path, for exploring the structure of
+    /// large repetitive documents.
+    ///
+    /// Paths are compared across all converted documents. Any later element with a path seen
+    /// before is skipped together with its attributes and its whole subtree, so structures that
+    /// only occur below later elements are not sampled, and a document whose root element path
+    /// was sampled already contributes no triples. The sample keeps all of its attributes.
+    /// Elements skipped because of [`ConversionOptions::conditions`] do not count.
+    pub sample_per_path: bool,
+    /// Record on each sampled element the number of elements sharing its path, the sample
+    /// included, as an `occurrences` `xsd:integer` literal. The counts are written once all
+    /// documents have been converted. Elements inside skipped subtrees are not visited, so
+    /// repeated elements nested in a repeated element are only counted below the sample of the
+    /// outer one. Has no effect unless `sample_per_path` is set.
+    pub sample_counts: bool,
+    /// Record on each element class the number of elements typed with it, as an
+    /// `instanceCount` `xsd:integer` literal, so the size of a class is known without counting
+    /// its instances.
+    ///
+    /// The counts are written once all documents have been converted, outside any document
+    /// graph, and span all documents. Elements skipped by [`ConversionOptions::conditions`] or
+    /// `sample_per_path` do not count. Requires `type_triples`.
+    pub instance_counts: bool,
+    /// Tell elements whose value is known to be absent from elements that merely have no
+    /// content: an element with `xsi:nil="true"` gets `isNil true`, any other element without
+    /// child elements or text gets `isEmpty true`, both `xsd:boolean`.
+    ///
+    /// Whitespace-only text counts as no text unless it is kept with `emit_whitespace_only`, and
+    /// child elements skipped by [`ConversionOptions::conditions`] or `sample_per_path` do not
+    /// count as content. The `xsi:nil` attribute itself is converted like any other.
+    pub empty_markers: bool,
+    /// Type each element as a `LeafElement` if it has no child elements, or a `BranchElement`
+    /// if it has, in addition to its other types. The type is written once the element ends.
+    ///
+    /// Child elements skipped by [`ConversionOptions::conditions`] or `sample_per_path` do not
+    /// count, nor do the nodes expanded from [`ConversionOptions::embedded_json`], which are not
+    /// elements.
+    pub element_kinds: bool,
+    /// Collect problems that do not stop the conversion, such as attributes with empty values,
+    /// into [`ConversionSummary::warnings`], see [`Warning`]. Off by default, as a large
+    /// document can produce many of them.
+    pub collect_warnings: bool,
+    /// Emit the qualified name of each element as written with `hasQName`, e.g. `dc:title`, in
+    /// addition to its local name. Elements without a prefix get their local name. Independent
+    /// of `preserve_namespaces`.
+    pub emit_qnames: bool,
+    /// Emit only triples with these predicates, dropping all others, e.g. `hasValue` and
+    /// `hasName` for the names and values without the document structure. `None`, the default,
+    /// emits every predicate. Dropped triples do not count towards
+    /// [`ConversionOptions::max_triples`].
+    pub only_predicates: Option<Vec<NamedNode>>,
+    /// Annotate the value triples of element text and attribute values with RDF-star: each
+    /// quoted value triple gets the `sourceLine` and `sourceColumn` of its element's start tag,
+    /// `xsd:integer`s starting at 1, and, if its literal is typed, `typedBy` saying whether the
+    /// datatype came from a binary `encoding`, a datatype `hint` or `inference`. Values in
+    /// `rdf:List`s, JSON and mixed content are not annotated.
+    ///
+    /// The output is N-Triples-star, which only RDF-star aware parsers read.
+    #[cfg(feature = "rdf-star")]
+    pub value_annotations: bool,
+}
+
+/// A condition on an element's own attributes, written `element@attribute=value`, e.g.
+/// `order@status=paid`.
+///
+/// `element` is a dotted element path from the document root (`orders.order`) or an element
+/// name (`order`), matched like the keys of [`ConversionOptions::class_map`]. `attribute` is the
+/// attribute's qualified name as written (`status` or `x:status`). The condition holds if the
+/// element has that attribute with exactly `value`; it is evaluated when the element starts, so
+/// text and descendants play no part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributeCondition {
+    /// Element path or name the condition applies to.
+    pub element: String,
+    /// Qualified name of the attribute to test.
+    pub attribute: String,
+    /// Value the attribute must have.
+    pub value: String,
+}
+
+impl AttributeCondition {
+    /// Returns whether the condition applies to the element with the given `path` (without the
+    /// model namespace) and `name`.
+    fn applies_to(&self, path: &str, name: &str) -> bool {
+        self.element == path || self.element == name
+    }
+
+    /// Returns whether `attributes` satisfy the condition.
+    fn holds(&self, attributes: &[OwnedAttribute]) -> bool {
+        attributes
+            .iter()
+            .any(|attr| attr.value == self.value && attr.name.borrow().to_repr() == self.attribute)
+    }
+}
+
+impl std::str::FromStr for AttributeCondition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parsed = s
+            .split_once('@')
+            .and_then(|(element, rest)| Some((element, rest.split_once('=')?)));
+        match parsed {
+            Some((element, (attribute, value))) if !element.is_empty() && !attribute.is_empty() => {
+                Ok(Self {
+                    element: element.to_string(),
+                    attribute: attribute.to_string(),
+                    value: value.to_string(),
+                })
+            }
+            _ => Err(format!("expected element@attribute=value, got '{s}'")),
+        }
+    }
+}
+
+impl Default for ConversionOptions {
+    fn default() -> Self {
+        Self {
+            multi_value_policy: MultiValuePolicy::default(),
+            split_values: HashMap::new(),
+            attribute_bag: false,
+            property_style: false,
+            child_predicates: false,
+            trim_text: true,
+            emit_whitespace_only: false,
+            class_key: ClassKey::default(),
+            type_triples: true,
+            class_hierarchy: true,
+            path_delimiter: ".".to_string(),
+            embed_ontology: false,
+            owl_safe: false,
+            document_node: false,
+            document_iri: DocumentIri::Minted,
+            corpus_node: None,
+            corpus_predicate: None,
+            prov: false,
+            conversion_time: ConversionTime::Omit,
+            record_options: false,
+            source_iris: SourceIris::File,
+            link_document: false,
+            #[cfg(feature = "digest")]
+            digest: None,
+            inverse_links: None,
+            namespace_overrides: HashMap::new(),
+            preserve_namespaces: false,
+            reconstruction_graph: None,
+            document_graphs: None,
+            emit_namespace_decls: false,
+            node_ids: NodeIds::default(),
+            max_input_bytes: None,
+            max_triples: None,
+            max_elements: None,
+            infer_datatypes: false,
+            vocabulary: ModelVocabulary::default(),
+            json_leaves: false,
+            text_as_node: false,
+            class_map: HashMap::new(),
+            promoted_attributes: HashMap::new(),
+            flag_elements: HashMap::new(),
+            binary: HashMap::new(),
+            invalid_binary: BinaryPolicy::default(),
+            datatypes: HashMap::new(),
+            embedded_json: HashMap::new(),
+            name_encoding: NameEncoding::Percent,
+            source_offsets: false,
+            branch_text: BranchTextPolicy::default(),
+            sort_attributes: false,
+            conditions: Vec::new(),
+            invalid_utf8: Utf8Policy::default(),
+            collapse_wrappers: false,
+            sample_per_path: false,
+            sample_counts: false,
+            instance_counts: false,
+            empty_markers: false,
+            element_kinds: false,
+            collect_warnings: false,
+            emit_qnames: false,
+            only_predicates: None,
+            #[cfg(feature = "rdf-star")]
+            value_annotations: false,
+        }
+    }
 }
 
-const X2R: &str = "https://decisym.ai/xml2rdf/model#";
+impl ConversionOptions {
+    /// Checks the options for contradictions before converting anything, returning every one
+    /// found, in a stable order:
+    ///
+    /// - `prov`, `link_document`, `conversion_time`, `record_options`, `digest` or a
+    ///   `document_iri` other than [`DocumentIri::Minted`] are set without `document_node`,
+    ///   `corpus_predicate` without `corpus_node`, `source_iris` is not [`SourceIris::File`]
+    ///   without `prov` or [`DocumentIdentity::Source`] document graphs, `sample_counts` without
+    ///   `sample_per_path`, or `instance_counts` without `type_triples`
+    ///   ([`ConfigError::Requires`]).
+    /// - A `namespace_overrides` namespace overlaps the model namespace
+    ///   ([`ConfigError::ReservedNamespace`]).
+    /// - A key is in both `binary` and `datatypes` ([`ConfigError::BinaryAndDatatype`]).
+    /// - Two `conditions` on the same element require different values of the same attribute
+    ///   ([`ConfigError::ContradictoryConditions`]).
+    /// - `path_delimiter` or a `split_values` delimiter is empty, a fixed `conversion_time` is
+    ///   not an `xsd:dateTime`, a promoted attribute has no name, or `owl_safe` is set with
+    ///   [`MultiValuePolicy::List`] or [`BranchTextPolicy::Mixed`]
+    ///   ([`ConfigError::InvalidValue`]).
+    ///
+    /// ```
+    /// use xml2rdf::convert::ConversionOptions;
+    /// use xml2rdf::error::ConfigError;
+    ///
+    /// let options = ConversionOptions {
+    ///     prov: true,
+    ///     ..Default::default()
+    /// };
+    /// let expected = ConfigError::Requires {
+    ///     option: "prov",
+    ///     requires: "document_node",
+    /// };
+    /// assert_eq!(options.validate(), Err(vec![expected]));
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<ConfigError>> {
+        let mut errors = Vec::new();
+        let mut requires = |set: bool, option, requires| {
+            if set {
+                errors.push(ConfigError::Requires { option, requires });
+            }
+        };
+        let no_document = !self.document_node;
+        requires(self.prov && no_document, "prov", "document_node");
+        requires(
+            self.link_document && no_document,
+            "link_document",
+            "document_node",
+        );
+        requires(
+            self.document_iri != DocumentIri::Minted && no_document,
+            "document_iri",
+            "document_node",
+        );
+        requires(
+            self.corpus_predicate.is_some() && self.corpus_node.is_none(),
+            "corpus_predicate",
+            "corpus_node",
+        );
+        requires(
+            self.conversion_time != ConversionTime::Omit && no_document,
+            "conversion_time",
+            "document_node",
+        );
+        requires(
+            self.record_options && no_document,
+            "record_options",
+            "document_node",
+        );
+        #[cfg(feature = "digest")]
+        requires(
+            self.digest.is_some() && no_document,
+            "digest",
+            "document_node",
+        );
+        requires(
+            self.source_iris != SourceIris::File
+                && !self.prov
+                && self.document_graphs != Some(DocumentIdentity::Source),
+            "source_iris",
+            "prov",
+        );
+        requires(
+            self.sample_counts && !self.sample_per_path,
+            "sample_counts",
+            "sample_per_path",
+        );
+        requires(
+            self.instance_counts && !self.type_triples,
+            "instance_counts",
+            "type_triples",
+        );
+
+        let mut overrides: Vec<_> = self.namespace_overrides.iter().collect();
+        overrides.sort();
+        for (key, namespace) in overrides {
+            if overlaps_model(namespace) {
+                errors.push(ConfigError::ReservedNamespace {
+                    key: key.clone(),
+                    namespace: namespace.clone(),
+                });
+            }
+        }
+
+        let mut binary: Vec<&String> = self.binary.keys().collect();
+        binary.sort();
+        for key in binary {
+            if self.datatypes.contains_key(key) {
+                errors.push(ConfigError::BinaryAndDatatype { key: key.clone() });
+            }
+        }
+
+        let mut contradictory = BTreeSet::new();
+        for (i, condition) in self.conditions.iter().enumerate() {
+            for other in &self.conditions[i + 1..] {
+                if other.element == condition.element
+                    && other.attribute == condition.attribute
+                    && other.value != condition.value
+                {
+                    contradictory.insert((&condition.element, &condition.attribute));
+                }
+            }
+        }
+        for (element, attribute) in contradictory {
+            errors.push(ConfigError::ContradictoryConditions {
+                element: element.clone(),
+                attribute: attribute.clone(),
+            });
+        }
+
+        if self.path_delimiter.is_empty() {
+            errors.push(ConfigError::InvalidValue {
+                option: "path_delimiter",
+                message: "must not be empty, or different paths could share a class".to_string(),
+            });
+        }
+        if self.owl_safe && self.multi_value_policy == MultiValuePolicy::List {
+            errors.push(ConfigError::InvalidValue {
+                option: "multi_value_policy",
+                message: "list values would make the value predicates object properties, which \
+                          owl_safe declares datatype properties"
+                    .to_string(),
+            });
+        }
+        if self.owl_safe && self.branch_text == BranchTextPolicy::Mixed {
+            errors.push(ConfigError::InvalidValue {
+                option: "branch_text",
+                message: "mixed content is linked as an rdf:List, which owl_safe does not declare"
+                    .to_string(),
+            });
+        }
+        let mut split: Vec<_> = self.split_values.iter().collect();
+        split.sort();
+        for (key, _) in split.into_iter().filter(|(_, d)| d.is_empty()) {
+            errors.push(ConfigError::InvalidValue {
+                option: "split_values",
+                message: format!("empty delimiter given for '{key}'"),
+            });
+        }
+        if let ConversionTime::Fixed(time) = &self.conversion_time {
+            if datatype::is_valid(time, xsd::DATE_TIME) != Some(true) {
+                errors.push(ConfigError::InvalidValue {
+                    option: "conversion_time",
+                    message: format!("'{time}' is not an xsd:dateTime"),
+                });
+            }
+        }
+        let mut promoted: Vec<_> = self.promoted_attributes.iter().collect();
+        promoted.sort_by_key(|(key, _)| *key);
+        for (key, promotion) in promoted {
+            if promotion.attribute.is_empty() {
+                errors.push(ConfigError::InvalidValue {
+                    option: "promoted_attributes",
+                    message: format!("no attribute name given for '{key}'"),
+                });
+            }
+        }
 
-const XML_ELEMENT: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "XmlNode"));
-const XML_ATTRIBUTE: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "XmlAttribute"));
-const HAS_CHILD: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "hasChild"));
-const HAS_ATTRIBUTE: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "hasAttribute"));
-const HAS_NAME: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "hasName"));
-const HAS_VALUE: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "hasValue"));
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
 
 /// Converts XML data to RDF format.
 ///
@@ -49,6 +1115,11 @@ const HAS_VALUE: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "
 /// - `namespace`: Optional custom namespace for RDF predicates.
 /// - `output`: use RdfWriter trait to add generated triples to desired format (File or Graph)
 ///
+/// Files that are empty or contain no elements (only a declaration, comments or processing
+/// instructions) are not an error: they produce no triples, or only their document node when
+/// [`ConversionOptions::document_node`] is set, and are listed in
+/// [`ConversionSummary::empty_documents`].
+///
 /// # Example
 /// ```rust
 /// use xml2rdf::convert::parse_xml;
@@ -66,123 +1137,3120 @@ pub fn parse_xml(
     files: Vec<String>,
     output: &mut dyn RdfWriter,
     namespace: &str,
-) -> std::io::Result<()> {
+) -> Result<ConversionSummary, ConvertError> {
+    parse_xml_with_options(files, output, namespace, &ConversionOptions::default())
+}
+
+/// Converts XML data to RDF format using the provided [`ConversionOptions`].
+///
+/// Behaves like [`parse_xml`], with `options` controlling how the RDF output is shaped.
+pub fn parse_xml_with_options(
+    files: Vec<String>,
+    output: &mut dyn RdfWriter,
+    namespace: &str,
+    options: &ConversionOptions,
+) -> Result<ConversionSummary, ConvertError> {
+    let mut converter = Converter::new(output, namespace, options)?;
     for file in files.into_iter() {
-        let file = std::fs::File::open(file)?;
-        let file_reader = std::io::BufReader::new(file);
-        let parser = EventReader::new(file_reader);
-
-        let mut stack: Vec<Node> = Vec::new();
-        let mut subject: Option<Node> = None;
-
-        for e in parser {
-            match e {
-                Ok(XmlEvent::StartElement {
-                    name, attributes, ..
-                }) => {
-                    // Define the subject as the IRI of the element
-                    let id = Uuid::new_v4().hyphenated().to_string();
-                    let path = if let Some(parent) = stack.last_mut() {
-                        format!("{}.{}", parent.path, name.local_name)
-                    } else {
-                        format!("{X2R}{}", name.local_name)
-                    };
-                    subject = Some(Node {
-                        id: NamedNode::new(format!("{}/{}", namespace, id).as_str()).unwrap(),
-                        path,
-                    });
+        if converter.output.truncated {
+            break;
+        }
+        #[cfg(not(feature = "zip"))]
+        if has_extension(&file, "zip") {
+            return Err(ConvertError::Open {
+                path: file,
+                error: std::io::Error::new(
+                    std::io::ErrorKind::Unsupported,
+                    "built without zip support, enable the zip feature to convert archives",
+                ),
+            });
+        }
+        let reader = std::fs::File::open(&file).map_err(|error| ConvertError::Open {
+            path: file.clone(),
+            error,
+        })?;
+        #[cfg(feature = "zip")]
+        if has_extension(&file, "zip") {
+            converter.convert_archive(reader, Some(&file))?;
+            continue;
+        }
+        converter.source = Some(Source {
+            path: file,
+            entry: None,
+        });
+        converter.convert(reader)?;
+    }
 
-                    if let Some(ref s) = subject {
-                        if let Some(parent) = stack.last_mut() {
-                            output.add_triple(TripleRef::new(
-                                parent.id.as_ref(),
-                                HAS_CHILD,
-                                s.id.as_ref(),
-                            ))?;
-                        }
-                        let object = NamedNode::new(&s.path).unwrap();
-                        output.add_triple(TripleRef::new(s.id.as_ref(), TYPE, object.as_ref()))?;
-
-                        let object = Literal::new_simple_literal(name.local_name.clone());
-                        output.add_triple(TripleRef::new(
-                            s.id.as_ref(),
-                            HAS_NAME,
-                            TermRef::Literal(object.as_ref()),
-                        ))?;
+    converter.finish()
+}
 
-                        output.add_triple(TripleRef::new(
-                            s.id.as_ref(),
-                            SUB_CLASS_OF,
-                            XML_ELEMENT,
-                        ))?;
+/// Converts XML read from `reader` to RDF format using the provided [`ConversionOptions`].
+///
+/// This is the building block of [`parse_xml_with_options`] for callers whose XML does not live
+/// in a file, such as in-memory documents or network streams.
+///
+/// # Example
+/// ```rust
+/// use xml2rdf::convert::{parse_xml_reader, ConversionOptions};
+/// use xml2rdf::writer::GraphWriter;
+/// use oxrdf::Graph;
+///
+/// let xml = "<note><to>Tove</to></note>";
+/// let mut g = Graph::new();
+/// let mut w = GraphWriter::new(&mut g);
+/// parse_xml_reader(
+///     xml.as_bytes(),
+///     &mut w,
+///     "https://decisym.ai/xml2rdf/data",
+///     &ConversionOptions::default(),
+/// )
+/// .unwrap();
+/// assert_eq!(g.len(), 8);
+/// ```
+pub fn parse_xml_reader<R: std::io::Read>(
+    reader: R,
+    output: &mut dyn RdfWriter,
+    namespace: &str,
+    options: &ConversionOptions,
+) -> Result<ConversionSummary, ConvertError> {
+    let mut converter = Converter::new(output, namespace, options)?;
+    converter.convert(reader)?;
+    converter.finish()
+}
 
-                        stack.push(s.clone());
-                    }
+/// Infers a data namespace from the XML read from `reader`: the namespace URI of its root
+/// element, without a trailing `/` or `#`, as node IRIs are minted under it with a `/`.
+///
+/// ```
+/// use xml2rdf::convert::infer_namespace;
+///
+/// let xml = r#"<catalog xmlns="https://example.com/books/"><book/></catalog>"#;
+/// assert_eq!(
+///     infer_namespace(xml.as_bytes()).as_deref(),
+///     Some("https://example.com/books")
+/// );
+/// assert_eq!(infer_namespace("<catalog/>".as_bytes()), None);
+/// ```
+///
+/// Returns `None` if the root element has no namespace, if the namespace is not a valid IRI or
+/// overlaps the model namespace, and if no root element can be read. Only the input up to the
+/// root start tag is read.
+pub fn infer_namespace(reader: impl std::io::Read) -> Option<String> {
+    let mut parser = EventReader::new(std::io::BufReader::new(reader));
+    let namespace = loop {
+        match parser.next().ok()? {
+            XmlEvent::StartElement { name, .. } => break name.namespace?,
+            XmlEvent::EndDocument => return None,
+            _ => {}
+        }
+    };
+    let namespace = namespace.trim_end_matches(['/', '#']);
+    if overlaps_model(namespace) || NamedNode::new(format!("{namespace}/node")).is_err() {
+        return None;
+    }
+    Some(namespace.to_string())
+}
 
-                    // Write triples for each attribute of the element
-                    for attr in attributes {
-                        if let Some(ref s) = subject {
-                            let attrib_id = Uuid::new_v4().hyphenated().to_string();
-                            let path = format!("{}.-{}", s.path, attr.name.local_name);
-
-                            let attr_subject =
-                                NamedNode::new(format!("{}/{}", namespace, attrib_id)).unwrap();
-
-                            output.add_triple(TripleRef::new(
-                                s.id.as_ref(),
-                                HAS_ATTRIBUTE,
-                                attr_subject.as_ref(),
-                            ))?;
-
-                            let attr_object = NamedNode::new(path).unwrap();
-                            output.add_triple(TripleRef::new(
-                                attr_subject.as_ref(),
-                                TYPE,
-                                attr_object.as_ref(),
-                            ))?;
-
-                            output.add_triple(TripleRef::new(
-                                attr_object.as_ref(),
-                                SUB_CLASS_OF,
-                                XML_ATTRIBUTE,
-                            ))?;
-
-                            if !attr.value.is_empty() {
-                                let attr_object = Literal::new_simple_literal(&attr.value);
-
-                                output.add_triple(TripleRef::new(
-                                    attr_subject.as_ref(),
-                                    HAS_VALUE,
-                                    TermRef::Literal(attr_object.as_ref()),
-                                ))?;
-                            } else {
-                                // TODO any edgecases that need to be addressed here?
-                            }
-                        }
-                    }
-                }
-                Ok(XmlEvent::Characters(text)) => {
-                    // Strip unnecessary whitespace
-                    let text = text.trim();
-                    if !text.is_empty() {
-                        if let Some(ref s) = subject {
-                            let content_object = Literal::new_simple_literal(text);
-                            output.add_triple(TripleRef::new(
-                                s.id.as_ref(),
-                                HAS_VALUE,
-                                TermRef::Literal(content_object.as_ref()),
-                            ))?;
-                        }
+/// Converts the XML document in `input` to N-Triples, entirely in memory.
+///
+/// Unlike the file-based entry points this performs no I/O at all, so it is the entry point to
+/// use when the library is compiled to WebAssembly (`wasm32-unknown-unknown`), e.g. to convert
+/// documents in the browser.
+///
+/// # Example
+/// ```rust
+/// use xml2rdf::convert::{convert_bytes, ConversionOptions};
+///
+/// let nt = convert_bytes(
+///     b"<note><to>Tove</to></note>",
+///     "https://decisym.ai/xml2rdf/data",
+///     &ConversionOptions::default(),
+/// )
+/// .unwrap();
+/// assert_eq!(nt.lines().count(), 8);
+/// ```
+pub fn convert_bytes(
+    input: &[u8],
+    namespace: &str,
+    options: &ConversionOptions,
+) -> Result<String, ConvertError> {
+    let mut w = StringWriter::new();
+    parse_xml_reader(input, &mut w, namespace, options)?;
+    Ok(w.into_string())
+}
+
+/// Converts several XML files into `graph`, adding to the triples it already holds.
+///
+/// The files are converted in one run, as by [`parse_xml_with_options`], so triples they share,
+/// such as class declarations, are produced once. Triples already in `graph` are kept, and
+/// those produced again are merged with them, so the graph grows by the distinct new triples
+/// only. See [`GraphWriter`].
+///
+/// # Example
+/// ```rust
+/// use xml2rdf::convert::{convert_into_graph, ConversionOptions};
+/// use oxrdf::Graph;
+///
+/// let mut g = Graph::new();
+/// convert_into_graph(
+///     vec!["data.xml".to_string(), "more.xml".to_string()],
+///     &mut g,
+///     "https://decisym.ai/xml2rdf/data",
+///     &ConversionOptions::default(),
+/// );
+/// ```
+pub fn convert_into_graph(
+    files: Vec<String>,
+    graph: &mut Graph,
+    namespace: &str,
+    options: &ConversionOptions,
+) -> Result<ConversionSummary, ConvertError> {
+    parse_xml_with_options(files, &mut GraphWriter::new(graph), namespace, options)
+}
+
+/// An iterator converting the XML document read from a reader, yielding its triples lazily.
+///
+/// Where the other entry points push every triple into an [`RdfWriter`], a `TripleStream` only
+/// parses as far as needed to produce the next triple, buffering the few triples a single XML
+/// event generates. It composes with iterator adapters, and dropping it stops the conversion.
+///
+/// Conversion errors are yielded as `Err` items, after which the stream ends. Triples cannot
+/// carry a graph name, so [`ConversionOptions::reconstruction_graph`] and
+/// [`ConversionOptions::document_graphs`] yield an error.
+///
+/// # Example
+/// ```rust
+/// use xml2rdf::convert::{ConversionOptions, TripleStream};
+///
+/// let xml = "<note><to>Tove</to></note>";
+/// let options = ConversionOptions::default();
+/// let stream = TripleStream::new(xml.as_bytes(), "https://decisym.ai/xml2rdf/data", &options)
+///     .unwrap();
+/// let names: Vec<_> = stream
+///     .filter_map(Result::ok)
+///     .filter(|t| t.predicate.as_str().ends_with("hasName"))
+///     .collect();
+/// assert_eq!(names.len(), 2);
+/// ```
+pub struct TripleStream<'a, R: std::io::Read> {
+    converter: Converter<'a, TripleQueue>,
+    document: Document<R>,
+    failed: bool,
+    /// Whether the triples spanning the document, such as counts, have been queued
+    finished: bool,
+}
+
+impl<'a, R: std::io::Read> TripleStream<'a, R> {
+    /// Starts converting the XML document read from `reader`.
+    ///
+    /// Fails if `namespace` is reserved or the first bytes of the input cannot be read.
+    pub fn new(
+        reader: R,
+        namespace: &'a str,
+        options: &'a ConversionOptions,
+    ) -> Result<Self, ConvertError> {
+        let mut converter = Converter::new(TripleQueue::default(), namespace, options)?;
+        let document = Document::open(reader, options, options.max_input_bytes)?;
+        converter.begin_document()?;
+        Ok(Self {
+            converter,
+            document,
+            failed: false,
+            finished: false,
+        })
+    }
+}
+
+impl<R: std::io::Read> Iterator for TripleStream<'_, R> {
+    type Item = Result<Triple, ConvertError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(triple) = self.converter.output.writer.0.pop_front() {
+                return Some(Ok(triple));
+            }
+            if self.failed || self.finished {
+                return None;
+            }
+            let stepped = if self.document.done {
+                self.finished = true;
+                self.converter.write_totals()
+            } else {
+                self.converter.step(&mut self.document)
+            };
+            if let Err(e) = stepped {
+                self.failed = true;
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+/// Buffers the triples of a [`TripleStream`] until they are pulled.
+#[derive(Default)]
+struct TripleQueue(VecDeque<Triple>);
+
+impl RdfWriter for TripleQueue {
+    fn add_triple(&mut self, triple: TripleRef) -> std::io::Result<()> {
+        self.0.push_back(triple.into_owned());
+        Ok(())
+    }
+}
+
+/// Converts every XML document in the zip archive read from `reader` to RDF format using the
+/// provided [`ConversionOptions`].
+///
+/// Entries are streamed straight from the archive without extracting them. Entries in nested
+/// directories are converted too; directories and entries without an `.xml` extension are
+/// skipped. [`parse_xml_with_options`] does the same for input files with a `.zip` extension.
+#[cfg(feature = "zip")]
+pub fn parse_zip_reader<R: std::io::Read + std::io::Seek>(
+    reader: R,
+    output: &mut dyn RdfWriter,
+    namespace: &str,
+    options: &ConversionOptions,
+) -> Result<ConversionSummary, ConvertError> {
+    let mut converter = Converter::new(output, namespace, options)?;
+    converter.convert_archive(reader, None)?;
+    converter.finish()
+}
+
+/// The raw input of a document, limited in size for [`ConversionOptions::max_input_bytes`] and
+/// hashed as it is read for [`ConversionOptions::digest`].
+#[cfg(feature = "digest")]
+type Input<R> = LimitReader<DigestReader<R>>;
+#[cfg(not(feature = "digest"))]
+type Input<R> = LimitReader<R>;
+
+/// An XML document being converted.
+struct Document<R: std::io::Read> {
+    parser: EventReader<OffsetReader<Utf8Reader<std::io::BufReader<Input<R>>>>>,
+    /// Whether the input has no bytes at all
+    zero_bytes: bool,
+    /// Whether an element has been seen
+    has_elements: bool,
+    /// Whether the document has been converted completely
+    done: bool,
+}
+
+impl<R: std::io::Read> Document<R> {
+    /// Opens the document read from `reader`, which may read at most `max_bytes`.
+    fn open(
+        reader: R,
+        options: &ConversionOptions,
+        max_bytes: Option<u64>,
+    ) -> Result<Self, ConvertError> {
+        #[cfg(feature = "digest")]
+        let reader = DigestReader::new(reader, options.digest);
+        let mut reader = std::io::BufReader::new(LimitReader::new(reader, max_bytes));
+        let head = match reader.fill_buf() {
+            Ok(head) => head,
+            Err(e) => return Err(reader.get_ref().error(e)),
+        };
+        let zero_bytes = head.is_empty();
+        let policy = is_utf8(head).then_some(options.invalid_utf8);
+        Ok(Self {
+            parser: EventReader::new(OffsetReader::new(Utf8Reader::new(reader, policy))),
+            zero_bytes,
+            has_elements: false,
+            done: false,
+        })
+    }
+
+    /// Returns the raw input of the document, below the buffer the parser reads from.
+    fn input(&self) -> &Input<R> {
+        self.parser.source().inner.inner.get_ref()
+    }
+
+    #[cfg(feature = "digest")]
+    fn input_mut(&mut self) -> &mut Input<R> {
+        self.parser.source_mut().inner.inner.get_mut()
+    }
+}
+
+/// Fails reading once more than a maximum number of bytes would be returned, for
+/// [`ConversionOptions::max_input_bytes`].
+struct LimitReader<R> {
+    inner: R,
+    /// Maximum number of bytes to return, `None` for no limit
+    limit: Option<u64>,
+    /// Number of bytes returned so far
+    count: u64,
+    /// Whether reading failed because the input is longer than `limit`
+    exceeded: bool,
+}
+
+impl<R> LimitReader<R> {
+    fn new(inner: R, limit: Option<u64>) -> Self {
+        Self {
+            inner,
+            limit,
+            count: 0,
+            exceeded: false,
+        }
+    }
+
+    /// Returns the limit if reading failed because the input exceeds it.
+    fn exceeded_limit(&self) -> Option<u64> {
+        self.limit.filter(|_| self.exceeded)
+    }
+
+    /// Converts an error reading through this reader into a conversion error.
+    fn error(&self, e: std::io::Error) -> ConvertError {
+        match self.exceeded_limit() {
+            Some(limit) => ConvertError::InputTooLarge {
+                source: None,
+                limit,
+            },
+            None => ConvertError::Io(e),
+        }
+    }
+}
+
+impl<R: std::io::Read> std::io::Read for LimitReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let Some(limit) = self.limit else {
+            let n = self.inner.read(buf)?;
+            self.count += n as u64;
+            return Ok(n);
+        };
+        if self.count == limit {
+            // Input of exactly `limit` bytes is fine, any further byte is not returned
+            if buf.is_empty() || self.inner.read(&mut [0])? == 0 {
+                return Ok(0);
+            }
+            self.exceeded = true;
+            return Err(std::io::Error::other("input exceeds the maximum size"));
+        }
+        let allowed = usize::try_from(limit - self.count).unwrap_or(usize::MAX);
+        let len = buf.len().min(allowed);
+        let n = self.inner.read(&mut buf[..len])?;
+        self.count += n as u64;
+        Ok(n)
+    }
+}
+
+/// Counts the bytes the XML parser has consumed, to locate elements in their source.
+///
+/// `xml-rs` reads its input a byte at a time and reports start and end tag events as soon as
+/// their closing `>` has been read, so when a tag event arrives `offset` is the offset just past
+/// the tag. Attribute values cannot contain a raw `<`, so `tag_start` is then the offset of the
+/// tag's opening `<`.
+struct OffsetReader<R> {
+    inner: R,
+    /// Number of bytes read so far
+    offset: u64,
+    /// Offset of the last `<` read
+    tag_start: u64,
+}
+
+impl<R> OffsetReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            offset: 0,
+            tag_start: 0,
+        }
+    }
+}
+
+impl<R: std::io::Read> std::io::Read for OffsetReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if let Some(i) = buf[..n].iter().rposition(|&b| b == b'<') {
+            self.tag_start = self.offset + i as u64;
+        }
+        self.offset += n as u64;
+        Ok(n)
+    }
+}
+
+/// Checks the UTF-8 validity of the bytes read by the XML parser, failing at or replacing invalid
+/// sequences according to a [`Utf8Policy`].
+///
+/// Input is read ahead in chunks so that sequences split across reads are validated whole, and
+/// each chunk is validated once, as `xml-rs` reads a byte at a time.
+struct Utf8Reader<R> {
+    inner: R,
+    /// The policy to apply, `None` to pass the input through unchecked
+    policy: Option<Utf8Policy>,
+    /// Input read ahead
+    buf: Vec<u8>,
+    /// Start of the bytes of `buf` not yet returned
+    start: usize,
+    /// End of the bytes of `buf` known to be valid
+    valid: usize,
+    /// Whether the inner reader is exhausted
+    eof: bool,
+    /// Offset in the input of `buf[0]`
+    buf_offset: u64,
+    /// Bytes of a replacement character not yet returned
+    replacement: &'static [u8],
+    /// Offset in the input of the invalid sequence the reader failed at
+    invalid_at: Option<u64>,
+    /// Offsets in the input of the invalid sequences replaced so far
+    replaced: Vec<u64>,
+}
+
+impl<R: std::io::Read> Utf8Reader<R> {
+    const CHUNK: usize = 8192;
+
+    fn new(inner: R, policy: Option<Utf8Policy>) -> Self {
+        Self {
+            inner,
+            policy,
+            buf: Vec::new(),
+            start: 0,
+            valid: 0,
+            eof: false,
+            buf_offset: 0,
+            replacement: &[],
+            invalid_at: None,
+            replaced: Vec::new(),
+        }
+    }
+
+    /// Reads ahead until at least the longest UTF-8 sequence is buffered, or the input ends.
+    fn fill(&mut self) -> std::io::Result<()> {
+        while self.buf.len() - self.start < 4 && !self.eof {
+            self.buf.drain(..self.start);
+            self.buf_offset += self.start as u64;
+            self.valid -= self.start;
+            self.start = 0;
+
+            let len = self.buf.len();
+            self.buf.resize(len + Self::CHUNK, 0);
+            let n = self.inner.read(&mut self.buf[len..])?;
+            self.buf.truncate(len + n);
+            self.eof = n == 0;
+        }
+        Ok(())
+    }
+}
+
+impl<R: std::io::Read> std::io::Read for Utf8Reader<R> {
+    fn read(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+        let Some(policy) = self.policy else {
+            return self.inner.read(out);
+        };
+        if !self.replacement.is_empty() {
+            let n = out.len().min(self.replacement.len());
+            out[..n].copy_from_slice(&self.replacement[..n]);
+            self.replacement = &self.replacement[n..];
+            return Ok(n);
+        }
+
+        if self.start == self.valid {
+            self.fill()?;
+            let rest = &self.buf[self.start..];
+            if rest.is_empty() {
+                return Ok(0);
+            }
+            match std::str::from_utf8(rest) {
+                Ok(_) => self.valid = self.buf.len(),
+                Err(e) if e.valid_up_to() > 0 => self.valid = self.start + e.valid_up_to(),
+                Err(e) => {
+                    // With four bytes buffered, an incomplete sequence is one cut off by the end
+                    // of the input
+                    let len = e.error_len().unwrap_or(rest.len());
+                    if policy == Utf8Policy::Strict {
+                        self.invalid_at = Some(self.buf_offset + self.start as u64);
+                        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, e));
                     }
+                    self.replaced.push(self.buf_offset + self.start as u64);
+                    self.start += len;
+                    self.valid = self.start;
+                    self.replacement = "\u{FFFD}".as_bytes();
+                    return self.read(out);
                 }
-                Ok(XmlEvent::EndElement { .. }) => {
-                    stack.pop();
-                    subject = None;
-                }
-                _ => {}
             }
         }
+
+        let n = out.len().min(self.valid - self.start);
+        out[..n].copy_from_slice(&self.buf[self.start..self.start + n]);
+        self.start += n;
+        Ok(n)
     }
+}
 
-    Ok(())
+/// Returns whether the document starting with `head` is encoded in UTF-8: it has no UTF-16
+/// byte order mark or UTF-16 encoded `<`, and its XML declaration, if any, declares UTF-8 or no
+/// encoding.
+fn is_utf8(head: &[u8]) -> bool {
+    if head.starts_with(&[0xFE, 0xFF])
+        || head.starts_with(&[0xFF, 0xFE])
+        || head.starts_with(&[0, b'<'])
+        || head.starts_with(&[b'<', 0])
+    {
+        return false;
+    }
+    let head = head.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(head);
+    let Some(declaration) = head.strip_prefix(b"<?xml") else {
+        return true;
+    };
+    let end = declaration
+        .windows(2)
+        .position(|w| w == b"?>")
+        .unwrap_or(declaration.len());
+    let declaration = String::from_utf8_lossy(&declaration[..end]);
+    let Some((_, rest)) = declaration.split_once("encoding") else {
+        return true;
+    };
+    let encoding = rest
+        .trim_start_matches(|c: char| c.is_whitespace() || c == '=')
+        .trim_start_matches(['"', '\''])
+        .split(['"', '\''])
+        .next()
+        .unwrap_or_default();
+    encoding.eq_ignore_ascii_case("utf-8") || encoding.eq_ignore_ascii_case("utf8")
+}
+
+/// Where the document being converted was read from.
+struct Source {
+    /// Path of the file, as given to the converter
+    path: String,
+    /// Path of the document within the zip archive at `path`, if any
+    entry: Option<String>,
+}
+
+impl Source {
+    /// Returns the location as given, with `!/` separating an archive from its entry.
+    fn label(&self) -> String {
+        match &self.entry {
+            Some(entry) => format!("{}!/{}", self.path, entry),
+            None => self.path.clone(),
+        }
+    }
+
+    /// Returns the IRI of the source under `scheme`, wrapped as `jar:...!/entry` for an archive
+    /// entry.
+    fn iri(&self, scheme: &SourceIris) -> String {
+        let path = Path::new(&self.path);
+        let iri = match scheme {
+            SourceIris::File | SourceIris::Opaque => file_iri(path),
+            SourceIris::Base(base) => relative_iri(base, path),
+        };
+        let iri = match &self.entry {
+            Some(entry) => {
+                let mut iri = format!("jar:{iri}!/");
+                percent_encode(&mut iri, entry, b"-._~/");
+                iri
+            }
+            None => iri,
+        };
+        match scheme {
+            SourceIris::Opaque => format!(
+                "urn:uuid:{}",
+                Uuid::new_v5(&Uuid::NAMESPACE_URL, iri.as_bytes())
+            ),
+            _ => iri,
+        }
+    }
+
+    /// Returns the file name, percent-encoded, under `namespace`, followed by the path of the
+    /// archive entry if any.
+    fn file_name_iri(&self, namespace: &str) -> String {
+        let name = Path::new(&self.path)
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        let mut iri = format!("{namespace}/");
+        percent_encode(&mut iri, &name, b"-._~");
+        if let Some(entry) = &self.entry {
+            iri.push('/');
+            percent_encode(&mut iri, entry, b"-._~/");
+        }
+        iri
+    }
+}
+
+/// Outcome of a successful conversion.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConversionSummary {
+    /// Number of triples written.
+    pub triples: usize,
+    /// Whether conversion stopped early because [`ConversionOptions::max_triples`] was reached.
+    pub truncated: bool,
+    /// Number of documents converted, including empty ones.
+    pub documents: usize,
+    /// Number of input bytes read. Documents whose conversion stopped early, e.g. at
+    /// [`ConversionOptions::max_triples`], count only the bytes read up to that point.
+    pub input_bytes: u64,
+    /// Documents that contained no elements, in conversion order.
+    pub empty_documents: Vec<EmptyDocument>,
+    /// Problems that did not stop the conversion, in the order they were met. Only collected
+    /// with [`ConversionOptions::collect_warnings`].
+    pub warnings: Vec<Warning>,
+}
+
+/// A document that produced no element data.
+///
+/// Such documents convert successfully to zero triples, or to just their document node when
+/// [`ConversionOptions::document_node`] is set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmptyDocument {
+    /// Where the document was read from, if known.
+    pub source: Option<String>,
+    /// Whether the input had no bytes at all, as opposed to only an XML declaration, comments or
+    /// processing instructions.
+    pub zero_bytes: bool,
+}
+
+/// A problem that did not stop the conversion, see [`ConversionOptions::collect_warnings`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    /// What kind of problem this is.
+    pub kind: WarningKind,
+    /// Dotted path of the element or attribute concerned, as in [`ConversionOptions::class_map`]
+    /// keys, or empty for problems concerning a whole document.
+    pub path: String,
+    /// Where the document was read from, if known.
+    pub source: Option<String>,
+    /// Location in the XML source, if known.
+    pub position: Option<TextPosition>,
+    /// A description of the problem.
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(source) = &self.source {
+            write!(f, "{source}: ")?;
+        }
+        f.write_str(&self.message)?;
+        if let Some(position) = self.position {
+            write!(f, " at {position}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The kinds of [`Warning`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarningKind {
+    /// An attribute with an empty value, or a whitespace-only one unless
+    /// [`ConversionOptions::emit_whitespace_only`] is set. It gets an attribute node, but no
+    /// value triple.
+    EmptyAttribute,
+    /// An element skipped with its subtree because it does not satisfy
+    /// [`ConversionOptions::conditions`].
+    SkippedElement,
+    /// An invalid UTF-8 sequence replaced under [`Utf8Policy::Lossy`]. The message gives its byte
+    /// offset in the input.
+    ReplacedInvalidUtf8,
+    /// The conversion stopped because [`ConversionOptions::max_triples`] was reached.
+    Truncated,
+    /// Content not valid in its [`ConversionOptions::binary`] encoding, kept as a plain literal
+    /// under [`BinaryPolicy::Warn`].
+    InvalidBinary,
+    /// The document is not well-formed XML, e.g. a closing tag does not match the open element.
+    /// Its conversion stopped at the error, keeping the triples of everything before it; the
+    /// elements still open are left incomplete rather than linked to the wrong parents. The
+    /// path is that of the innermost open element.
+    MalformedXml,
+}
+
+/// Counts the triples passed to the wrapped writer, dropping any beyond `max_triples` and any
+/// with predicates outside `only_predicates`.
+struct Output<W> {
+    writer: W,
+    max_triples: Option<usize>,
+    triples: usize,
+    /// Whether a triple has been dropped
+    truncated: bool,
+    /// Named graph receiving the triples of the current document, see
+    /// [`ConversionOptions::document_graphs`]
+    graph: Option<NamedNode>,
+    /// The only predicates passed on, see [`ConversionOptions::only_predicates`]
+    only_predicates: Option<Vec<NamedNode>>,
+}
+
+impl<W> Output<W> {
+    /// Returns whether a triple with `predicate` is emitted and fits under the limit, recording
+    /// truncation if not.
+    fn admit(&mut self, predicate: NamedNodeRef) -> bool {
+        if self
+            .only_predicates
+            .as_ref()
+            .is_some_and(|only| !only.iter().any(|p| p.as_ref() == predicate))
+        {
+            return false;
+        }
+        if self.max_triples.is_some_and(|max| self.triples >= max) {
+            self.truncated = true;
+            return false;
+        }
+        self.triples += 1;
+        true
+    }
+}
+
+impl<W: RdfWriter> RdfWriter for Output<W> {
+    fn add_triple(&mut self, triple: TripleRef) -> std::io::Result<()> {
+        if self.admit(triple.predicate) {
+            match &self.graph {
+                Some(graph) => self.writer.add_quad(triple.in_graph(graph.as_ref()))?,
+                None => self.writer.add_triple(triple)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn add_quad(&mut self, quad: QuadRef) -> std::io::Result<()> {
+        if self.admit(quad.predicate) {
+            self.writer.add_quad(quad)?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.writer.reserve(additional)
+    }
+}
+
+/// Conversion state shared by every document converted in a single run.
+struct Converter<'a, W = &'a mut dyn RdfWriter> {
+    output: Output<W>,
+    namespace: &'a str,
+    options: &'a ConversionOptions,
+    /// Open elements of the document currently being converted
+    stack: Vec<Node<'a>>,
+    /// Class of each element or attribute path seen so far, so repeated and wide elements do not
+    /// re-validate the same IRIs
+    classes: HashMap<String, NamedNode>,
+    /// Data namespaces that have already produced a valid node IRI
+    checked_namespaces: Vec<&'a str>,
+    /// Whether any document has been converted yet
+    started: bool,
+    /// Path of the file the current document is read from, if any
+    source: Option<Source>,
+    /// Document node of the document currently being converted, if written
+    document: Option<NamedOrBlankNode>,
+    /// Document nodes named by [`ConversionOptions::document_iri`] so far, so no two documents
+    /// share one
+    document_iris: HashSet<NamedNode>,
+    /// Graphs named by [`DocumentIdentity::FileName`] so far, so no two documents share one
+    file_name_graphs: HashSet<NamedNode>,
+    /// Node of the conversion run, minted by the first document linked to it under
+    /// [`ConversionOptions::record_options`]
+    run: Option<NamedOrBlankNode>,
+    /// Classes of embedded JSON values declared `rdfs:subClassOf` `JsonValue` so far
+    json_classes: HashSet<NamedNode>,
+    /// Predicate of each attribute key seen so far under [`ConversionOptions::property_style`]
+    properties: HashMap<String, NamedNode>,
+    /// Predicate of each element name seen so far under [`ConversionOptions::child_predicates`]
+    child_properties: HashMap<String, NamedNode>,
+    /// Slug of each name encoded by [`NameEncoding::Slug`] so far
+    slugs: HashMap<String, String>,
+    /// Slugs given to names so far, so no two names share one
+    taken_slugs: HashSet<String>,
+    /// Nesting depth inside a subtree skipped because of [`ConversionOptions::conditions`], 0
+    /// while converting
+    skip_depth: usize,
+    /// Documents converted so far that contained no elements
+    empty_documents: Vec<EmptyDocument>,
+    /// Warnings collected so far, if [`ConversionOptions::collect_warnings`] is set
+    warnings: Vec<Warning>,
+    /// Child element held back by [`ConversionOptions::collapse_wrappers`]
+    held: Option<HeldChild>,
+    /// Number of documents converted so far
+    documents: usize,
+    /// Number of input bytes read by the documents converted so far
+    input_bytes: u64,
+    /// Number of elements converted in the current document
+    document_elements: usize,
+    /// The conversion time recorded on document nodes
+    converted_at: Option<Literal>,
+    /// Index into `samples` of each element path sampled by
+    /// [`ConversionOptions::sample_per_path`]
+    sampled_paths: HashMap<String, usize>,
+    /// Each sampled element and the number of elements sharing its path, in sampling order
+    samples: Vec<(NamedOrBlankNode, u64)>,
+    /// Index into `instance_counts` of each class counted by
+    /// [`ConversionOptions::instance_counts`]
+    counted_classes: HashMap<NamedNode, usize>,
+    /// Each counted class and the number of elements typed with it, in order of first use
+    instance_counts: Vec<(NamedNode, u64)>,
+}
+
+impl<'a, W: RdfWriter> Converter<'a, W> {
+    /// Creates a converter, rejecting a data `namespace` that overlaps the model namespace.
+    fn new(
+        output: W,
+        namespace: &'a str,
+        options: &'a ConversionOptions,
+    ) -> Result<Self, ConvertError> {
+        let overrides = options.namespace_overrides.values();
+        if let Some(namespace) = [namespace]
+            .into_iter()
+            .chain(overrides.map(String::as_str))
+            .find(|ns| overlaps_model(ns))
+        {
+            return Err(ConvertError::ReservedNamespace {
+                namespace: namespace.to_string(),
+            });
+        }
+        Ok(Self {
+            output: Output {
+                writer: output,
+                max_triples: options.max_triples,
+                triples: 0,
+                truncated: false,
+                graph: None,
+                only_predicates: options.only_predicates.clone(),
+            },
+            namespace,
+            options,
+            stack: Vec::new(),
+            classes: HashMap::new(),
+            checked_namespaces: Vec::new(),
+            started: false,
+            source: None,
+            document: None,
+            document_iris: HashSet::new(),
+            file_name_graphs: HashSet::new(),
+            run: None,
+            json_classes: HashSet::new(),
+            properties: HashMap::new(),
+            child_properties: HashMap::new(),
+            slugs: HashMap::new(),
+            taken_slugs: HashSet::new(),
+            skip_depth: 0,
+            empty_documents: Vec::new(),
+            warnings: Vec::new(),
+            held: None,
+            documents: 0,
+            input_bytes: 0,
+            document_elements: 0,
+            converted_at: match &options.conversion_time {
+                ConversionTime::Omit => None,
+                ConversionTime::Now => Some(datatype::format_date_time(SystemTime::now())),
+                ConversionTime::Fixed(time) => Some(time.clone()),
+            }
+            .map(|time| Literal::new_typed_literal(time, xsd::DATE_TIME)),
+            sampled_paths: HashMap::new(),
+            samples: Vec::new(),
+            counted_classes: HashMap::new(),
+            instance_counts: Vec::new(),
+        })
+    }
+
+    /// Writes the sample counts and flushes the output once all documents have been converted.
+    fn finish(mut self) -> Result<ConversionSummary, ConvertError> {
+        self.write_totals()?;
+        self.output.flush()?;
+        Ok(ConversionSummary {
+            triples: self.output.triples,
+            truncated: self.output.truncated,
+            documents: self.documents,
+            input_bytes: self.input_bytes,
+            empty_documents: self.empty_documents,
+            warnings: self.warnings,
+        })
+    }
+
+    /// Writes what spans all documents: the sample and instance counts and the conversion run.
+    fn write_totals(&mut self) -> Result<(), ConvertError> {
+        // Counts span all documents, so they belong to no document graph
+        self.output.graph = None;
+        if self.options.sample_per_path && self.options.sample_counts {
+            for (sample, count) in &self.samples {
+                let count = Literal::from(*count);
+                self.output.add_triple(TripleRef::new(
+                    sample.as_ref(),
+                    OCCURRENCES,
+                    TermRef::Literal(count.as_ref()),
+                ))?;
+            }
+        }
+        if let Some(run) = self.run.take() {
+            self.write_run(&run)?;
+        }
+        for (class, count) in &self.instance_counts {
+            let count = Literal::from(*count);
+            self.output.add_triple(TripleRef::new(
+                class.as_ref(),
+                INSTANCE_COUNT,
+                TermRef::Literal(count.as_ref()),
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Converts a single XML document, naming its source in errors about its content.
+    fn convert<R: std::io::Read>(&mut self, reader: R) -> Result<(), ConvertError> {
+        self.convert_document(reader)
+            .map_err(|e| e.in_source(self.source.as_ref().map(Source::label)))
+    }
+
+    /// Converts a single XML document, see [`Converter::convert`].
+    fn convert_document<R: std::io::Read>(&mut self, reader: R) -> Result<(), ConvertError> {
+        let max_bytes = self
+            .options
+            .max_input_bytes
+            .map(|max| max.saturating_sub(self.input_bytes));
+        let mut document = Document::open(reader, self.options, max_bytes)?;
+        self.begin_document()?;
+        while !document.done {
+            self.step(&mut document)?;
+        }
+        Ok(())
+    }
+
+    /// Resets the per-document state, and writes the ontology before the first document if
+    /// configured.
+    fn begin_document(&mut self) -> Result<(), ConvertError> {
+        self.stack.clear();
+        self.document = None;
+        self.skip_depth = 0;
+        self.held = None;
+        self.document_elements = 0;
+        self.output.graph = None;
+
+        if !self.started {
+            self.started = true;
+            if self.options.embed_ontology {
+                for triple in ontology() {
+                    self.output.add_triple(triple.as_ref())?;
+                }
+                for triple in self.options.vocabulary.sub_properties() {
+                    self.output.add_triple(triple.as_ref())?;
+                }
+            }
+            if self.options.inverse_links == Some(InverseLinks::Declared) {
+                for (inverse, property) in [(HAS_PARENT, HAS_CHILD), (ATTRIBUTE_OF, HAS_ATTRIBUTE)]
+                {
+                    self.output
+                        .add_triple(TripleRef::new(inverse, OWL_INVERSE_OF, property))?;
+                }
+            }
+            if self.options.owl_safe {
+                self.write_owl_declarations()?;
+            }
+        }
+
+        if self.options.document_graphs.is_some() {
+            // Each graph gets the class triples of its own document, so replacing one graph
+            // leaves the others complete
+            self.classes.clear();
+        }
+        if let Some(identity @ (DocumentIdentity::Source | DocumentIdentity::FileName)) =
+            &self.options.document_graphs
+        {
+            let Some(source) = &self.source else {
+                return Err(ConvertError::MissingIdentity {
+                    source: None,
+                    attribute: None,
+                });
+            };
+            let iri = match identity {
+                DocumentIdentity::FileName => source.file_name_iri(self.namespace),
+                _ => source.iri(&self.options.source_iris),
+            };
+            let graph = mint_iri(iri, "", TextPosition::new())?;
+            if *identity == DocumentIdentity::FileName
+                && !self.file_name_graphs.insert(graph.clone())
+            {
+                return Err(ConvertError::DuplicateGraph {
+                    source: None,
+                    graph: graph.into_string(),
+                });
+            }
+            self.output.graph = Some(graph);
+        }
+        Ok(())
+    }
+
+    /// Declares the model terms, the PROV-O terms if used, and the configured domain classes
+    /// and predicates, for [`ConversionOptions::owl_safe`].
+    fn write_owl_declarations(&mut self) -> Result<(), ConvertError> {
+        let mut declarations = owl_declarations();
+        if self.options.prov {
+            declarations.push(Triple::new(
+                PROV_WAS_DERIVED_FROM,
+                TYPE,
+                OWL_OBJECT_PROPERTY,
+            ));
+            declarations.push(Triple::new(
+                PROV_GENERATED_AT_TIME,
+                TYPE,
+                OWL_DATATYPE_PROPERTY,
+            ));
+        }
+        let classes = self.options.class_map.values();
+        let predicates = self
+            .options
+            .promoted_attributes
+            .values()
+            .filter_map(|p| p.predicate.as_ref())
+            .chain(self.options.flag_elements.values());
+        let mut configured: Vec<_> = classes
+            .map(|class| Triple::new(class.clone(), TYPE, OWL_CLASS))
+            .chain(predicates.map(|p| Triple::new(p.clone(), TYPE, OWL_DATATYPE_PROPERTY)))
+            .filter(|triple| !declarations.contains(triple))
+            .collect();
+        // Sorted, so the declarations come out in the same order on every run
+        configured.sort_by_key(Triple::to_string);
+        configured.dedup();
+        for triple in declarations.iter().chain(&configured) {
+            self.output.add_triple(triple.as_ref())?;
+        }
+        Ok(())
+    }
+
+    /// Processes the next XML event of `document`, finishing the document at its end.
+    fn step<R: std::io::Read>(&mut self, document: &mut Document<R>) -> Result<(), ConvertError> {
+        // Stop once the triple limit has been hit, leaving the output as complete triples
+        if self.output.truncated || document.zero_bytes {
+            return self.end_document(document);
+        }
+
+        let parser = &mut document.parser;
+        let e = parser.next();
+        let position = parser.position();
+        match e {
+            Ok(XmlEvent::StartElement {
+                name,
+                attributes,
+                namespace,
+            }) => {
+                document.has_elements = true;
+                let held = usize::from(self.held.is_some());
+                if self.skip_depth > 0 {
+                    self.skip_depth += 1;
+                } else if self
+                    .options
+                    .max_elements
+                    .is_some_and(|max| self.document_elements + held >= max)
+                {
+                    // Close the open elements, so their values are still written
+                    self.release_held()?;
+                    while !self.stack.is_empty() {
+                        self.end_element()?;
+                    }
+                    return self.end_document(document);
+                } else {
+                    // A held child with children of its own is no wrapped scalar
+                    self.release_held()?;
+                    let tag_start = parser.source().tag_start;
+                    if self.can_hold(&name, &attributes) {
+                        self.held = Some(HeldChild {
+                            name,
+                            namespace,
+                            position,
+                            start_offset: tag_start,
+                            text: Vec::new(),
+                            end_offset: None,
+                            trailing: Vec::new(),
+                        });
+                    } else {
+                        self.start_element(&name, attributes, namespace, position)?;
+                        if self.options.source_offsets && self.skip_depth == 0 {
+                            self.write_offset(START_OFFSET, tag_start)?;
+                        }
+                    }
+                }
+            }
+            Ok(XmlEvent::Characters(text))
+            | Ok(XmlEvent::CData(text))
+            | Ok(XmlEvent::Whitespace(text)) => match &mut self.held {
+                Some(held) if held.end_offset.is_none() => held.text.push(text),
+                Some(held) if text.trim().is_empty() => held.trailing.push(text),
+                Some(_) => {
+                    // Text next to the held child makes its parent mixed content
+                    self.release_held()?;
+                    self.push_text(text);
+                }
+                None => self.push_text(text),
+            },
+            Ok(XmlEvent::EndElement { .. }) if self.skip_depth > 0 => self.skip_depth -= 1,
+            Ok(XmlEvent::EndElement { .. }) => match &mut self.held {
+                Some(held) if held.end_offset.is_none() => {
+                    held.end_offset = Some(parser.source().offset);
+                }
+                Some(_) => {
+                    self.collapse_held()?;
+                    if self.options.source_offsets {
+                        self.write_offset(END_OFFSET, parser.source().offset)?;
+                    }
+                    self.end_element()?;
+                }
+                None => {
+                    if self.options.source_offsets {
+                        self.write_offset(END_OFFSET, parser.source().offset)?;
+                    }
+                    self.end_element()?
+                }
+            },
+            Ok(XmlEvent::EndDocument) => return self.end_document(document),
+            Err(e) => {
+                if let Some(offset) = parser.source().inner.invalid_at {
+                    document.done = true;
+                    return Err(ConvertError::InvalidUtf8 {
+                        source: None,
+                        offset,
+                    });
+                }
+                if let Some(limit) = document.input().exceeded_limit() {
+                    document.done = true;
+                    return Err(ConvertError::InputTooLarge {
+                        source: None,
+                        limit,
+                    });
+                }
+                // Stop at the first XML syntax error. Documents without elements are reported
+                // as empty instead.
+                if document.has_elements {
+                    let path = self
+                        .stack
+                        .last()
+                        .map(|s| s.path.clone())
+                        .unwrap_or_default();
+                    self.warn(WarningKind::MalformedXml, &path, Some(position), || {
+                        e.msg().to_string()
+                    });
+                }
+                return self.end_document(document);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Collects a fragment of text on the enclosing element, skipping whitespace-only fragments
+    /// unless they were asked for.
+    fn push_text(&mut self, text: String) {
+        let keep = self.options.emit_whitespace_only || !text.trim().is_empty();
+        match self.stack.last_mut() {
+            Some(s) if self.skip_depth == 0 => {
+                if self.options.branch_text == BranchTextPolicy::Mixed {
+                    match s.content.last_mut() {
+                        Some(Content::Text(run)) => run.push_str(&text),
+                        _ => s.content.push(Content::Text(text.clone())),
+                    }
+                }
+                if self.options.text_as_node {
+                    // The run in progress, if no child element started since
+                    let next = s.children + s.runs.len();
+                    match s.runs.last_mut() {
+                        Some((index, run)) if *index + 1 == next => run.push_str(&text),
+                        _ => s.runs.push((next, text.clone())),
+                    }
+                }
+                if keep {
+                    s.text.push(text);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns whether the element starting with `name` and `attributes` may be the wrapped
+    /// scalar of a wrapper to collapse: it has no attributes, is neither skipped nor sampled out,
+    /// and is the first child of an element without text of its own.
+    fn can_hold(&self, name: &OwnedName, attributes: &[OwnedAttribute]) -> bool {
+        if !self.options.collapse_wrappers || !attributes.is_empty() {
+            return false;
+        }
+        let Some(parent) = self.stack.last() else {
+            return false;
+        };
+        let key = self.name_key(name);
+        let path = format!("{}.{key}", parent.path);
+        parent.children == 0
+            && parent.text.iter().all(|t| t.trim().is_empty())
+            && self.satisfies_conditions(&path, &key, &[])
+            && !self.sampled_paths.contains_key(&path)
+    }
+
+    /// Converts the held child, if any, after all, replaying the events held back.
+    fn release_held(&mut self) -> Result<(), ConvertError> {
+        let Some(held) = self.held.take() else {
+            return Ok(());
+        };
+        self.start_element(&held.name, Vec::new(), held.namespace, held.position)?;
+        if self.options.source_offsets {
+            self.write_offset(START_OFFSET, held.start_offset)?;
+        }
+        for text in held.text {
+            self.push_text(text);
+        }
+        if let Some(end_offset) = held.end_offset {
+            if self.options.source_offsets {
+                self.write_offset(END_OFFSET, end_offset)?;
+            }
+            self.end_element()?;
+            for text in held.trailing {
+                self.push_text(text);
+            }
+        }
+        Ok(())
+    }
+
+    /// At the end of the held child's parent, makes the child's text the parent's if the child
+    /// is a wrapped scalar, or converts the child after all otherwise.
+    fn collapse_held(&mut self) -> Result<(), ConvertError> {
+        let is_scalar = self
+            .held
+            .as_ref()
+            .is_some_and(|held| held.text.iter().any(|t| !t.trim().is_empty()));
+        if !is_scalar {
+            return self.release_held();
+        }
+        let Some(held) = self.held.take() else {
+            return Ok(());
+        };
+        if let Some(parent) = self.stack.last_mut() {
+            parent.text.clear();
+            parent.content.clear();
+        }
+        for text in held.text {
+            self.push_text(text);
+        }
+        Ok(())
+    }
+
+    /// Marks `document` as done, recording it if it turned out to have no elements.
+    fn end_document<R: std::io::Read>(
+        &mut self,
+        document: &mut Document<R>,
+    ) -> Result<(), ConvertError> {
+        document.done = true;
+        // Convert a child still held back when the document ended early
+        self.release_held()?;
+
+        // Documents without elements still get their document node
+        if !document.has_elements && !self.output.truncated {
+            if self.options.document_node {
+                self.write_document(None, document.parser.position())?;
+            }
+            self.empty_documents.push(EmptyDocument {
+                source: self.source.as_ref().map(Source::label),
+                zero_bytes: document.zero_bytes,
+            });
+        }
+        for offset in std::mem::take(&mut document.parser.source_mut().inner.replaced) {
+            self.warn(WarningKind::ReplacedInvalidUtf8, "", None, || {
+                format!("invalid UTF-8 sequence at byte offset {offset} replaced")
+            });
+        }
+        if self.output.truncated
+            && !self
+                .warnings
+                .iter()
+                .any(|w| w.kind == WarningKind::Truncated)
+        {
+            let position = document.parser.position();
+            let triples = self.output.triples;
+            self.warn(WarningKind::Truncated, "", Some(position), || {
+                format!("stopped after {triples} triples, the maximum")
+            });
+        }
+        #[cfg(feature = "digest")]
+        self.write_digest(document)?;
+        self.documents += 1;
+        self.input_bytes += document.input().count;
+        Ok(())
+    }
+
+    /// Reads `document` to its end and records the digest of its bytes on its document node, if
+    /// [`ConversionOptions::digest`] is set.
+    #[cfg(feature = "digest")]
+    fn write_digest<R: std::io::Read>(
+        &mut self,
+        document: &mut Document<R>,
+    ) -> Result<(), ConvertError> {
+        let (Some(algorithm), Some(node)) = (self.options.digest, &self.document) else {
+            return Ok(());
+        };
+        let input = document.input_mut();
+        if let Err(e) = std::io::copy(input, &mut std::io::sink()) {
+            return Err(input.error(e));
+        }
+        if let Some(digest) = input.inner.finish() {
+            let digest = Literal::new_simple_literal(digest);
+            self.output.add_triple(TripleRef::new(
+                node.as_ref(),
+                algorithm.predicate(),
+                TermRef::Literal(digest.as_ref()),
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Converts each XML entry of a zip archive, read from the file at `path` if known.
+    #[cfg(feature = "zip")]
+    fn convert_archive<R: std::io::Read + std::io::Seek>(
+        &mut self,
+        reader: R,
+        path: Option<&str>,
+    ) -> Result<(), ConvertError> {
+        let mut archive = zip::ZipArchive::new(reader).map_err(std::io::Error::other)?;
+        for i in 0..archive.len() {
+            if self.output.truncated {
+                break;
+            }
+            let entry = archive.by_index(i).map_err(std::io::Error::other)?;
+            if !entry.is_file() || !has_extension(entry.name(), "xml") {
+                continue;
+            }
+            self.source = path.map(|path| Source {
+                path: path.to_string(),
+                entry: Some(entry.name().to_string()),
+            });
+            self.convert(entry)?;
+        }
+        Ok(())
+    }
+
+    fn start_element(
+        &mut self,
+        name: &OwnedName,
+        attributes: Vec<OwnedAttribute>,
+        namespace: Namespace,
+        position: TextPosition,
+    ) -> Result<(), ConvertError> {
+        let local_name = name.local_name.as_str();
+        let key = self.name_key(name);
+
+        // Define the subject as the IRI of the element
+        let path = if let Some(parent) = self.stack.last() {
+            format!("{}.{}", parent.path, key)
+        } else {
+            format!("{X2R}{}", key)
+        };
+        if !self.satisfies_conditions(&path, &key, &attributes) {
+            self.skip_depth = 1;
+            self.warn(WarningKind::SkippedElement, &path, Some(position), || {
+                "element skipped, it does not satisfy the conditions".to_string()
+            });
+            return Ok(());
+        }
+        if let (None, Some(DocumentIdentity::RootAttribute(attribute))) =
+            (self.stack.last(), &self.options.document_graphs)
+        {
+            let Some(value) = attributes
+                .iter()
+                .find(|attr| attr.name.borrow().to_repr() == *attribute)
+                .map(|attr| &attr.value)
+            else {
+                return Err(ConvertError::MissingIdentity {
+                    source: None,
+                    attribute: Some(attribute.clone()),
+                });
+            };
+            let mut iri = format!("{}/", self.namespace);
+            percent_encode(&mut iri, value, b"-._~");
+            self.output.graph = Some(mint_iri(iri, &path, position)?);
+        }
+        // Only the first element of each path is sampled
+        if let Some(&sample) = self.sampled_paths.get(&path) {
+            self.samples[sample].1 += 1;
+            self.skip_depth = 1;
+            return Ok(());
+        }
+        self.document_elements += 1;
+        // An element with held back attributes turned out not to be a leaf
+        if self.options.json_leaves {
+            self.write_deferred_attributes()?;
+        }
+        let (index, sibling) = match self.stack.last_mut() {
+            Some(parent) => {
+                close_run(parent, self.options);
+                // Text nodes count among the siblings, but not in the keys of elements
+                let sibling = parent.children + parent.runs.len();
+                parent.children += 1;
+                (parent.children - 1, sibling)
+            }
+            None => (0, 0),
+        };
+        let node_key = if matches!(self.options.node_ids, NodeIds::Deterministic { .. }) {
+            match self.stack.last() {
+                Some(parent) => format!("{}/{index}", parent.key),
+                None => format!("{}/{index}", self.document_key()),
+            }
+        } else {
+            String::new()
+        };
+        let data_namespace = self.data_namespace(&path, &key);
+        let class_path = self.class_path(&key);
+        let mut s = Node {
+            id: self.mint_node(data_namespace, &path, || node_key.clone(), position)?,
+            path,
+            name: key,
+            text: Vec::new(),
+            children: 0,
+            key: node_key,
+            namespace: None,
+            attributes: Vec::new(),
+            promoted: None,
+            content: Vec::new(),
+            runs: Vec::new(),
+            position,
+            data_namespace,
+            class_path,
+            nil: (self.options.empty_markers || !self.options.flag_elements.is_empty())
+                && attributes.iter().any(is_xsi_nil),
+        };
+        self.write_index(&s.id, SIBLING_INDEX, sibling)?;
+        if self.options.sample_per_path {
+            self.sampled_paths
+                .insert(s.path.clone(), self.samples.len());
+            self.samples.push((s.id.clone(), 1));
+        }
+
+        // Each attribute produces up to four triples
+        self.output.reserve(4 + attributes.len() * 4);
+
+        let child_predicate = if self.options.child_predicates && !self.stack.is_empty() {
+            Some(self.child_predicate(&s.name, &s.path, position)?)
+        } else {
+            None
+        };
+        if let Some(parent) = self.stack.last_mut() {
+            if self.options.branch_text == BranchTextPolicy::Mixed {
+                parent.content.push(Content::Element(s.id.clone()));
+            }
+            let predicate = child_predicate
+                .as_ref()
+                .map_or(HAS_CHILD, NamedNode::as_ref);
+            self.output
+                .add_triple(TripleRef::new(parent.id.as_ref(), predicate, s.id.as_ref()))?;
+            if self.options.inverse_links == Some(InverseLinks::Materialized) {
+                self.output.add_triple(TripleRef::new(
+                    s.id.as_ref(),
+                    HAS_PARENT,
+                    parent.id.as_ref(),
+                ))?;
+            }
+        } else {
+            if self.options.document_node {
+                self.write_document(Some(&s), position)?;
+            }
+            if let Some(corpus) = &self.options.corpus_node {
+                let predicate = self.options.corpus_predicate.as_ref();
+                let predicate = predicate.map_or(HAS_CHILD, NamedNode::as_ref);
+                self.output
+                    .add_triple(TripleRef::new(corpus, predicate, s.id.as_ref()))?;
+                if self.options.inverse_links == Some(InverseLinks::Materialized) {
+                    self.output
+                        .add_triple(TripleRef::new(s.id.as_ref(), HAS_PARENT, corpus))?;
+                }
+            }
+        }
+        self.write_in_document(&s.id)?;
+        if self.options.owl_safe {
+            // The element class, not the element node, goes below XmlNode
+            if self.options.type_triples || self.options.class_hierarchy {
+                let class = self.class_iri(
+                    &s.path,
+                    s.class_path.as_deref(),
+                    || s.name.clone(),
+                    position,
+                )?;
+                self.write_class(&s.id, &class, XML_ELEMENT)?;
+            }
+        } else if self.options.type_triples {
+            let object = self.class_iri(
+                &s.path,
+                s.class_path.as_deref(),
+                || s.name.clone(),
+                position,
+            )?;
+            self.output
+                .add_triple(TripleRef::new(s.id.as_ref(), TYPE, object.as_ref()))?;
+        }
+        if self.options.instance_counts && self.options.type_triples {
+            let class = self.class_iri(
+                &s.path,
+                s.class_path.as_deref(),
+                || s.name.clone(),
+                position,
+            )?;
+            match self.counted_classes.get(&class) {
+                Some(&i) => self.instance_counts[i].1 += 1,
+                None => {
+                    self.counted_classes
+                        .insert(class.clone(), self.instance_counts.len());
+                    self.instance_counts.push((class, 1));
+                }
+            }
+        }
+        if !self.options.class_map.is_empty() {
+            self.write_domain_classes(&s)?;
+        }
+
+        let object = Literal::new_simple_literal(local_name);
+        self.output.add_triple(TripleRef::new(
+            s.id.as_ref(),
+            HAS_NAME,
+            TermRef::Literal(object.as_ref()),
+        ))?;
+        if self.options.emit_qnames {
+            let qname = Literal::new_simple_literal(name.borrow().to_repr());
+            self.output.add_triple(TripleRef::new(
+                s.id.as_ref(),
+                HAS_QNAME,
+                TermRef::Literal(qname.as_ref()),
+            ))?;
+        }
+
+        if self.options.class_hierarchy && !self.options.owl_safe {
+            self.output
+                .add_triple(TripleRef::new(s.id.as_ref(), SUB_CLASS_OF, XML_ELEMENT))?;
+        }
+
+        let mut attributes: Vec<(usize, OwnedAttribute)> =
+            attributes.into_iter().enumerate().collect();
+        s.promoted = self.take_promoted(&s, &mut attributes);
+        if (self.options.json_leaves || s.promoted.is_some()) && !attributes.is_empty() {
+            s.attributes = attributes;
+        } else {
+            self.write_attributes(&s, attributes, position)?;
+        }
+
+        if self.options.emit_namespace_decls {
+            self.write_namespace_decls(&s, &namespace)?;
+            s.namespace = Some(namespace);
+        }
+
+        self.stack.push(s);
+        Ok(())
+    }
+
+    /// Records the byte `offset` of the innermost open element with `predicate`.
+    fn write_offset(&mut self, predicate: NamedNodeRef, offset: u64) -> Result<(), ConvertError> {
+        if let Some(s) = self.stack.last() {
+            let offset = Literal::from(offset);
+            self.output.add_triple(TripleRef::new(
+                s.id.as_ref(),
+                predicate,
+                TermRef::Literal(offset.as_ref()),
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the data namespace of the element at `path` named `name`: its own
+    /// [`ConversionOptions::namespace_overrides`] entry, by path before name, or else the one of
+    /// its parent, or else the namespace given to the converter.
+    fn data_namespace(&self, path: &str, name: &str) -> &'a str {
+        let overrides = &self.options.namespace_overrides;
+        if !overrides.is_empty() {
+            let path = path.strip_prefix(X2R).unwrap_or(path);
+            if let Some(namespace) = overrides.get(path).or_else(|| overrides.get(name)) {
+                return namespace;
+            }
+        }
+        match self.stack.last() {
+            Some(parent) => parent.data_namespace,
+            None => self.namespace,
+        }
+    }
+
+    /// Returns whether the element at `path` named `name` satisfies every condition naming it.
+    fn satisfies_conditions(&self, path: &str, name: &str, attributes: &[OwnedAttribute]) -> bool {
+        let path = path.strip_prefix(X2R).unwrap_or(path);
+        self.options
+            .conditions
+            .iter()
+            .filter(|c| c.applies_to(path, name))
+            .all(|c| c.holds(attributes))
+    }
+
+    /// Types the element `s` with the domain classes its path or name is mapped to in
+    /// [`ConversionOptions::class_map`].
+    fn write_domain_classes(&mut self, s: &Node) -> Result<(), ConvertError> {
+        let path = s.path.strip_prefix(X2R).unwrap_or(&s.path);
+        let by_path = self.options.class_map.get(path);
+        let by_name = self.options.class_map.get(&s.name);
+        for class in by_path
+            .into_iter()
+            .chain(by_name.filter(|c| Some(*c) != by_path))
+        {
+            self.output
+                .add_triple(TripleRef::new(s.id.as_ref(), TYPE, class.as_ref()))?;
+        }
+        Ok(())
+    }
+
+    /// Writes the attribute nodes of the element `s`, given with their indices, directly on the
+    /// element or in its attribute bag.
+    fn write_attributes(
+        &mut self,
+        s: &Node<'a>,
+        mut attributes: Vec<(usize, OwnedAttribute)>,
+        position: TextPosition,
+    ) -> Result<(), ConvertError> {
+        if attributes.is_empty() {
+            return Ok(());
+        }
+        // Indices record the source order even when emitting in name order
+        if self.options.sort_attributes {
+            attributes.sort_by(|(_, a), (_, b)| {
+                (
+                    a.name.namespace.as_deref().unwrap_or(""),
+                    &a.name.local_name,
+                )
+                    .cmp(&(
+                        b.name.namespace.as_deref().unwrap_or(""),
+                        &b.name.local_name,
+                    ))
+            });
+        }
+        if self.options.property_style {
+            return self.write_attribute_properties(s, attributes, position);
+        }
+
+        // Attribute paths share the element path as a prefix, so build them in a single buffer
+        let mut path = format!("{}.-", s.path);
+        let prefix_len = path.len();
+        let mut class_path = s
+            .class_path
+            .as_ref()
+            .map(|p| format!("{p}{}-", self.options.path_delimiter));
+        let class_prefix_len = class_path.as_ref().map_or(0, String::len);
+
+        // Attributes hang off the element itself, or off its attribute bag
+        let attr_parent = if self.options.attribute_bag {
+            let bag =
+                self.mint_node(s.data_namespace, &path, || format!("{}/@", s.key), position)?;
+            self.output.add_triple(TripleRef::new(
+                s.id.as_ref(),
+                HAS_ATTRIBUTE_BAG,
+                bag.as_ref(),
+            ))?;
+            self.output
+                .add_triple(TripleRef::new(bag.as_ref(), TYPE, XML_ATTRIBUTE_BAG))?;
+            self.write_in_document(&bag)?;
+            bag
+        } else {
+            s.id.clone()
+        };
+
+        for (index, attr) in attributes {
+            let key = self.name_key(&attr.name);
+            path.truncate(prefix_len);
+            path.push_str(&key);
+            if let Some(class_path) = &mut class_path {
+                class_path.truncate(class_prefix_len);
+                class_path.push_str(&key);
+            }
+
+            let attr_key = || match &attr.name.namespace {
+                Some(ns) => format!("{}/@{{{ns}}}{}", s.key, attr.name.local_name),
+                None => format!("{}/@{}", s.key, attr.name.local_name),
+            };
+            let attr_subject = self.mint_node(s.data_namespace, &path, attr_key, position)?;
+            self.write_index(&attr_subject, ATTRIBUTE_INDEX, index)?;
+            self.write_in_document(&attr_subject)?;
+
+            self.output.add_triple(TripleRef::new(
+                attr_parent.as_ref(),
+                HAS_ATTRIBUTE,
+                attr_subject.as_ref(),
+            ))?;
+            if self.options.inverse_links == Some(InverseLinks::Materialized) {
+                self.output.add_triple(TripleRef::new(
+                    attr_subject.as_ref(),
+                    ATTRIBUTE_OF,
+                    attr_parent.as_ref(),
+                ))?;
+            }
+
+            if self.options.type_triples || self.options.class_hierarchy {
+                let canonical = || format!("{}{}-{}", s.name, self.options.path_delimiter, key);
+                let attr_object =
+                    self.class_iri(&path, class_path.as_deref(), canonical, position)?;
+                self.write_class(&attr_subject, &attr_object, XML_ATTRIBUTE)?;
+            }
+
+            if let Some(value) = attribute_value(&attr.value, self.options) {
+                let attr_object =
+                    self.value_literal(value, &path, || format!("{}.-{}", s.name, key), position)?;
+
+                let triple = TripleRef::new(
+                    attr_subject.as_ref(),
+                    self.options.vocabulary.attribute_value.as_ref(),
+                    TermRef::Literal(attr_object.as_ref()),
+                );
+                self.output.add_triple(triple)?;
+                #[cfg(feature = "rdf-star")]
+                if self.options.value_annotations {
+                    let name = format!("{}.-{}", s.name, key);
+                    let typed_by = typed_by(&attr_object, &path, &name, self.options);
+                    write_value_annotations(&mut self.output, triple, position, typed_by)?;
+                }
+            } else {
+                self.warn(WarningKind::EmptyAttribute, &path, Some(position), || {
+                    empty_attribute_message(&attr)
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes `attributes` as properties of `s`, see [`ConversionOptions::property_style`].
+    fn write_attribute_properties(
+        &mut self,
+        s: &Node,
+        attributes: Vec<(usize, OwnedAttribute)>,
+        position: TextPosition,
+    ) -> Result<(), ConvertError> {
+        for (_, attr) in attributes {
+            let key = self.name_key(&attr.name);
+            let path = format!("{}.-{key}", s.path);
+            let Some(value) = attribute_value(&attr.value, self.options) else {
+                self.warn(WarningKind::EmptyAttribute, &path, Some(position), || {
+                    empty_attribute_message(&attr)
+                });
+                continue;
+            };
+            let predicate = match self.properties.get(&key) {
+                Some(predicate) => predicate.clone(),
+                None => {
+                    let predicate = mint_iri(format!("{X2R}-{key}"), &path, position)?;
+                    if self.options.owl_safe {
+                        self.output.add_triple(TripleRef::new(
+                            predicate.as_ref(),
+                            TYPE,
+                            OWL_DATATYPE_PROPERTY,
+                        ))?;
+                    }
+                    if self.options.embed_ontology {
+                        self.output.add_triple(TripleRef::new(
+                            predicate.as_ref(),
+                            SUB_PROPERTY_OF,
+                            self.options.vocabulary.attribute_value.as_ref(),
+                        ))?;
+                    }
+                    self.properties.insert(key.clone(), predicate.clone());
+                    predicate
+                }
+            };
+            let value =
+                self.value_literal(value, &path, || format!("{}.-{key}", s.name), position)?;
+            let triple = TripleRef::new(
+                s.id.as_ref(),
+                predicate.as_ref(),
+                TermRef::Literal(value.as_ref()),
+            );
+            self.output.add_triple(triple)?;
+            #[cfg(feature = "rdf-star")]
+            if self.options.value_annotations {
+                let name = format!("{}.-{key}", s.name);
+                let typed_by = typed_by(&value, &path, &name, self.options);
+                write_value_annotations(&mut self.output, triple, position, typed_by)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the predicate linking elements named `name` to their parent, see
+    /// [`ConversionOptions::child_predicates`], declaring it when first used.
+    fn child_predicate(
+        &mut self,
+        name: &str,
+        path: &str,
+        position: TextPosition,
+    ) -> Result<NamedNode, ConvertError> {
+        if let Some(predicate) = self.child_properties.get(name) {
+            return Ok(predicate.clone());
+        }
+        let predicate = mint_iri(format!("{X2R}has-{name}"), path, position)?;
+        if self.options.owl_safe {
+            self.output.add_triple(TripleRef::new(
+                predicate.as_ref(),
+                TYPE,
+                OWL_OBJECT_PROPERTY,
+            ))?;
+        }
+        if self.options.embed_ontology {
+            self.output.add_triple(TripleRef::new(
+                predicate.as_ref(),
+                SUB_PROPERTY_OF,
+                HAS_CHILD,
+            ))?;
+        }
+        self.child_properties
+            .insert(name.to_string(), predicate.clone());
+        Ok(predicate)
+    }
+
+    /// Returns the [`ConversionOptions::promoted_attributes`] entry of the element at `path` named
+    /// `name`, by path before name.
+    fn promotion(&self, path: &str, name: &str) -> Option<&'a PromotedAttribute> {
+        let promoted = &self.options.promoted_attributes;
+        if promoted.is_empty() {
+            return None;
+        }
+        let path = path.strip_prefix(X2R).unwrap_or(path);
+        promoted.get(path).or_else(|| promoted.get(name))
+    }
+
+    /// Removes the attribute to promote to the value of `s` from its `attributes`.
+    fn take_promoted(
+        &self,
+        s: &Node,
+        attributes: &mut Vec<(usize, OwnedAttribute)>,
+    ) -> Option<(usize, OwnedAttribute)> {
+        let promotion = self.promotion(&s.path, &s.name)?;
+        let at = attributes
+            .iter()
+            .position(|(_, attr)| self.name_key(&attr.name) == promotion.attribute)?;
+        Some(attributes.remove(at))
+    }
+
+    /// Writes the value of the promoted attribute `attr` as the value of `s`, with `predicate` or
+    /// else the predicate for element text.
+    fn write_promoted(
+        &mut self,
+        s: &Node,
+        attr: &OwnedAttribute,
+        predicate: Option<&NamedNode>,
+    ) -> Result<(), ConvertError> {
+        let key = self.name_key(&attr.name);
+        let path = format!("{}.-{key}", s.path);
+        let Some(value) = attribute_value(&attr.value, self.options) else {
+            self.warn(WarningKind::EmptyAttribute, &path, Some(s.position), || {
+                empty_attribute_message(attr)
+            });
+            return Ok(());
+        };
+        let value =
+            self.value_literal(value, &path, || format!("{}.-{key}", s.name), s.position)?;
+        let predicate = predicate.unwrap_or(&self.options.vocabulary.text);
+        let triple = TripleRef::new(
+            s.id.as_ref(),
+            predicate.as_ref(),
+            TermRef::Literal(value.as_ref()),
+        );
+        self.output.add_triple(triple)?;
+        #[cfg(feature = "rdf-star")]
+        if self.options.value_annotations {
+            let name = format!("{}.-{key}", s.name);
+            let typed_by = typed_by(&value, &path, &name, self.options);
+            write_value_annotations(&mut self.output, triple, s.position, typed_by)?;
+        }
+        Ok(())
+    }
+
+    /// Types the attribute or JSON node `node` with `class` and places `class` below `parent` in
+    /// the model hierarchy, each as far as [`ConversionOptions::type_triples`] and
+    /// [`ConversionOptions::class_hierarchy`] ask for.
+    fn write_class(
+        &mut self,
+        node: &NamedOrBlankNode,
+        class: &NamedNode,
+        parent: NamedNodeRef,
+    ) -> Result<(), ConvertError> {
+        if self.options.type_triples {
+            self.output
+                .add_triple(TripleRef::new(node.as_ref(), TYPE, class.as_ref()))?;
+        }
+        if self.options.class_hierarchy {
+            self.output
+                .add_triple(TripleRef::new(class.as_ref(), SUB_CLASS_OF, parent))?;
+        }
+        Ok(())
+    }
+
+    /// Writes the attributes held back on the innermost open element, unless they wait for its
+    /// promoted attribute.
+    fn write_deferred_attributes(&mut self) -> Result<(), ConvertError> {
+        match self.stack.pop() {
+            Some(node) if node.promoted.is_some() => {
+                self.stack.push(node);
+                Ok(())
+            }
+            Some(mut node) => {
+                let attributes = std::mem::take(&mut node.attributes);
+                let result = self.write_attributes(&node, attributes, node.position);
+                self.stack.push(node);
+                result
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Closes the innermost open element, writing its text and any held back attributes.
+    fn end_element(&mut self) -> Result<(), ConvertError> {
+        let Some(mut s) = self.stack.pop() else {
+            return Ok(());
+        };
+        close_run(&mut s, self.options);
+        if self.options.branch_text == BranchTextPolicy::DropWhenHasChildren && s.children > 0 {
+            s.text.clear();
+            s.runs.clear();
+        }
+        if self.options.empty_markers {
+            self.write_empty_marker(&s)?;
+        }
+        if self.options.element_kinds {
+            let kind = if s.children > 0 {
+                BRANCH_ELEMENT
+            } else {
+                LEAF_ELEMENT
+            };
+            self.output
+                .add_triple(TripleRef::new(s.id.as_ref(), TYPE, kind))?;
+        }
+        self.write_flag(&s)?;
+        let mut attributes = std::mem::take(&mut s.attributes);
+        if let Some((index, attr)) = s.promoted.take() {
+            let predicate = self
+                .promotion(&s.path, &s.name)
+                .and_then(|p| p.predicate.as_ref());
+            if predicate.is_none() && !s.text.is_empty() {
+                // The text stays the value, so the attribute is an attribute node after all
+                let at = attributes.partition_point(|(i, _)| *i < index);
+                attributes.insert(at, (index, attr));
+            } else {
+                self.write_promoted(&s, &attr, predicate)?;
+            }
+        }
+        if let Some((mode, json)) = self.embedded_json(&s) {
+            self.write_attributes(&s, attributes, s.position)?;
+            return self.write_embedded_json(&mut s, mode, json);
+        }
+        if self.options.json_leaves
+            && s.children == 0
+            && !s.text.is_empty()
+            && attributes
+                .iter()
+                .any(|(_, attr)| attribute_value(&attr.value, self.options).is_some())
+        {
+            for (_, attr) in &attributes {
+                if attribute_value(&attr.value, self.options).is_none() {
+                    let path = format!("{}.-{}", s.path, self.name_key(&attr.name));
+                    self.warn(WarningKind::EmptyAttribute, &path, Some(s.position), || {
+                        empty_attribute_message(attr)
+                    });
+                }
+            }
+            let json =
+                Literal::new_typed_literal(json_leaf(&s, &attributes, self.options), RDF_JSON);
+            self.output.add_triple(TripleRef::new(
+                s.id.as_ref(),
+                self.options.vocabulary.text.as_ref(),
+                TermRef::Literal(json.as_ref()),
+            ))?;
+            return Ok(());
+        }
+        self.write_attributes(&s, attributes, s.position)?;
+        if self.options.text_as_node {
+            self.write_text_nodes(&mut s)?;
+        } else {
+            self.write_values(&s)?;
+        }
+        if self.options.branch_text == BranchTextPolicy::Mixed {
+            self.write_content(&s)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the text runs of `s` as text nodes, as described for
+    /// [`ConversionOptions::text_as_node`].
+    fn write_text_nodes(&mut self, s: &mut Node<'a>) -> Result<(), ConvertError> {
+        for (i, (sibling, run)) in std::mem::take(&mut s.runs).into_iter().enumerate() {
+            let id = self.mint_node(
+                s.data_namespace,
+                &s.path,
+                || format!("{}/t{i}", s.key),
+                s.position,
+            )?;
+            self.write_index(&id, SIBLING_INDEX, sibling)?;
+            self.output
+                .add_triple(TripleRef::new(s.id.as_ref(), HAS_CHILD, id.as_ref()))?;
+            if self.options.inverse_links == Some(InverseLinks::Materialized) {
+                self.output
+                    .add_triple(TripleRef::new(id.as_ref(), HAS_PARENT, s.id.as_ref()))?;
+            }
+            self.write_in_document(&id)?;
+            self.output
+                .add_triple(TripleRef::new(id.as_ref(), TYPE, TEXT_NODE))?;
+            // The run is written like the whole text of an element, as the text node's value
+            let element = std::mem::replace(&mut s.id, id);
+            let text = std::mem::replace(&mut s.text, vec![run]);
+            let written = self.write_values(s);
+            s.id = element;
+            s.text = text;
+            written?;
+        }
+        Ok(())
+    }
+
+    /// Writes `isNil` if `s` is declared nil, or `isEmpty` if it has no content, as described for
+    /// [`ConversionOptions::empty_markers`].
+    fn write_empty_marker(&mut self, s: &Node) -> Result<(), ConvertError> {
+        let marker = if s.nil {
+            IS_NIL
+        } else if s.children == 0 && s.text.is_empty() {
+            IS_EMPTY
+        } else {
+            return Ok(());
+        };
+        let yes = Literal::from(true);
+        self.output.add_triple(TripleRef::new(
+            s.id.as_ref(),
+            marker,
+            TermRef::Literal(yes.as_ref()),
+        ))?;
+        Ok(())
+    }
+
+    /// Flags the parent of `s` with `true` if `s` is an empty element listed in
+    /// [`ConversionOptions::flag_elements`].
+    fn write_flag(&mut self, s: &Node) -> Result<(), ConvertError> {
+        let flags = &self.options.flag_elements;
+        if flags.is_empty() || s.nil || s.children > 0 || !s.text.is_empty() {
+            return Ok(());
+        }
+        let path = s.path.strip_prefix(X2R).unwrap_or(&s.path);
+        let Some(predicate) = flags.get(path).or_else(|| flags.get(&s.name)) else {
+            return Ok(());
+        };
+        let Some(parent) = self.stack.last() else {
+            return Ok(());
+        };
+        let yes = Literal::from(true);
+        self.output.add_triple(TripleRef::new(
+            parent.id.as_ref(),
+            predicate.as_ref(),
+            TermRef::Literal(yes.as_ref()),
+        ))?;
+        Ok(())
+    }
+
+    /// Returns the [`ConversionOptions::embedded_json`] mode of `s` and its text parsed as JSON,
+    /// if it is listed and its text is a JSON object or array.
+    fn embedded_json(&self, s: &Node) -> Option<(EmbeddedJson, json::Value)> {
+        let modes = &self.options.embedded_json;
+        if modes.is_empty() || s.text.is_empty() {
+            return None;
+        }
+        let path = s.path.strip_prefix(X2R).unwrap_or(&s.path);
+        let mode = *modes.get(path).or_else(|| modes.get(&s.name))?;
+        Some((mode, json::parse(&s.text.concat())?))
+    }
+
+    /// Writes the JSON `value` embedded in the text of `s` as its `mode` says.
+    fn write_embedded_json(
+        &mut self,
+        s: &mut Node<'a>,
+        mode: EmbeddedJson,
+        value: json::Value,
+    ) -> Result<(), ConvertError> {
+        match mode {
+            EmbeddedJson::Literal => {
+                let json = Literal::new_typed_literal(s.text.concat().trim(), RDF_JSON);
+                self.output.add_triple(TripleRef::new(
+                    s.id.as_ref(),
+                    self.options.vocabulary.text.as_ref(),
+                    TermRef::Literal(json.as_ref()),
+                ))?;
+            }
+            EmbeddedJson::Expand => match value {
+                json::Value::Object(members) => {
+                    for (key, value) in &members {
+                        self.write_json(s, key, value)?;
+                    }
+                }
+                array => self.write_json(s, "item", &array)?,
+            },
+        }
+        Ok(())
+    }
+
+    /// Writes the JSON `value` of the member or array item `name` below `parent`, as described
+    /// for [`EmbeddedJson::Expand`].
+    fn write_json(
+        &mut self,
+        parent: &mut Node<'a>,
+        name: &str,
+        value: &json::Value,
+    ) -> Result<(), ConvertError> {
+        match value {
+            json::Value::Array(items) => {
+                for item in items {
+                    if let json::Value::Array(_) = item {
+                        let mut node = self.write_json_node(parent, name)?;
+                        self.write_json(&mut node, "item", item)?;
+                    } else {
+                        self.write_json(parent, name, item)?;
+                    }
+                }
+            }
+            json::Value::Object(members) => {
+                let mut node = self.write_json_node(parent, name)?;
+                for (key, value) in members {
+                    self.write_json(&mut node, key, value)?;
+                }
+            }
+            scalar => {
+                let node = self.write_json_node(parent, name)?;
+                if let Some(literal) = scalar.literal() {
+                    self.output.add_triple(TripleRef::new(
+                        node.id.as_ref(),
+                        self.options.vocabulary.text.as_ref(),
+                        TermRef::Literal(literal.as_ref()),
+                    ))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Encodes the JSON key `name` of a child of `parent` for class IRIs and paths, as
+    /// [`ConversionOptions::name_encoding`] says.
+    fn encode_name(&mut self, name: &str, parent: &Node) -> Result<String, ConvertError> {
+        // Keys may hold any character, including the path delimiter
+        let safe = |c: char| c.is_alphanumeric() || matches!(c, '-' | '_' | '~');
+        match self.options.name_encoding {
+            NameEncoding::Percent => {
+                let mut key = String::new();
+                percent_encode(&mut key, name, b"-_~");
+                Ok(key)
+            }
+            NameEncoding::Reject if name.chars().all(safe) => Ok(name.to_string()),
+            NameEncoding::Reject => Err(ConvertError::UnsafeName {
+                source: None,
+                path: parent
+                    .path
+                    .strip_prefix(X2R)
+                    .unwrap_or(&parent.path)
+                    .to_string(),
+                name: name.to_string(),
+                position: parent.position,
+            }),
+            NameEncoding::Slug => {
+                if let Some(slug) = self.slugs.get(name) {
+                    return Ok(slug.clone());
+                }
+                let mut base = String::new();
+                for c in name.chars() {
+                    if safe(c) {
+                        base.push(c);
+                    } else if !base.ends_with('-') {
+                        base.push('-');
+                    }
+                }
+                let mut slug = base.clone();
+                let mut n = 1;
+                while self.taken_slugs.contains(&slug) {
+                    n += 1;
+                    slug = format!("{base}-{n}");
+                }
+                self.taken_slugs.insert(slug.clone());
+                self.slugs.insert(name.to_string(), slug.clone());
+                Ok(slug)
+            }
+        }
+    }
+
+    /// Mints and links the node of a JSON member or array item `name` below `parent`.
+    fn write_json_node(
+        &mut self,
+        parent: &mut Node<'a>,
+        name: &str,
+    ) -> Result<Node<'a>, ConvertError> {
+        let index = parent.children;
+        parent.children += 1;
+        let key = self.encode_name(name, parent)?;
+        let path = format!("{}.{key}", parent.path);
+        let class_path = parent
+            .class_path
+            .as_ref()
+            .map(|p| format!("{p}{}{key}", self.options.path_delimiter));
+        let node_key = if matches!(self.options.node_ids, NodeIds::Deterministic { .. }) {
+            format!("{}/j{index}", parent.key)
+        } else {
+            String::new()
+        };
+        let position = parent.position;
+        let id = self.mint_node(parent.data_namespace, &path, || node_key.clone(), position)?;
+        self.write_index(&id, SIBLING_INDEX, index)?;
+
+        self.output
+            .add_triple(TripleRef::new(parent.id.as_ref(), HAS_CHILD, id.as_ref()))?;
+        if self.options.inverse_links == Some(InverseLinks::Materialized) {
+            self.output
+                .add_triple(TripleRef::new(id.as_ref(), HAS_PARENT, parent.id.as_ref()))?;
+        }
+        self.write_in_document(&id)?;
+        if self.options.type_triples || self.options.class_hierarchy {
+            let class = self.class_iri(&path, class_path.as_deref(), || key.clone(), position)?;
+            if self.options.type_triples {
+                self.output
+                    .add_triple(TripleRef::new(id.as_ref(), TYPE, class.as_ref()))?;
+            }
+            if self.options.class_hierarchy && self.json_classes.insert(class.clone()) {
+                self.output
+                    .add_triple(TripleRef::new(class.as_ref(), SUB_CLASS_OF, JSON_VALUE))?;
+            }
+        }
+        let name = Literal::new_simple_literal(name);
+        self.output.add_triple(TripleRef::new(
+            id.as_ref(),
+            HAS_NAME,
+            TermRef::Literal(name.as_ref()),
+        ))?;
+
+        Ok(Node {
+            path,
+            id,
+            name: key,
+            text: Vec::new(),
+            children: 0,
+            key: node_key,
+            namespace: None,
+            attributes: Vec::new(),
+            promoted: None,
+            content: Vec::new(),
+            runs: Vec::new(),
+            position,
+            data_namespace: parent.data_namespace,
+            class_path,
+            nil: false,
+        })
+    }
+
+    /// Writes the `hasContent` list of `s` if it has mixed content.
+    fn write_content(&mut self, s: &Node) -> Result<(), ConvertError> {
+        let mut has_child = false;
+        let mut has_text = false;
+        for item in &s.content {
+            match item {
+                Content::Element(_) => has_child = true,
+                Content::Text(text) => has_text |= !text.trim().is_empty(),
+            }
+        }
+        if !has_child || !has_text {
+            return Ok(());
+        }
+
+        let items: Vec<Term> = s
+            .content
+            .iter()
+            .map(|item| match item {
+                Content::Text(text) => Literal::new_simple_literal(text).into(),
+                Content::Element(id) => id.clone().into(),
+            })
+            .collect();
+        let list = write_list(&mut self.output, &items)?;
+        self.output
+            .add_triple(TripleRef::new(s.id.as_ref(), HAS_CONTENT, list.as_ref()))?;
+        Ok(())
+    }
+
+    /// Returns the key of an element or attribute name within class IRIs: the local name, or with
+    /// [`ConversionOptions::preserve_namespaces`] the percent-encoded `{namespace}local` Clark
+    /// notation for names in a namespace.
+    ///
+    /// Only class IRIs and paths use the key; `hasName` always carries the local name as written.
+    fn name_key(&self, name: &OwnedName) -> String {
+        match name.namespace.as_deref() {
+            Some(ns) if self.options.preserve_namespaces && !ns.is_empty() => {
+                let mut key = String::from("%7B");
+                percent_encode(&mut key, ns, b"");
+                key.push_str("%7D");
+                key.push_str(&name.local_name);
+                key
+            }
+            _ => name.local_name.clone(),
+        }
+    }
+
+    /// Writes a `declaresNamespace` node for each prefix mapping of `namespace` that is new or
+    /// changed compared to the enclosing element, i.e. declared by the element `s` itself.
+    fn write_namespace_decls(
+        &mut self,
+        s: &Node,
+        namespace: &Namespace,
+    ) -> Result<(), ConvertError> {
+        let root = NamespaceStack::default().squash();
+        let parent = match self.stack.last().and_then(|p| p.namespace.as_ref()) {
+            Some(parent) => parent,
+            None => &root,
+        };
+        for (prefix, uri) in namespace {
+            if parent.get(prefix) == Some(uri) {
+                continue;
+            }
+            let declaration = BlankNode::default();
+            self.output.add_triple(TripleRef::new(
+                s.id.as_ref(),
+                DECLARES_NAMESPACE,
+                declaration.as_ref(),
+            ))?;
+            let prefix = Literal::new_simple_literal(prefix);
+            self.output.add_triple(TripleRef::new(
+                declaration.as_ref(),
+                NAMESPACE_PREFIX,
+                TermRef::Literal(prefix.as_ref()),
+            ))?;
+            let uri = Literal::new_simple_literal(uri);
+            self.output.add_triple(TripleRef::new(
+                declaration.as_ref(),
+                NAMESPACE_URI,
+                TermRef::Literal(uri.as_ref()),
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Records the document order `index` of an element or attribute node in the reconstruction
+    /// graph, if one is configured.
+    fn write_index(
+        &mut self,
+        subject: &NamedOrBlankNode,
+        predicate: NamedNodeRef,
+        index: usize,
+    ) -> Result<(), ConvertError> {
+        if let Some(graph) = &self.options.reconstruction_graph {
+            let index = Literal::from(index as u64);
+            self.output.add_quad(QuadRef::new(
+                subject.as_ref(),
+                predicate,
+                index.as_ref(),
+                graph.as_ref(),
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Writes the document node for the current document, linking it to its `root` element, if
+    /// it has one, and to the file it was read from.
+    fn write_document(
+        &mut self,
+        root: Option<&Node>,
+        position: TextPosition,
+    ) -> Result<(), ConvertError> {
+        let path = root.map(|root| root.path.as_str()).unwrap_or_default();
+        let document = match &self.options.document_iri {
+            DocumentIri::Minted => {
+                let key = self.document_key();
+                self.mint_node(self.namespace, path, || key, position)?
+            }
+            DocumentIri::FileName => {
+                let Some(source) = &self.source else {
+                    return Err(ConvertError::InvalidDocumentIri {
+                        source: None,
+                        iri: None,
+                    });
+                };
+                let iri = source.file_name_iri(self.namespace);
+                self.name_document(mint_iri(iri, path, position)?)?
+            }
+            DocumentIri::Explicit(iri) => self.name_document(iri.clone())?,
+        };
+        self.output
+            .add_triple(TripleRef::new(document.as_ref(), TYPE, XML_DOCUMENT))?;
+        self.document = Some(document.clone());
+        if let Some(source) = &self.source {
+            if self.options.prov {
+                let source = mint_iri(source.iri(&self.options.source_iris), path, position)?;
+                self.output.add_triple(TripleRef::new(
+                    document.as_ref(),
+                    PROV_WAS_DERIVED_FROM,
+                    source.as_ref(),
+                ))?;
+            } else {
+                let source = Literal::new_simple_literal(source.iri(&self.options.source_iris));
+                self.output.add_triple(TripleRef::new(
+                    document.as_ref(),
+                    SOURCE_FILE,
+                    TermRef::Literal(source.as_ref()),
+                ))?;
+            }
+        }
+        if let Some(time) = &self.converted_at {
+            let predicate = if self.options.prov {
+                PROV_GENERATED_AT_TIME
+            } else {
+                CONVERTED_AT
+            };
+            self.output.add_triple(TripleRef::new(
+                document.as_ref(),
+                predicate,
+                TermRef::Literal(time.as_ref()),
+            ))?;
+        }
+        if let Some(root) = root {
+            self.output.add_triple(TripleRef::new(
+                document.as_ref(),
+                HAS_ROOT,
+                root.id.as_ref(),
+            ))?;
+        }
+        if self.options.record_options {
+            let run = match &self.run {
+                Some(run) => run.clone(),
+                None => {
+                    // Runs recorded alike share a deterministic IRI, others never do
+                    let mut key = "x2r:run".to_string();
+                    for (predicate, value) in self.run_values() {
+                        key.push_str(&format!(" {predicate}={value:?}"));
+                    }
+                    let run = self.mint_node(self.namespace, path, || key, position)?;
+                    self.run.insert(run).clone()
+                }
+            };
+            self.output.add_triple(TripleRef::new(
+                document.as_ref(),
+                CONVERTED_BY,
+                run.as_ref(),
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the description of the conversion run, see [`ConversionOptions::record_options`].
+    fn run_values(&self) -> Vec<(NamedNodeRef<'static>, String)> {
+        let features = [
+            ("canonical", cfg!(feature = "canonical")),
+            ("compress", cfg!(feature = "compress")),
+            ("ctrlc", cfg!(feature = "ctrlc")),
+            ("digest", cfg!(feature = "digest")),
+            ("metrics", cfg!(feature = "metrics")),
+            ("oxigraph", cfg!(feature = "oxigraph")),
+            ("rdf-star", cfg!(feature = "rdf-star")),
+            ("syslog", cfg!(feature = "syslog")),
+            ("zip", cfg!(feature = "zip")),
+        ];
+        [
+            (TOOL_VERSION, env!("CARGO_PKG_VERSION").to_string()),
+            (DATA_NAMESPACE, self.namespace.to_string()),
+            (NODE_IDS, self.options.node_ids.name().to_string()),
+            (CLASS_KEY, value_name(self.options.class_key)),
+        ]
+        .into_iter()
+        .chain(match self.options.node_ids {
+            NodeIds::Deterministic { hash } => Some((NODE_ID_HASH, value_name(hash))),
+            _ => None,
+        })
+        .chain(
+            features
+                .into_iter()
+                .filter(|(_, enabled)| *enabled)
+                .map(|(feature, _)| (ENABLED_FEATURE, feature.to_string())),
+        )
+        .collect()
+    }
+
+    /// Describes the conversion run `run`, see [`ConversionOptions::record_options`].
+    fn write_run(&mut self, run: &NamedOrBlankNode) -> Result<(), ConvertError> {
+        self.output
+            .add_triple(TripleRef::new(run.as_ref(), TYPE, CONVERSION_RUN))?;
+        for (predicate, value) in self.run_values() {
+            let value = Literal::new_simple_literal(value);
+            self.output.add_triple(TripleRef::new(
+                run.as_ref(),
+                predicate,
+                TermRef::Literal(value.as_ref()),
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the named document node `iri`, unless another document already has it.
+    fn name_document(&mut self, iri: NamedNode) -> Result<NamedOrBlankNode, ConvertError> {
+        if !self.document_iris.insert(iri.clone()) {
+            return Err(ConvertError::InvalidDocumentIri {
+                source: None,
+                iri: Some(iri.into_string()),
+            });
+        }
+        Ok(iri.into())
+    }
+
+    /// Emits the `hasValue` triples for the text collected on `node` according to the
+    /// multi-value policy and trimming options.
+    fn write_values(&mut self, node: &Node) -> Result<(), ConvertError> {
+        if node.text.is_empty() {
+            return Ok(());
+        }
+
+        let options = self.options;
+        let policy = options.multi_value_policy;
+        let mut literal = |text: &str| {
+            let text = if options.trim_text { text.trim() } else { text };
+            self.value_literal(text, &node.path, || node.name.clone(), node.position)
+        };
+        let splits = &options.split_values;
+        let delimiter = if splits.is_empty() {
+            None
+        } else {
+            let path = node.path.strip_prefix(X2R).unwrap_or(&node.path);
+            splits.get(path).or_else(|| splits.get(&node.name))
+        };
+        let values: Vec<Literal> = if let Some(delimiter) = delimiter {
+            let text = node.text.concat();
+            let values = text
+                .split(delimiter.as_str())
+                .filter(|piece| {
+                    let piece = if options.trim_text {
+                        piece.trim()
+                    } else {
+                        piece
+                    };
+                    !piece.is_empty()
+                })
+                .map(&mut literal)
+                .collect::<Result<Vec<_>, _>>()?;
+            if values.is_empty() {
+                return Ok(());
+            }
+            values
+        } else {
+            match policy {
+                MultiValuePolicy::Concatenate => vec![literal(&node.text.concat())?],
+                MultiValuePolicy::FirstOnly => vec![literal(&node.text[0])?],
+                MultiValuePolicy::AllowMultiple | MultiValuePolicy::List => node
+                    .text
+                    .iter()
+                    .map(|t| literal(t))
+                    .collect::<Result<_, _>>()?,
+            }
+        };
+
+        if policy != MultiValuePolicy::List {
+            for value in values.iter() {
+                let triple = TripleRef::new(
+                    node.id.as_ref(),
+                    options.vocabulary.text.as_ref(),
+                    TermRef::Literal(value.as_ref()),
+                );
+                self.output.add_triple(triple)?;
+                #[cfg(feature = "rdf-star")]
+                if options.value_annotations {
+                    let typed_by = typed_by(value, &node.path, &node.name, options);
+                    write_value_annotations(&mut self.output, triple, node.position, typed_by)?;
+                }
+            }
+            return Ok(());
+        }
+
+        let values: Vec<Term> = values.into_iter().map(Term::from).collect();
+        let list = write_list(&mut self.output, &values)?;
+        self.output.add_triple(TripleRef::new(
+            node.id.as_ref(),
+            options.vocabulary.text.as_ref(),
+            list.as_ref(),
+        ))?;
+        Ok(())
+    }
+
+    /// Creates the literal for a value, see [`value_literal`]. Under [`BinaryPolicy::Warn`],
+    /// content not valid in its binary encoding is kept as a plain literal with a warning.
+    fn value_literal(
+        &mut self,
+        value: &str,
+        path: &str,
+        name: impl FnOnce() -> String,
+        position: TextPosition,
+    ) -> Result<Literal, ConvertError> {
+        match value_literal(value, path, name, position, self.options) {
+            Err(ConvertError::InvalidBinary { path, encoding, .. })
+                if self.options.invalid_binary == BinaryPolicy::Warn =>
+            {
+                self.warn(WarningKind::InvalidBinary, &path, Some(position), || {
+                    format!("invalid {encoding} content in '{path}' kept as a plain literal")
+                });
+                Ok(Literal::new_simple_literal(value))
+            }
+            result => result,
+        }
+    }
+
+    /// Records a warning about the element or attribute at `path`, if
+    /// [`ConversionOptions::collect_warnings`] is set.
+    fn warn(
+        &mut self,
+        kind: WarningKind,
+        path: &str,
+        position: Option<TextPosition>,
+        message: impl FnOnce() -> String,
+    ) {
+        if !self.options.collect_warnings {
+            return;
+        }
+        self.warnings.push(Warning {
+            kind,
+            path: path.strip_prefix(X2R).unwrap_or(path).to_string(),
+            source: self.source.as_ref().map(Source::label),
+            position,
+            message: message(),
+        });
+    }
+
+    /// Links `node` to the current document node, if [`ConversionOptions::link_document`] is
+    /// set.
+    fn write_in_document(&mut self, node: &NamedOrBlankNode) -> Result<(), ConvertError> {
+        if !self.options.link_document {
+            return Ok(());
+        }
+        if let Some(document) = &self.document {
+            self.output.add_triple(TripleRef::new(
+                node.as_ref(),
+                IN_DOCUMENT,
+                document.as_ref(),
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Mints a node for the element or attribute at `path`, as configured by
+    /// [`ConversionOptions::node_ids`]. `key` gives the stable position of the node used by
+    /// [`NodeIds::Deterministic`].
+    ///
+    /// A UUID suffix cannot make a valid namespace invalid, so only the first IRI is validated.
+    fn mint_node(
+        &mut self,
+        namespace: &'a str,
+        path: &str,
+        key: impl FnOnce() -> String,
+        position: TextPosition,
+    ) -> Result<NamedOrBlankNode, ConvertError> {
+        let uuid = match self.options.node_ids {
+            NodeIds::Blank => return Ok(BlankNode::default().into()),
+            NodeIds::Uuid => Uuid::new_v4(),
+            NodeIds::Deterministic { hash } => hash.uuid(&key()),
+            NodeIds::Urn => {
+                let mut buf = Uuid::encode_buffer();
+                let id = Uuid::new_v4().hyphenated().encode_lower(&mut buf);
+                return Ok(NamedNode::new_unchecked(format!("urn:x2r:{id}")).into());
+            }
+        };
+        let mut buf = Uuid::encode_buffer();
+        let id = uuid.hyphenated().encode_lower(&mut buf);
+        let mut iri = String::with_capacity(namespace.len() + 1 + id.len());
+        iri.push_str(namespace);
+        iri.push('/');
+        iri.push_str(id);
+        if self.checked_namespaces.contains(&namespace) {
+            return Ok(NamedNode::new_unchecked(iri).into());
+        }
+        let node = mint_iri(iri, path, position)?;
+        self.checked_namespaces.push(namespace);
+        Ok(node.into())
+    }
+
+    /// Returns the stable key of the document being converted: its source label, or an empty
+    /// string for documents not read from a file.
+    fn document_key(&self) -> String {
+        self.source.as_ref().map(Source::label).unwrap_or_default()
+    }
+
+    /// Returns the path of a new element named `key` as written in class IRIs, joined with
+    /// [`ConversionOptions::path_delimiter`], or `None` under the default `.` delimiter, where it
+    /// is the element path itself.
+    fn class_path(&self, key: &str) -> Option<String> {
+        let delimiter = &self.options.path_delimiter;
+        if delimiter == "." {
+            return None;
+        }
+        Some(match self.stack.last() {
+            Some(parent) => format!("{}{delimiter}{key}", parent.class_path.as_ref()?),
+            None => format!("{X2R}{key}"),
+        })
+    }
+
+    /// Returns the class of an element or attribute node given its full `path`, the
+    /// `class_path` it is written as in class IRIs if that differs, and the `canonical`
+    /// name-based key used by [`ClassKey::Name`].
+    ///
+    /// Under [`ClassKey::Name`] the path class is declared `owl:equivalentClass` to the canonical
+    /// class the first time it is seen, so the structural path stays discoverable. With
+    /// [`ConversionOptions::owl_safe`] the classes are declared `owl:Class` then too.
+    fn class_iri(
+        &mut self,
+        path: &str,
+        class_path: Option<&str>,
+        canonical: impl FnOnce() -> String,
+        position: TextPosition,
+    ) -> Result<NamedNode, ConvertError> {
+        if let Some(class) = self.classes.get(path) {
+            return Ok(class.clone());
+        }
+        let path_class = mint_iri(class_path.unwrap_or(path).to_string(), path, position)?;
+        if self.options.owl_safe {
+            self.output
+                .add_triple(TripleRef::new(path_class.as_ref(), TYPE, OWL_CLASS))?;
+        }
+        let class = match self.options.class_key {
+            ClassKey::Path => path_class,
+            ClassKey::Name => {
+                let class = mint_iri(format!("{X2R}{}", canonical()), path, position)?;
+                if path_class != class {
+                    self.output.add_triple(TripleRef::new(
+                        path_class.as_ref(),
+                        OWL_EQUIVALENT_CLASS,
+                        class.as_ref(),
+                    ))?;
+                    if self.options.owl_safe {
+                        self.output
+                            .add_triple(TripleRef::new(class.as_ref(), TYPE, OWL_CLASS))?;
+                    }
+                }
+                class
+            }
+        };
+        self.classes.insert(path.to_string(), class.clone());
+        Ok(class)
+    }
+}
+
+/// Returns the `file://` IRI of `path`, resolved against the working directory, and to its real
+/// location where it exists, see [`absolute_file_iri`].
+fn file_iri(path: &Path) -> String {
+    let path = std::fs::canonicalize(path)
+        .or_else(|_| std::path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf());
+    absolute_file_iri(&path.to_string_lossy())
+}
+
+/// Returns the `file://` IRI of an absolute path in Unix or Windows form, with every byte outside
+/// the unreserved set, `/` and `:` percent-encoded:
+///
+/// ```
+/// use xml2rdf::convert::absolute_file_iri;
+///
+/// assert_eq!(absolute_file_iri("/data/a b.xml"), "file:///data/a%20b.xml");
+/// assert_eq!(absolute_file_iri(r"C:\data\a.xml"), "file:///C:/data/a.xml");
+/// assert_eq!(
+///     absolute_file_iri(r"\\server\share\a.xml"),
+///     "file://server/share/a.xml"
+/// );
+/// ```
+///
+/// Backslashes separate the steps of Windows paths only: drive paths such as `C:\data`, UNC
+/// paths naming a server such as `\\server\share`, and the verbatim forms of both returned by
+/// [`std::fs::canonicalize`] on Windows, `\\?\C:\data` and `\\?\UNC\server\share`. In
+/// Unix paths they are part of a file name, and percent-encoded.
+pub fn absolute_file_iri(path: &str) -> String {
+    let path = match path.strip_prefix(r"\\?\") {
+        Some(verbatim) => match verbatim.strip_prefix(r"UNC\") {
+            Some(unc) => format!(r"\\{unc}"),
+            None => verbatim.to_string(),
+        },
+        None => path.to_string(),
+    };
+    let mut iri = String::from("file://");
+    if let Some(unc) = path.strip_prefix(r"\\") {
+        // The server becomes the host of the IRI
+        let (host, rest) = unc.split_once('\\').unwrap_or((unc, ""));
+        percent_encode(&mut iri, host, b"-._~");
+        iri.push('/');
+        percent_encode(&mut iri, &rest.replace('\\', "/"), b"-._~/:");
+    } else if is_drive_path(&path) {
+        iri.push('/');
+        percent_encode(&mut iri, &path.replace('\\', "/"), b"-._~/:");
+    } else {
+        if !path.starts_with('/') {
+            iri.push('/');
+        }
+        percent_encode(&mut iri, &path, b"-._~/:");
+    }
+    iri
+}
+
+/// Returns whether `path` starts with a Windows drive letter, as in `C:\data` or `C:/data`.
+fn is_drive_path(path: &str) -> bool {
+    match path.as_bytes() {
+        [letter, b':'] => letter.is_ascii_alphabetic(),
+        [letter, b':', separator, ..] => {
+            letter.is_ascii_alphabetic() && matches!(separator, b'\\' | b'/')
+        }
+        _ => false,
+    }
+}
+
+/// Returns `base` followed by `path` relative to the working directory, or by its file name if
+/// it lies elsewhere, with every byte outside the unreserved set and `/` percent-encoded.
+fn relative_iri(base: &str, path: &Path) -> String {
+    let absolute = std::fs::canonicalize(path)
+        .or_else(|_| std::path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf());
+    let relative = std::env::current_dir()
+        .and_then(std::fs::canonicalize)
+        .ok()
+        .and_then(|dir| absolute.strip_prefix(dir).ok().map(Path::to_path_buf))
+        .or_else(|| absolute.file_name().map(Into::into))
+        .unwrap_or_default();
+    let mut iri = base.to_string();
+    percent_encode(
+        &mut iri,
+        &relative.to_string_lossy().replace('\\', "/"),
+        b"-._~/",
+    );
+    iri
+}
+
+/// Returns whether the data namespace `namespace` lies within the model namespace [`X2R`], or
+/// names the model namespace itself without its trailing `#`, so that node IRIs minted under it
+/// could be mistaken for, or collide with, model terms.
+fn overlaps_model(namespace: &str) -> bool {
+    let model = X2R.trim_end_matches('#');
+    namespace.starts_with(X2R) || namespace.trim_end_matches(['/', '#']) == model
+}
+
+/// Returns whether `attr` is `xsi:nil` declaring its element nil, i.e. `nil` in the XML Schema
+/// instance namespace with the value `true` or `1`.
+fn is_xsi_nil(attr: &OwnedAttribute) -> bool {
+    attr.name.local_name == "nil"
+        && attr.name.namespace.as_deref() == Some(XSI)
+        && matches!(attr.value.trim(), "true" | "1")
+}
+
+/// Returns whether the file name `path` ends in `.{extension}`, ignoring ASCII case.
+fn has_extension(path: &str, extension: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case(extension))
+}
+
+/// Appends `s` to `out`, percent-encoding every byte other than ASCII letters, digits and `keep`.
+fn percent_encode(out: &mut String, s: &str, keep: &[u8]) {
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || keep.contains(&b) {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+}
+
+/// Creates the literal for element text or an attribute value, typed by
+/// [`crate::datatype::infer`] when [`ConversionOptions::infer_datatypes`] is set.
+fn infer_literal(text: &str, options: &ConversionOptions) -> Literal {
+    match options
+        .infer_datatypes
+        .then(|| datatype::infer(text))
+        .flatten()
+    {
+        Some(datatype) => Literal::new_typed_literal(text, datatype),
+        None => Literal::new_simple_literal(text),
+    }
+}
+
+/// Returns the command line name of `value`.
+fn value_name(value: impl clap::ValueEnum) -> String {
+    value
+        .to_possible_value()
+        .map_or_else(String::new, |v| v.get_name().to_string())
+}
+
+/// Returns the value of an attribute as written, trimmed as [`ConversionOptions::trim_text`]
+/// says, or `None` if it counts as empty: if it is empty, or whitespace-only without
+/// [`ConversionOptions::emit_whitespace_only`], as element text.
+fn attribute_value<'v>(value: &'v str, options: &ConversionOptions) -> Option<&'v str> {
+    if value.is_empty() || (value.trim().is_empty() && !options.emit_whitespace_only) {
+        return None;
+    }
+    Some(if options.trim_text {
+        value.trim()
+    } else {
+        value
+    })
+}
+
+/// Returns the [`WarningKind::EmptyAttribute`] message for `attr`.
+fn empty_attribute_message(attr: &OwnedAttribute) -> String {
+    if attr.value.is_empty() {
+        format!("attribute '{}' has an empty value", attr.name)
+    } else {
+        format!("attribute '{}' has a whitespace-only value", attr.name)
+    }
+}
+
+/// Returns the JSON object representing the leaf element `node` with its `attributes`, as
+/// described for [`ConversionOptions::json_leaves`].
+fn json_leaf(
+    node: &Node,
+    attributes: &[(usize, OwnedAttribute)],
+    options: &ConversionOptions,
+) -> String {
+    let text = node.text.concat();
+    let text = if options.trim_text {
+        text.trim()
+    } else {
+        &text
+    };
+    let mut json = String::from("{\"@value\":");
+    push_json_string(&mut json, text);
+    for (_, attr) in attributes {
+        let Some(value) = attribute_value(&attr.value, options) else {
+            continue;
+        };
+        json.push(',');
+        push_json_string(&mut json, &attr.name.borrow().to_repr());
+        json.push(':');
+        push_json_string(&mut json, value);
+    }
+    json.push('}');
+    json
+}
+
+/// Appends `s` to `out` as a JSON string, escaping quotes, backslashes and control characters.
+pub(crate) fn push_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Creates the literal for element text or an attribute value of the node at `path`, whose
+/// name-based key is `name`: typed by its [`ConversionOptions::binary`] encoding if it has
+/// one, otherwise by its valid [`ConversionOptions::datatypes`] hint, otherwise as by
+/// [`infer_literal`].
+fn value_literal(
+    value: &str,
+    path: &str,
+    name: impl FnOnce() -> String,
+    position: TextPosition,
+    options: &ConversionOptions,
+) -> Result<Literal, ConvertError> {
+    if options.binary.is_empty() && options.datatypes.is_empty() {
+        return Ok(infer_literal(value, options));
+    }
+    let path = path.strip_prefix(X2R).unwrap_or(path);
+    let name = name();
+    match options
+        .binary
+        .get(path)
+        .or_else(|| options.binary.get(&name))
+    {
+        Some(encoding) if encoding.is_valid(value) => {
+            return Ok(Literal::new_typed_literal(value, encoding.datatype()));
+        }
+        Some(&encoding) => {
+            return Err(ConvertError::InvalidBinary {
+                source: None,
+                path: path.to_string(),
+                encoding,
+                position,
+            })
+        }
+        None => {}
+    }
+    match options
+        .datatypes
+        .get(path)
+        .or_else(|| options.datatypes.get(&name))
+    {
+        Some(datatype) if datatype::is_valid(value, datatype.as_ref()) != Some(false) => {
+            Ok(Literal::new_typed_literal(value, datatype.as_ref()))
+        }
+        _ => Ok(infer_literal(value, options)),
+    }
+}
+
+/// Creates a `NamedNode`, reporting the XML path and source position if `iri` is invalid.
+fn mint_iri(iri: String, path: &str, position: TextPosition) -> Result<NamedNode, ConvertError> {
+    NamedNode::new(iri.as_str()).map_err(|_| ConvertError::InvalidIri {
+        source: None,
+        path: path.to_string(),
+        iri,
+        position,
+    })
+}
+
+/// Ends the text run of `node` in progress, if any, dropping it if it is whitespace-only and
+/// whitespace-only text is not kept.
+fn close_run(node: &mut Node, options: &ConversionOptions) {
+    let blank = node.runs.last().is_some_and(|(index, run)| {
+        *index + 1 == node.children + node.runs.len()
+            && !options.emit_whitespace_only
+            && run.trim().is_empty()
+    });
+    if blank {
+        node.runs.pop();
+    }
+}
+
+/// Returns where the datatype of the value `literal` of the node at `path`, whose name-based key
+/// is `name`, came from, as recorded by [`ConversionOptions::value_annotations`]: `encoding`,
+/// `hint` or `inference`. Returns `None` for plain literals.
+#[cfg(feature = "rdf-star")]
+fn typed_by(
+    literal: &Literal,
+    path: &str,
+    name: &str,
+    options: &ConversionOptions,
+) -> Option<&'static str> {
+    if literal.datatype() == xsd::STRING {
+        return None;
+    }
+    let path = path.strip_prefix(X2R).unwrap_or(path);
+    let hint = options
+        .datatypes
+        .get(path)
+        .or_else(|| options.datatypes.get(name));
+    if options.binary.contains_key(path) || options.binary.contains_key(name) {
+        Some("encoding")
+    } else if hint.is_some_and(|hint| hint.as_ref() == literal.datatype()) {
+        Some("hint")
+    } else {
+        Some("inference")
+    }
+}
+
+/// Writes the RDF-star annotations of the value triple `triple` of the element starting at
+/// `position`, as described for [`ConversionOptions::value_annotations`].
+#[cfg(feature = "rdf-star")]
+fn write_value_annotations(
+    output: &mut dyn RdfWriter,
+    triple: TripleRef,
+    position: TextPosition,
+    typed_by: Option<&str>,
+) -> std::io::Result<()> {
+    let quoted = triple.into_owned();
+    let line = Literal::from(position.row + 1);
+    let column = Literal::from(position.column + 1);
+    output.add_triple(TripleRef::new(
+        &quoted,
+        SOURCE_LINE,
+        TermRef::Literal(line.as_ref()),
+    ))?;
+    output.add_triple(TripleRef::new(
+        &quoted,
+        SOURCE_COLUMN,
+        TermRef::Literal(column.as_ref()),
+    ))?;
+    if let Some(typed_by) = typed_by {
+        let typed_by = Literal::new_simple_literal(typed_by);
+        output.add_triple(TripleRef::new(
+            &quoted,
+            TYPED_BY,
+            TermRef::Literal(typed_by.as_ref()),
+        ))?;
+    }
+    Ok(())
+}
+
+/// Writes an `rdf:List` of `items`, returning its head.
+fn write_list(output: &mut dyn RdfWriter, items: &[Term]) -> std::io::Result<Term> {
+    // Build the list back to front so each cell can point at its successor
+    let mut rest: Term = NIL.into_owned().into();
+    for item in items.iter().rev() {
+        let cell = BlankNode::default();
+        output.add_triple(TripleRef::new(cell.as_ref(), FIRST, item.as_ref()))?;
+        output.add_triple(TripleRef::new(cell.as_ref(), REST, rest.as_ref()))?;
+        rest = cell.into();
+    }
+    Ok(rest)
 }
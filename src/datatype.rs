@@ -0,0 +1,323 @@
+// Copyright (c) 2024-2025, Decisym, LLC
+// Licensed under the BSD 3-Clause License (see LICENSE file in the project root).
+
+//! # XML2RDF Datatype Inference
+//!
+//! Recognizes XML Schema datatypes from the lexical form of element text and attribute values.
+//!
+//! Inference is deliberately conservative: a value only gets a datatype if it is already in the
+//! canonical-looking form of that type, so identifiers such as `007` or `+5` stay plain strings.
+
+use oxrdf::vocab::xsd;
+use oxrdf::NamedNodeRef;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Returns the XML Schema datatype `value` is recognized as, or `None` for plain strings.
+///
+/// Recognized, in order: `xsd:boolean` (`true`, `false`), `xsd:integer` (`-12`, `0`),
+/// `xsd:decimal` (`9.99`, `-0.5`), `xsd:double` (`1.5E3`), `xsd:date` (`2024-01-31`),
+/// `xsd:dateTime` (`2024-01-31T12:00:00Z`), `xsd:gYearMonth` (`2024-01`) and `xsd:duration`
+/// (`P1Y2M`, `PT30M`). Numbers with leading zeros or a `+` sign are not promoted.
+///
+/// A bare year such as `2024` is an `xsd:integer` here; it is only an `xsd:gYear` where a hint
+/// says so, see [`is_valid`].
+pub fn infer(value: &str) -> Option<NamedNodeRef<'static>> {
+    if value == "true" || value == "false" {
+        Some(xsd::BOOLEAN)
+    } else if is_integer(value) {
+        Some(xsd::INTEGER)
+    } else if is_decimal(value) {
+        Some(xsd::DECIMAL)
+    } else if is_double(value) {
+        Some(xsd::DOUBLE)
+    } else if is_date(value) {
+        Some(xsd::DATE)
+    } else if is_date_time(value) {
+        Some(xsd::DATE_TIME)
+    } else if is_g_year_month(value) {
+        Some(xsd::G_YEAR_MONTH)
+    } else if is_duration(value) {
+        Some(xsd::DURATION)
+    } else {
+        None
+    }
+}
+
+/// Returns whether `value` is in the lexical form [`infer`] recognizes for `datatype`, or in
+/// that of `xsd:gYear` (`2024`, optionally with a timezone) for `xsd:gYear`.
+///
+/// Returns `None` for other datatypes, whose lexical forms are not checked.
+pub fn is_valid(value: &str, datatype: NamedNodeRef) -> Option<bool> {
+    let valid = if datatype == xsd::G_YEAR {
+        is_g_year(value)
+    } else if datatype == xsd::INTEGER {
+        is_integer(value)
+    } else if datatype == xsd::DECIMAL {
+        is_integer(value) || is_decimal(value)
+    } else if [
+        xsd::BOOLEAN,
+        xsd::DOUBLE,
+        xsd::DATE,
+        xsd::DATE_TIME,
+        xsd::G_YEAR_MONTH,
+        xsd::DURATION,
+    ]
+    .contains(&datatype)
+    {
+        infer(value) == Some(datatype)
+    } else {
+        return None;
+    };
+    Some(valid)
+}
+
+/// Returns the `xsd:dateTime` lexical form of `time` in UTC, to the second. Times before 1970
+/// are clamped to the epoch.
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use xml2rdf::datatype::format_date_time;
+///
+/// let time = UNIX_EPOCH + Duration::from_secs(1_706_702_400);
+/// assert_eq!(format_date_time(time), "2024-01-31T12:00:00Z");
+/// ```
+pub fn format_date_time(time: SystemTime) -> String {
+    let seconds = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (days, time) = (seconds / 86_400, seconds % 86_400);
+    // Civil date of a day count, after Howard Hinnant's `civil_from_days`, with years starting
+    // in March so leap days come last
+    let days = days + 719_468;
+    let (era, day_of_era) = (days / 146_097, days % 146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        time / 3_600,
+        time / 60 % 60,
+        time % 60
+    )
+}
+
+/// Encoding of binary content carried as text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BinaryEncoding {
+    /// Base64 (RFC 4648 standard alphabet, padded), typed `xsd:base64Binary`.
+    Base64,
+    /// Hexadecimal digits, two per byte, typed `xsd:hexBinary`.
+    Hex,
+}
+
+impl BinaryEncoding {
+    /// Returns the XML Schema datatype of content in this encoding.
+    pub fn datatype(self) -> NamedNodeRef<'static> {
+        match self {
+            BinaryEncoding::Base64 => xsd::BASE_64_BINARY,
+            BinaryEncoding::Hex => xsd::HEX_BINARY,
+        }
+    }
+
+    /// Returns whether `value` is valid content in this encoding.
+    ///
+    /// Base64 content may be split by whitespace, as in line-wrapped data; hex content may not.
+    pub fn is_valid(self, value: &str) -> bool {
+        match self {
+            BinaryEncoding::Base64 => is_base64(value),
+            BinaryEncoding::Hex => {
+                value.len() % 2 == 0 && value.bytes().all(|b| b.is_ascii_hexdigit())
+            }
+        }
+    }
+}
+
+impl fmt::Display for BinaryEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BinaryEncoding::Base64 => f.write_str("base64"),
+            BinaryEncoding::Hex => f.write_str("hex"),
+        }
+    }
+}
+
+/// Groups of four base64 characters, the last of which may end in one or two `=`.
+fn is_base64(value: &str) -> bool {
+    let chars: Vec<u8> = value.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    let data = chars
+        .strip_suffix(b"==")
+        .or_else(|| chars.strip_suffix(b"="))
+        .unwrap_or(&chars);
+    chars.len() % 4 == 0
+        && data
+            .iter()
+            .all(|&b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/')
+}
+
+/// An optional `-` followed by digits without a leading zero, or `0` itself.
+fn is_integer(value: &str) -> bool {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    is_digits(digits) && (digits == "0" || !digits.starts_with('0')) && value != "-0"
+}
+
+/// An integer part as for [`is_integer`] (or `-0`), a `.` and at least one fractional digit.
+fn is_decimal(value: &str) -> bool {
+    match value.split_once('.') {
+        Some((int, frac)) => (is_integer(int) || int == "-0") && is_digits(frac),
+        None => false,
+    }
+}
+
+/// An integer or decimal mantissa, an `E` or `e` and an optionally signed integer exponent.
+fn is_double(value: &str) -> bool {
+    match value.split_once(['E', 'e']) {
+        Some((mantissa, exponent)) => {
+            let exponent = exponent.strip_prefix('-').unwrap_or(exponent);
+            (is_integer(mantissa) || is_decimal(mantissa)) && is_digits(exponent)
+        }
+        None => false,
+    }
+}
+
+/// `YYYY-MM-DD`, optionally followed by a timezone.
+fn is_date(value: &str) -> bool {
+    value.is_char_boundary(10) && is_day(&value[..10]) && is_timezone(&value[10..])
+}
+
+/// `YYYY-MM-DDThh:mm:ss`, optional fractional seconds, optionally followed by a timezone.
+fn is_date_time(value: &str) -> bool {
+    if value.len() < 19 || !value.is_char_boundary(19) || value.as_bytes()[10] != b'T' {
+        return false;
+    }
+    let (time, rest) = value[11..].split_at(8);
+    let rest = match rest.strip_prefix('.') {
+        Some(fraction) => {
+            let end = fraction
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(fraction.len());
+            if end == 0 {
+                return false;
+            }
+            &fraction[end..]
+        }
+        None => rest,
+    };
+    is_day(&value[..10]) && is_time(time) && is_timezone(rest)
+}
+
+/// A year of at least four digits, without leading zeros beyond four, optionally negative and
+/// optionally followed by a timezone.
+fn is_g_year(value: &str) -> bool {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    let end = digits
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(digits.len());
+    let year = &digits[..end];
+    year.len() >= 4 && (year.len() == 4 || !year.starts_with('0')) && is_timezone(&digits[end..])
+}
+
+/// `YYYY-MM`, optionally followed by a timezone.
+fn is_g_year_month(value: &str) -> bool {
+    value.is_char_boundary(7)
+        && value.as_bytes().get(4) == Some(&b'-')
+        && is_digits(&value[..4])
+        && in_range(&value[5..7], 1, 12)
+        && is_timezone(&value[7..])
+}
+
+/// An optional `-`, a `P`, then years, months and days, then a `T` and hours, minutes and
+/// possibly fractional seconds, each as digits followed by its designator. At least one part
+/// must be present, and at least one time part after a `T`.
+fn is_duration(value: &str) -> bool {
+    let Some(rest) = value.strip_prefix('-').unwrap_or(value).strip_prefix('P') else {
+        return false;
+    };
+    let (date, time) = match rest.split_once('T') {
+        Some((date, time)) => (date, Some(time)),
+        None => (rest, None),
+    };
+    let date_parts = duration_parts(date, &['Y', 'M', 'D'], false);
+    let time_parts = match time {
+        Some(time) => match duration_parts(time, &['H', 'M', 'S'], true) {
+            Some(0) | None => return false,
+            parts => parts,
+        },
+        None => Some(0),
+    };
+    match (date_parts, time_parts) {
+        (Some(date), Some(time)) => date + time > 0,
+        _ => false,
+    }
+}
+
+/// Counts the `<digits><designator>` parts of `value`, which must appear in the order of
+/// `designators`, each at most once. If `fraction` is set, the number before the last
+/// designator may have a fractional part. Returns `None` if `value` is not made of such parts.
+fn duration_parts(mut value: &str, designators: &[char], fraction: bool) -> Option<usize> {
+    let mut parts = 0;
+    let mut allowed = designators;
+    while !value.is_empty() {
+        let end = value.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+        let (number, rest) = value.split_at(end);
+        let designator = rest.chars().next()?;
+        let index = allowed.iter().position(|&d| d == designator)?;
+        let valid = match number.split_once('.') {
+            Some((int, frac)) => {
+                fraction
+                    && designators.last() == Some(&designator)
+                    && is_digits(int)
+                    && is_digits(frac)
+            }
+            None => is_digits(number),
+        };
+        if !valid {
+            return None;
+        }
+        parts += 1;
+        allowed = &allowed[index + 1..];
+        value = &rest[1..];
+    }
+    Some(parts)
+}
+
+fn is_day(value: &str) -> bool {
+    let b = value.as_bytes();
+    b.len() == 10
+        && b[4] == b'-'
+        && b[7] == b'-'
+        && is_digits(&value[..4])
+        && in_range(&value[5..7], 1, 12)
+        && in_range(&value[8..10], 1, 31)
+}
+
+fn is_time(value: &str) -> bool {
+    let b = value.as_bytes();
+    b.len() == 8
+        && b[2] == b':'
+        && b[5] == b':'
+        && in_range(&value[..2], 0, 23)
+        && in_range(&value[3..5], 0, 59)
+        && in_range(&value[6..8], 0, 59)
+}
+
+/// Empty, `Z`, or `+hh:mm` / `-hh:mm`.
+fn is_timezone(value: &str) -> bool {
+    match value.as_bytes() {
+        [] | [b'Z'] => true,
+        [b'+' | b'-', _, _, b':', _, _] => {
+            in_range(&value[1..3], 0, 14) && in_range(&value[4..6], 0, 59)
+        }
+        _ => false,
+    }
+}
+
+fn is_digits(value: &str) -> bool {
+    !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn in_range(value: &str, min: u32, max: u32) -> bool {
+    is_digits(value) && value.parse().is_ok_and(|n: u32| (min..=max).contains(&n))
+}
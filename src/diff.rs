@@ -0,0 +1,157 @@
+// Copyright (c) 2024-2025, Decisym, LLC
+// Licensed under the BSD 3-Clause License (see LICENSE file in the project root).
+
+//! # XML2RDF Graph Diff
+//!
+//! Compares two converted graphs, e.g. two versions of a document, for change review.
+//!
+//! ## Overview
+//! - [`diff_graphs`] reports the triples only in the old graph as removed, and those only in the
+//!   new graph as added. A changed value shows as a removed and an added triple with the same
+//!   subject and predicate.
+//! - Nodes are aligned by their IRIs, so the diff is only meaningful between conversions with
+//!   stable node identities, such as those of `--node-ids deterministic`. Blank nodes are aligned
+//!   by their content, see [`Graph::canonicalize`].
+//! - The `Display` implementation of [`GraphDiff`] writes N-Triples lines prefixed with `-` or
+//!   `+`, grouped by subject and predicate.
+
+use oxrdf::dataset::CanonicalizationAlgorithm;
+use oxrdf::{Graph, Triple};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// The differences between two graphs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphDiff {
+    /// Triples only in the old graph, sorted.
+    pub removed: Vec<Triple>,
+    /// Triples only in the new graph, sorted.
+    pub added: Vec<Triple>,
+}
+
+impl GraphDiff {
+    /// Returns whether the graphs are the same.
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.added.is_empty()
+    }
+
+    /// Returns the number of subject and predicate pairs that lost some objects and gained
+    /// others, i.e. changed values.
+    pub fn changed(&self) -> usize {
+        let mut changed = 0;
+        let mut added = self.added.iter().peekable();
+        let mut last: Option<&Triple> = None;
+        for removed in &self.removed {
+            if last.is_some_and(|last| compare(last, removed) == Ordering::Equal) {
+                continue;
+            }
+            while added
+                .peek()
+                .is_some_and(|a| compare(a, removed) == Ordering::Less)
+            {
+                added.next();
+            }
+            if added
+                .peek()
+                .is_some_and(|a| compare(a, removed) == Ordering::Equal)
+            {
+                changed += 1;
+                last = Some(removed);
+            }
+        }
+        changed
+    }
+
+    /// Returns a one-line summary of the numbers of removed, added and changed triples.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} removed, {} added, {} changed",
+            self.removed.len(),
+            self.added.len(),
+            self.changed()
+        )
+    }
+}
+
+/// Writes the removed triples prefixed with `- ` and the added ones with `+ `, as N-Triples:
+///
+/// ```
+/// use oxrdf::{Graph, Literal, NamedNodeRef, TripleRef};
+/// use xml2rdf::diff::diff_graphs;
+///
+/// let node = NamedNodeRef::new_unchecked("https://example.com/n");
+/// let value = NamedNodeRef::new_unchecked("https://example.com/value");
+/// let graph = |v: &str| {
+///     let mut graph = Graph::new();
+///     graph.insert(TripleRef::new(node, value, &Literal::new_simple_literal(v)));
+///     graph
+/// };
+/// let diff = diff_graphs(&graph("old"), &graph("new"));
+/// assert_eq!(
+///     diff.to_string(),
+///     "- <https://example.com/n> <https://example.com/value> \"old\" .\n\
+///      + <https://example.com/n> <https://example.com/value> \"new\" .\n"
+/// );
+/// assert_eq!(diff.summary(), "1 removed, 1 added, 1 changed");
+/// ```
+///
+/// Lines are ordered by subject and predicate, so the old and new values of a changed triple are
+/// next to each other.
+impl fmt::Display for GraphDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut removed = self.removed.iter().peekable();
+        let mut added = self.added.iter().peekable();
+        loop {
+            let next = match (removed.peek(), added.peek()) {
+                (Some(r), Some(a)) if compare(r, a) == Ordering::Greater => {
+                    added.next().map(|t| ('+', t))
+                }
+                (Some(_), _) => removed.next().map(|t| ('-', t)),
+                (None, _) => added.next().map(|t| ('+', t)),
+            };
+            let Some((sign, triple)) = next else {
+                return Ok(());
+            };
+            writeln!(f, "{sign} {triple} .")?;
+        }
+    }
+}
+
+/// Orders triples by subject and predicate in their N-Triples form, ignoring the object.
+fn compare(a: &Triple, b: &Triple) -> Ordering {
+    a.subject
+        .to_string()
+        .cmp(&b.subject.to_string())
+        .then_with(|| a.predicate.as_str().cmp(b.predicate.as_str()))
+}
+
+/// Returns the triples removed from `old` and added in `new`.
+pub fn diff_graphs(old: &Graph, new: &Graph) -> GraphDiff {
+    let (old, new) = (canonical(old), canonical(new));
+    let only_in = |graph: &Graph, other: &Graph| {
+        let mut triples: Vec<Triple> = graph
+            .iter()
+            .filter(|t| !other.contains(*t))
+            .map(|t| t.into_owned())
+            .collect();
+        triples.sort_by_cached_key(|t| {
+            (
+                t.subject.to_string(),
+                t.predicate.as_str().to_string(),
+                t.object.to_string(),
+            )
+        });
+        triples
+    };
+    GraphDiff {
+        removed: only_in(&old, &new),
+        added: only_in(&new, &old),
+    }
+}
+
+/// Returns a copy of `graph` with its blank nodes relabeled by their content.
+fn canonical(graph: &Graph) -> Graph {
+    let mut graph = graph.clone();
+    graph.canonicalize(CanonicalizationAlgorithm::Unstable);
+    graph
+}
@@ -0,0 +1,76 @@
+// Copyright (c) 2024-2025, Decisym, LLC
+// Licensed under the BSD 3-Clause License (see LICENSE file in the project root).
+
+//! # XML2RDF Source Digests
+//!
+//! Computes a content digest of each converted document while it is read, so the exact bytes
+//! behind a document node can be recorded without reading the source a second time.
+
+use md5::Md5;
+use oxrdf::NamedNodeRef;
+use sha2::{Digest, Sha256};
+use std::io::{self, Read};
+
+use crate::vocab::{MD5, SHA256};
+
+/// Hash algorithm of source digests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DigestAlgorithm {
+    /// SHA-256, recorded with `x2r:sha256`.
+    Sha256,
+    /// MD5, recorded with `x2r:md5`. Only suitable for detecting accidental changes.
+    Md5,
+}
+
+impl DigestAlgorithm {
+    /// Returns the model predicate linking a document node to a digest of this algorithm.
+    pub fn predicate(self) -> NamedNodeRef<'static> {
+        match self {
+            DigestAlgorithm::Sha256 => SHA256,
+            DigestAlgorithm::Md5 => MD5,
+        }
+    }
+}
+
+enum Hasher {
+    Sha256(Sha256),
+    Md5(Md5),
+}
+
+/// Hashes the bytes read through it.
+pub(crate) struct DigestReader<R> {
+    inner: R,
+    hasher: Option<Hasher>,
+}
+
+impl<R: Read> DigestReader<R> {
+    /// Wraps `inner`, hashing with `algorithm`, or passing the input through if `None`.
+    pub(crate) fn new(inner: R, algorithm: Option<DigestAlgorithm>) -> Self {
+        let hasher = algorithm.map(|algorithm| match algorithm {
+            DigestAlgorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+            DigestAlgorithm::Md5 => Hasher::Md5(Md5::new()),
+        });
+        Self { inner, hasher }
+    }
+
+    /// Returns the lowercase hex digest of the bytes read so far, or `None` if the reader is not
+    /// hashing. Bytes read afterwards are not hashed.
+    pub(crate) fn finish(&mut self) -> Option<String> {
+        match self.hasher.take()? {
+            Hasher::Sha256(hasher) => Some(format!("{:x}", hasher.finalize())),
+            Hasher::Md5(hasher) => Some(format!("{:x}", hasher.finalize())),
+        }
+    }
+}
+
+impl<R: Read> Read for DigestReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        match &mut self.hasher {
+            Some(Hasher::Sha256(hasher)) => hasher.update(&buf[..n]),
+            Some(Hasher::Md5(hasher)) => hasher.update(&buf[..n]),
+            None => {}
+        }
+        Ok(n)
+    }
+}
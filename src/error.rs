@@ -0,0 +1,367 @@
+// Copyright (c) 2024-2025, Decisym, LLC
+// Licensed under the BSD 3-Clause License (see LICENSE file in the project root).
+
+//! # XML2RDF Errors
+//!
+//! Error types returned by the XML2RDF conversion functions.
+
+use std::fmt;
+use std::io;
+use xml::common::TextPosition;
+
+use crate::datatype::BinaryEncoding;
+use crate::vocab::X2R;
+
+/// Errors that can occur while converting XML to RDF.
+#[derive(Debug)]
+pub enum ConvertError {
+    /// Reading the XML input or writing the RDF output failed. Displays as the I/O error itself.
+    Io(io::Error),
+    /// An input file could not be opened.
+    Open {
+        /// Path of the file, as given.
+        path: String,
+        /// Why opening it failed, included in the message.
+        error: io::Error,
+    },
+    /// An IRI minted for an element or attribute is not a valid IRI.
+    InvalidIri {
+        /// Where the document was read from, if known.
+        source: Option<String>,
+        /// Dotted path of the element or attribute the IRI was minted for.
+        path: String,
+        /// The rejected IRI string.
+        iri: String,
+        /// Location in the XML source of the element that produced the IRI.
+        position: TextPosition,
+    },
+    /// Content of an element or attribute marked as binary is not valid in its encoding.
+    InvalidBinary {
+        /// Where the document was read from, if known.
+        source: Option<String>,
+        /// Dotted path of the element or attribute.
+        path: String,
+        /// The encoding the content was expected in.
+        encoding: BinaryEncoding,
+        /// Location in the XML source of the element.
+        position: TextPosition,
+    },
+    /// The input contains a byte sequence that is not valid UTF-8, and
+    /// [`crate::convert::Utf8Policy::Strict`] is in effect.
+    InvalidUtf8 {
+        /// Where the document was read from, if known.
+        source: Option<String>,
+        /// Offset in bytes from the start of the input of the invalid sequence.
+        offset: u64,
+    },
+    /// The input is larger than [`crate::convert::ConversionOptions::max_input_bytes`].
+    InputTooLarge {
+        /// Where the document was read from, if known.
+        source: Option<String>,
+        /// The maximum number of input bytes.
+        limit: u64,
+    },
+    /// A document cannot be identified to name its graph, see
+    /// [`crate::convert::ConversionOptions::document_graphs`].
+    MissingIdentity {
+        /// Where the document was read from, if known.
+        source: Option<String>,
+        /// The identifying root attribute the document lacks, or `None` for a document
+        /// identified by its source or file name that was not read from a file.
+        attribute: Option<String>,
+    },
+    /// A document node cannot be named, see
+    /// [`crate::convert::ConversionOptions::document_iri`].
+    InvalidDocumentIri {
+        /// Where the document was read from, if known.
+        source: Option<String>,
+        /// The IRI another document of the conversion already has, or `None` for a document
+        /// named after its source file that was not read from a file.
+        iri: Option<String>,
+    },
+    /// A document graph named after a file name is already used by another document of the
+    /// conversion, see [`crate::convert::DocumentIdentity::FileName`].
+    DuplicateGraph {
+        /// Where the document was read from, if known.
+        source: Option<String>,
+        /// The graph IRI the other document already has.
+        graph: String,
+    },
+    /// A name has characters not allowed in IRIs, and
+    /// [`crate::convert::NameEncoding::Reject`] is in effect.
+    UnsafeName {
+        /// Where the document was read from, if known.
+        source: Option<String>,
+        /// Dotted path of the node the name belongs to.
+        path: String,
+        /// The rejected name, as written.
+        name: String,
+        /// Location in the XML source of the element holding the name.
+        position: TextPosition,
+    },
+    /// The data namespace overlaps the model namespace, so minted node IRIs could collide with
+    /// the reserved model terms.
+    ReservedNamespace {
+        /// The rejected data namespace.
+        namespace: String,
+    },
+}
+
+impl ConvertError {
+    /// Records that the error occurred in the document read from `label`, unless a source is
+    /// already known.
+    pub(crate) fn in_source(mut self, label: Option<String>) -> Self {
+        match &mut self {
+            ConvertError::InvalidIri { source, .. }
+            | ConvertError::InvalidBinary { source, .. }
+            | ConvertError::InvalidUtf8 { source, .. }
+            | ConvertError::InputTooLarge { source, .. }
+            | ConvertError::MissingIdentity { source, .. }
+            | ConvertError::InvalidDocumentIri { source, .. }
+            | ConvertError::DuplicateGraph { source, .. }
+            | ConvertError::UnsafeName { source, .. }
+                if source.is_none() =>
+            {
+                *source = label;
+            }
+            _ => {}
+        }
+        self
+    }
+}
+
+/// Writes `source` followed by `: `, if known.
+fn write_source(f: &mut fmt::Formatter<'_>, source: &Option<String>) -> fmt::Result {
+    match source {
+        Some(source) => write!(f, "{source}: "),
+        None => Ok(()),
+    }
+}
+
+/// Messages name the document and the position in it where known, followed by a suggested
+/// remedy:
+///
+/// ```
+/// use xml2rdf::error::ConvertError;
+///
+/// let e = ConvertError::InvalidUtf8 {
+///     source: Some("data.xml".to_string()),
+///     offset: 21,
+/// };
+/// assert_eq!(
+///     e.to_string(),
+///     "data.xml: invalid UTF-8 sequence at byte offset 21; re-encode the input as UTF-8, \
+///      or convert with the lossy UTF-8 policy"
+/// );
+/// ```
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConvertError::Io(e) => write!(f, "{e}"),
+            ConvertError::Open { path, error } => write!(f, "cannot open {path}: {error}"),
+            ConvertError::InvalidIri {
+                source,
+                path,
+                iri,
+                position,
+            } => {
+                write_source(f, source)?;
+                write!(
+                    f,
+                    "invalid IRI <{iri}> minted for '{path}' at {position}; the data namespace \
+                     must be a valid IRI ending in '/' or '#', and names may only use \
+                     characters allowed in IRIs"
+                )
+            }
+            ConvertError::InvalidBinary {
+                source,
+                path,
+                encoding,
+                position,
+            } => {
+                write_source(f, source)?;
+                write!(
+                    f,
+                    "invalid {encoding} content in '{path}' at {position}; fix the content, \
+                     or remove the binary encoding of '{path}'"
+                )
+            }
+            ConvertError::InvalidUtf8 { source, offset } => {
+                write_source(f, source)?;
+                write!(
+                    f,
+                    "invalid UTF-8 sequence at byte offset {offset}; re-encode the input as \
+                     UTF-8, or convert with the lossy UTF-8 policy"
+                )
+            }
+            ConvertError::InputTooLarge { source, limit } => {
+                write_source(f, source)?;
+                write!(
+                    f,
+                    "input exceeds the maximum of {limit} bytes; raise the limit, or split the \
+                     input"
+                )
+            }
+            ConvertError::MissingIdentity {
+                source,
+                attribute: Some(attribute),
+            } => {
+                write_source(f, source)?;
+                write!(
+                    f,
+                    "root element has no '{attribute}' attribute identifying the document; add \
+                     the attribute, or identify documents by their source file"
+                )
+            }
+            ConvertError::MissingIdentity {
+                source,
+                attribute: None,
+            } => {
+                write_source(f, source)?;
+                write!(
+                    f,
+                    "document was not read from a file, so it has no source identifying it; \
+                     identify documents by a root attribute instead"
+                )
+            }
+            ConvertError::InvalidDocumentIri {
+                source,
+                iri: Some(iri),
+            } => {
+                write_source(f, source)?;
+                write!(
+                    f,
+                    "document IRI <{iri}> is already used by another document; give an explicit \
+                     document IRI for a single document only, and convert files of the same \
+                     name separately"
+                )
+            }
+            ConvertError::InvalidDocumentIri { source, iri: None } => {
+                write_source(f, source)?;
+                write!(
+                    f,
+                    "document was not read from a file, so its document node cannot be named \
+                     after it; give an explicit document IRI instead"
+                )
+            }
+            ConvertError::DuplicateGraph { source, graph } => {
+                write_source(f, source)?;
+                write!(
+                    f,
+                    "document graph <{graph}> is already used by another document; convert \
+                     files of the same name separately, or identify documents by their source"
+                )
+            }
+            ConvertError::UnsafeName {
+                source,
+                path,
+                name,
+                position,
+            } => {
+                write_source(f, source)?;
+                write!(
+                    f,
+                    "name '{name}' in '{path}' at {position} has characters not allowed in IRIs; \
+                     rename it, or percent-encode or slug names"
+                )
+            }
+            ConvertError::ReservedNamespace { namespace } => write!(
+                f,
+                "data namespace <{namespace}> overlaps the model namespace <{X2R}>, \
+                 so node IRIs could collide with model terms; choose a namespace outside it"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConvertError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConvertError::Io(e) => e.source(),
+            ConvertError::Open { .. }
+            | ConvertError::InvalidIri { .. }
+            | ConvertError::InvalidBinary { .. }
+            | ConvertError::InvalidUtf8 { .. }
+            | ConvertError::InputTooLarge { .. }
+            | ConvertError::MissingIdentity { .. }
+            | ConvertError::InvalidDocumentIri { .. }
+            | ConvertError::DuplicateGraph { .. }
+            | ConvertError::UnsafeName { .. }
+            | ConvertError::ReservedNamespace { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for ConvertError {
+    fn from(e: io::Error) -> Self {
+        ConvertError::Io(e)
+    }
+}
+
+/// A contradiction between conversion options, found by
+/// [`crate::convert::ConversionOptions::validate`] before any input is read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// An option is set that has no effect without another one.
+    Requires {
+        /// The option that is set.
+        option: &'static str,
+        /// The option it depends on.
+        requires: &'static str,
+    },
+    /// A data namespace override overlaps the model namespace, see
+    /// [`ConvertError::ReservedNamespace`].
+    ReservedNamespace {
+        /// The element key of the override.
+        key: String,
+        /// The rejected data namespace.
+        namespace: String,
+    },
+    /// A key has both a binary encoding and a datatype hint. The encoding takes precedence, so
+    /// the hint would never apply.
+    BinaryAndDatatype {
+        /// The element or attribute key.
+        key: String,
+    },
+    /// Conditions on the same element require different values of one attribute, so the
+    /// element would always be skipped.
+    ContradictoryConditions {
+        /// The element path or name the conditions apply to.
+        element: String,
+        /// The attribute they test.
+        attribute: String,
+    },
+    /// An option has a value it cannot take.
+    InvalidValue {
+        /// The option.
+        option: &'static str,
+        /// Why the value is rejected.
+        message: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Requires { option, requires } => {
+                write!(f, "{option} has no effect without {requires}")
+            }
+            ConfigError::ReservedNamespace { key, namespace } => write!(
+                f,
+                "data namespace <{namespace}> for '{key}' overlaps the model namespace <{X2R}>"
+            ),
+            ConfigError::BinaryAndDatatype { key } => write!(
+                f,
+                "'{key}' has both a binary encoding and a datatype hint, which would never apply"
+            ),
+            ConfigError::ContradictoryConditions { element, attribute } => write!(
+                f,
+                "conditions require different values of '{attribute}' on '{element}', \
+                 so it would always be skipped"
+            ),
+            ConfigError::InvalidValue { option, message } => write!(f, "{option}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
@@ -0,0 +1,265 @@
+// Copyright (c) 2024-2025, Decisym, LLC
+// Licensed under the BSD 3-Clause License (see LICENSE file in the project root).
+
+//! # XML2RDF Embedded JSON
+//!
+//! A small [JSON](https://www.rfc-editor.org/rfc/rfc8259) parser for JSON embedded in XML
+//! text, see [`crate::convert::ConversionOptions::embedded_json`]. It also reads JSON prefix
+//! files and the conversion cache. The module is internal to the crate.
+
+use oxrdf::vocab::xsd;
+use oxrdf::Literal;
+
+/// Nesting depth beyond which input is not treated as JSON, so deeply nested text cannot
+/// exhaust the stack.
+const MAX_DEPTH: usize = 128;
+
+/// A parsed JSON value. Object members keep their document order, including duplicate keys.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// `null`.
+    Null,
+    /// `true` or `false`.
+    Bool(bool),
+    /// A number, kept in its lexical form.
+    Number(String),
+    /// A string, with its escapes resolved.
+    String(String),
+    /// An array, in document order.
+    Array(Vec<Value>),
+    /// An object, as its members in document order.
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Returns the literal for a scalar value: a plain string, an `xsd:boolean`, or an
+    /// `xsd:integer`, `xsd:decimal` or `xsd:double` depending on the form of the number.
+    /// Returns `None` for `null`, arrays and objects.
+    pub fn literal(&self) -> Option<Literal> {
+        match self {
+            Value::Bool(b) => Some(Literal::from(*b)),
+            Value::Number(n) if n.contains(['e', 'E']) => {
+                Some(Literal::new_typed_literal(n, xsd::DOUBLE))
+            }
+            Value::Number(n) if n.contains('.') => {
+                Some(Literal::new_typed_literal(n, xsd::DECIMAL))
+            }
+            Value::Number(n) => Some(Literal::new_typed_literal(n, xsd::INTEGER)),
+            Value::String(s) => Some(Literal::new_simple_literal(s)),
+            Value::Null | Value::Array(_) | Value::Object(_) => None,
+        }
+    }
+}
+
+/// Parses `text` as a JSON object or array, surrounded by optional whitespace. Returns `None`
+/// for anything else, including scalars such as `5` or `"a"`, which are as likely to be plain
+/// text.
+pub fn parse(text: &str) -> Option<Value> {
+    let mut parser = Parser {
+        input: text.as_bytes(),
+        pos: 0,
+    };
+    parser.whitespace();
+    if !matches!(parser.peek(), Some(b'{' | b'[')) {
+        return None;
+    }
+    let value = parser.value(0)?;
+    parser.whitespace();
+    (parser.pos == parser.input.len()).then_some(value)
+}
+
+/// Recursive descent parser over the bytes of the input.
+struct Parser<'a> {
+    input: &'a [u8],
+    /// Offset of the next byte to read
+    pos: usize,
+}
+
+impl Parser<'_> {
+    /// Returns the next byte without consuming it.
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    /// Skips JSON whitespace.
+    fn whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Consumes `token` if the input continues with it.
+    fn eat(&mut self, token: &[u8]) -> bool {
+        if self.input[self.pos..].starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Parses the value at the current position, nested `depth` levels deep.
+    fn value(&mut self, depth: usize) -> Option<Value> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        match self.peek()? {
+            b'{' => self.object(depth),
+            b'[' => self.array(depth),
+            b'"' => self.string().map(Value::String),
+            b't' if self.eat(b"true") => Some(Value::Bool(true)),
+            b'f' if self.eat(b"false") => Some(Value::Bool(false)),
+            b'n' if self.eat(b"null") => Some(Value::Null),
+            b'-' | b'0'..=b'9' => self.number(),
+            _ => None,
+        }
+    }
+
+    /// Parses an object, starting at its `{`.
+    fn object(&mut self, depth: usize) -> Option<Value> {
+        self.pos += 1;
+        let mut members = Vec::new();
+        self.whitespace();
+        if self.eat(b"}") {
+            return Some(Value::Object(members));
+        }
+        loop {
+            self.whitespace();
+            if self.peek()? != b'"' {
+                return None;
+            }
+            let key = self.string()?;
+            self.whitespace();
+            if !self.eat(b":") {
+                return None;
+            }
+            self.whitespace();
+            members.push((key, self.value(depth + 1)?));
+            self.whitespace();
+            if self.eat(b"}") {
+                return Some(Value::Object(members));
+            }
+            if !self.eat(b",") {
+                return None;
+            }
+        }
+    }
+
+    /// Parses an array, starting at its `[`.
+    fn array(&mut self, depth: usize) -> Option<Value> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        self.whitespace();
+        if self.eat(b"]") {
+            return Some(Value::Array(items));
+        }
+        loop {
+            self.whitespace();
+            items.push(self.value(depth + 1)?);
+            self.whitespace();
+            if self.eat(b"]") {
+                return Some(Value::Array(items));
+            }
+            if !self.eat(b",") {
+                return None;
+            }
+        }
+    }
+
+    /// An optional `-`, an integer part without leading zeros, an optional fraction and an
+    /// optional exponent.
+    fn number(&mut self) -> Option<Value> {
+        let start = self.pos;
+        self.eat(b"-");
+        if !self.eat(b"0") && self.digits() == 0 {
+            return None;
+        }
+        if self.eat(b".") && self.digits() == 0 {
+            return None;
+        }
+        if self.eat(b"e") || self.eat(b"E") {
+            let _ = self.eat(b"+") || self.eat(b"-");
+            if self.digits() == 0 {
+                return None;
+            }
+        }
+        let number = std::str::from_utf8(&self.input[start..self.pos]).ok()?;
+        Some(Value::Number(number.to_string()))
+    }
+
+    /// Consumes a run of ASCII digits, returning its length.
+    fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    /// Parses a string, starting at its opening quote, and resolves its escapes.
+    fn string(&mut self) -> Option<String> {
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            // Copy the run up to the next quote or escape as is
+            let run = self.input[self.pos..]
+                .iter()
+                .position(|&b| b == b'"' || b == b'\\' || b < 0x20)?;
+            out.push_str(std::str::from_utf8(&self.input[self.pos..self.pos + run]).ok()?);
+            self.pos += run;
+            match self.peek()? {
+                b'"' => {
+                    self.pos += 1;
+                    return Some(out);
+                }
+                b'\\' => {
+                    self.pos += 1;
+                    let escaped = self.peek()?;
+                    self.pos += 1;
+                    match escaped {
+                        b'"' => out.push('"'),
+                        b'\\' => out.push('\\'),
+                        b'/' => out.push('/'),
+                        b'b' => out.push('\u{8}'),
+                        b'f' => out.push('\u{c}'),
+                        b'n' => out.push('\n'),
+                        b'r' => out.push('\r'),
+                        b't' => out.push('\t'),
+                        b'u' => out.push(self.unicode_escape()?),
+                        _ => return None,
+                    }
+                }
+                // Unescaped control characters
+                _ => return None,
+            }
+        }
+    }
+
+    /// Decodes the four hex digits after `\u`, combining a surrogate pair with the `\uXXXX`
+    /// escape that follows it.
+    fn unicode_escape(&mut self) -> Option<char> {
+        let high = self.hex4()?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return char::from_u32(high);
+        }
+        if !self.eat(b"\\u") {
+            return None;
+        }
+        let low = self.hex4()?;
+        if !(0xDC00..0xE000).contains(&low) {
+            return None;
+        }
+        char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+    }
+
+    /// Consumes four hex digits, returning their value.
+    fn hex4(&mut self) -> Option<u32> {
+        let digits = self.input.get(self.pos..self.pos + 4)?;
+        let digits = std::str::from_utf8(digits).ok()?;
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        self.pos += 4;
+        u32::from_str_radix(digits, 16).ok()
+    }
+}
@@ -1,5 +1,23 @@
 // Copyright (c) 2024-2025, Decisym, LLC
 // Licensed under the BSD 3-Clause License (see LICENSE file in the project root).
 
+pub mod cache;
+#[cfg(feature = "canonical")]
+pub mod canonical;
+pub mod check;
 pub mod convert;
+pub mod datatype;
+pub mod diff;
+#[cfg(feature = "digest")]
+pub mod digest;
+pub mod error;
+pub(crate) mod json;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod prefixes;
+pub mod query;
+pub mod stats;
+#[cfg(all(unix, feature = "syslog"))]
+pub mod syslog;
+pub mod vocab;
 pub mod writer;
@@ -24,7 +24,7 @@
 //! ```
 //! This will take `data.xml`, apply the specified namespace, and save the RDF output in `output.nt`.
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use xml2rdf::*;
 
 /// Command-line interface for XML2RDF Converter
@@ -42,6 +42,8 @@ struct Cli {
 ///
 /// Contains the available commands for the XML2RDF converter.
 #[derive(Subcommand)]
+// Parsed once per run, so the size of the `convert` arguments does not matter
+#[allow(clippy::large_enum_variant)]
 enum Commands {
     /// Convert XML to RDF format.
     ///
@@ -50,9 +52,17 @@ enum Commands {
     Convert {
         /// Namespace for RDF graph generation.
         ///
-        /// A custom namespace to prefix RDF resources created from XML keys and values.
-        #[arg(short, long, default_value = "https://decisym.ai/xml2rdf/data")]
-        namespace: String,
+        /// A custom namespace to prefix RDF resources created from XML keys and values. Defaults
+        /// to https://decisym.ai/xml2rdf/data, see --infer-namespace.
+        #[arg(short, long)]
+        namespace: Option<String>,
+
+        /// Use the namespace URI of the first input file's root element as data namespace.
+        ///
+        /// Falls back to the default namespace if the root element has no namespace, or one
+        /// that cannot serve as data namespace. An explicit --namespace always wins.
+        #[arg(long, requires = "xml")]
+        infer_namespace: bool,
 
         /// Path to input XML file(s).
         ///
@@ -63,10 +73,1140 @@ enum Commands {
         /// Path to output file.
         ///
         /// Optional: Specify the path to save the generated RDF data. If not provided, data will be written
-        /// to stdout
+        /// to stdout. Files ending in `.ttl` are written as Turtle, `.trig` as TriG, `.rdf` as
+        /// RDF/XML, `.jsonld` as JSON-LD, others as N-Triples, unless --format is given.
+        /// Output is appended to an existing file, except RDF/XML, JSON-LD and --canonical
+        /// output, which replace it.
         #[arg(short, long)]
         output_file: Option<String>,
+
+        /// Output format, guessed from the output file name by default.
+        ///
+        /// `ndjson` writes one JSON object per triple and line, with the subject, predicate and
+        /// object in `s`, `p` and `o`, the object kind (`iri`, `bnode` or `literal`) in `ot`, and
+        /// the datatype and language tag of literals in `dt` and `lang`. `jsonld` buffers the
+        /// output in memory and writes it as one JSON-LD document once the conversion is done.
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+
+        /// Read namespace prefixes from a file of Turtle `@prefix` declarations or a JSON object.
+        ///
+        /// Turtle, TriG, RDF/XML and JSON-LD output declare the prefixes and abbreviate IRIs
+        /// with them, and prefixed names such as `xsd:gYear` can be used in --class-map,
+        /// --promote-attr, --flag, --datatype, --only-predicates, --reconstruction-graph,
+        /// --document-iri, --corpus-node and --corpus-predicate.
+        #[arg(long, value_name = "FILE")]
+        prefixes: Option<String>,
+
+        #[command(flatten)]
+        conversion: ConversionArgs,
+
+        /// Split the output file into numbered chunks of at most N triples (out.0.nt, out.1.nt, ...).
+        ///
+        /// Chunks are split strictly by count, so one element's triples may span two chunks.
+        #[arg(
+            long,
+            value_name = "N",
+            requires = "output_file",
+            conflicts_with = "format",
+            value_parser = parse_chunk_size
+        )]
+        max_triples_per_file: Option<usize>,
+
+        /// Skip triples already present in the output file, and repeated triples, when appending.
+        ///
+        /// The existing output is loaded into memory first, so re-running a conversion into the
+        /// same file does not duplicate the shared class and ontology triples.
+        #[arg(
+            long,
+            requires = "output_file",
+            conflicts_with_all = ["max_triples_per_file", "format"]
+        )]
+        dedup_existing: bool,
+
+        /// Skip input files unchanged since they were last converted, as recorded in FILE.
+        ///
+        /// FILE records the modification time and size of each converted file, and is updated
+        /// once the conversion succeeds. A corrupt FILE is ignored and every file converted
+        /// again. Changed files are converted again without removing their earlier output, see
+        /// --document-graph and --replace-graphs to replace it.
+        #[arg(long, value_name = "FILE", requires = "xml")]
+        cache: Option<String>,
+
+        /// Write the output file under a temporary name and rename it into place once done.
+        ///
+        /// Readers of the output file never see a partial conversion: the file keeps its
+        /// previous content, if any, until the conversion succeeds. The temporary file is
+        /// removed if it fails, or on the first Ctrl-C with the ctrlc feature; other signals,
+        /// and a second Ctrl-C, leave it behind as `.NAME.PID.tmp` next to the output file.
+        #[arg(
+            long,
+            requires = "output_file",
+            conflicts_with_all = ["max_triples_per_file", "dedup_existing"]
+        )]
+        atomic: bool,
+
+        /// Warn about input files that contain no elements, such as comment-only documents.
+        ///
+        /// Zero-byte files are always reported.
+        #[arg(long)]
+        warn_empty: bool,
+
+        /// Report problems that do not stop the conversion, such as attributes with empty
+        /// values, elements skipped by --where, replaced invalid UTF-8 and XML syntax errors that
+        /// ended a document early.
+        #[arg(long)]
+        warnings: bool,
+
+        /// Where to report progress, warnings and errors.
+        ///
+        /// `syslog` sends every record, including each input file and the final triple count, to
+        /// the system log with structured `key="value"` fields. stderr only shows warnings and
+        /// errors.
+        #[cfg(all(unix, feature = "syslog"))]
+        #[arg(long, value_enum, default_value_t = LogTarget::Stderr)]
+        log_target: LogTarget,
+
+        /// Path of the syslog socket, such as /var/run/syslog on macOS.
+        #[cfg(all(unix, feature = "syslog"))]
+        #[arg(long, value_name = "PATH", default_value = syslog::DEFAULT_SOCKET)]
+        syslog_socket: String,
+
+        /// Write conversion counters in the Prometheus text format to FILE once done.
+        ///
+        /// Reports the documents converted, triples written, failed conversions, input bytes
+        /// read and time spent converting, e.g. for the node exporter's textfile collector.
+        #[cfg(feature = "metrics")]
+        #[arg(long, value_name = "FILE")]
+        metrics_file: Option<String>,
+
+        /// Write canonical N-Quads (RDF Dataset Canonicalization, RDFC-1.0).
+        ///
+        /// The whole output is buffered in memory and its blank nodes relabeled, so equal input
+        /// converted with `--node-ids blank` produces identical bytes.
+        #[cfg(feature = "canonical")]
+        #[arg(long, conflicts_with_all = ["max_triples_per_file", "atomic", "format"])]
+        #[cfg_attr(feature = "compress", arg(conflicts_with = "compress"))]
+        canonical: bool,
+
+        /// Compress the N-Triples output, e.g. into `output.nt.gz` or `output.nt.zst`.
+        ///
+        /// Appending to an existing compressed file adds a stream, which decompresses as the
+        /// concatenation of the earlier and the new output.
+        #[cfg(feature = "compress")]
+        #[arg(
+            long,
+            value_enum,
+            conflicts_with_all = ["max_triples_per_file", "dedup_existing"]
+        )]
+        compress: Option<writer::Compression>,
+
+        /// Path to an Oxigraph store.
+        ///
+        /// Optional: Insert the generated RDF data directly into the on-disk Oxigraph store at this path
+        /// instead of writing N-Triples.
+        #[cfg(feature = "oxigraph")]
+        #[arg(long, conflicts_with = "output_file")]
+        #[cfg_attr(feature = "compress", arg(conflicts_with = "compress"))]
+        store: Option<String>,
+
+        /// Number of triples committed per Oxigraph store transaction.
+        #[cfg(feature = "oxigraph")]
+        #[arg(long, default_value_t = 10_000, requires = "store")]
+        store_batch_size: usize,
+
+        /// Clear each document graph in the store before inserting the document again, so the
+        /// store keeps only the latest version of every document.
+        #[cfg(feature = "oxigraph")]
+        #[arg(long, requires_all = ["store", "document_graph"])]
+        replace_graphs: bool,
     },
+
+    /// Convert XML and run a SPARQL query over the result.
+    ///
+    /// The `query` command converts the XML files into an in-memory Oxigraph store, evaluates a
+    /// SPARQL SELECT query against it and prints the results. Nothing is written to disk, which
+    /// makes it handy for exploring data or validating a mapping.
+    #[cfg(feature = "oxigraph")]
+    Query {
+        /// Namespace for RDF graph generation.
+        #[arg(short, long, default_value = DEFAULT_NAMESPACE)]
+        namespace: String,
+
+        /// Path to input XML file(s).
+        #[arg(short, long, num_args = 1.., required = true)]
+        xml: Vec<String>,
+
+        /// The SPARQL SELECT query to run.
+        #[arg(long)]
+        sparql: String,
+
+        /// How to print the query results.
+        #[arg(long, value_enum, default_value_t = query::ResultFormat::Table)]
+        format: query::ResultFormat,
+
+        /// Read namespace prefixes from a file, for prefixed names in option values, as for
+        /// `convert`.
+        #[arg(long, value_name = "FILE")]
+        prefixes: Option<String>,
+
+        #[command(flatten)]
+        conversion: ConversionArgs,
+    },
+
+    /// Report structural statistics of converted RDF.
+    ///
+    /// The `stats` command loads an RDF file produced by `convert` and reports element and attribute
+    /// counts, the maximum element depth, leaf and branch element counts, and the most common
+    /// element names.
+    Stats {
+        /// Path to the RDF file to analyze.
+        ///
+        /// The format is guessed from the file extension, defaulting to N-Triples.
+        #[arg(short, long)]
+        input: String,
+    },
+
+    /// Report the triples added and removed between two versions of converted RDF.
+    ///
+    /// The `diff` command loads both files and prints each removed triple prefixed with `-` and
+    /// each added one with `+`, as N-Triples grouped by subject and predicate. Nodes are matched
+    /// by IRI, so both versions should be converted from the same path with
+    /// `--node-ids deterministic`.
+    ///
+    /// Exits with 0 if the graphs are the same, 1 if they differ and 2 on errors, like diff(1).
+    Diff {
+        /// Path to the old version. The format is guessed from the file extension, defaulting to
+        /// N-Triples.
+        #[arg(long)]
+        old: String,
+
+        /// Path to the new version.
+        #[arg(long)]
+        new: String,
+
+        /// Print only the numbers of removed, added and changed triples.
+        #[arg(long)]
+        summary: bool,
+    },
+
+    /// Check that XML files are well-formed, without converting them.
+    ///
+    /// The `check` command streams each file through the XML parser and prints `ok`, or the
+    /// line and column of the first error and what is wrong, as a fast pre-flight check before a
+    /// conversion.
+    ///
+    /// Exits with 0 if every file is well-formed, 1 if any is not and 2 if any cannot be read.
+    Check {
+        /// Path to input XML file(s).
+        #[arg(short, long, num_args = 1.., required = true)]
+        xml: Vec<String>,
+    },
+}
+
+/// Options controlling how XML is converted to RDF, shared by `convert` and `query`.
+#[derive(Args)]
+struct ConversionArgs {
+    /// How to emit elements with several text fragments.
+    ///
+    /// Applies to elements whose text is split by CDATA sections or child elements.
+    #[arg(long, value_enum, default_value_t = convert::MultiValuePolicy::Concatenate)]
+    multi_value_policy: convert::MultiValuePolicy,
+
+    /// Split the text of an element holding a value list into separate values.
+    ///
+    /// KEY is matched like --class-map keys and DELIM is the delimiter, e.g. `tags=,` gives
+    /// `<tags>a, b,,c</tags>` the values "a", "b" and "c". Each piece is trimmed unless
+    /// --no-trim-text is given, and empty pieces are dropped. With --multi-value-policy list
+    /// the pieces form an rdf:List.
+    #[arg(long, value_name = "KEY=DELIM", value_parser = parse_split_value)]
+    split_value: Vec<(String, String)>,
+
+    /// Group each element's attributes under a single attribute bag node.
+    #[arg(long)]
+    attr_bag: bool,
+
+    /// Write attributes as properties of their element (`x2r:-name`) instead of attribute
+    /// nodes, for a flat, property-graph-like shape.
+    ///
+    /// Attributes of different namespaces with the same local name share a property unless
+    /// --preserve-namespaces is given.
+    #[arg(long, conflicts_with = "attr_bag")]
+    property_style: bool,
+
+    /// Link each element to its parent with a predicate named after it (`x2r:has-name`)
+    /// instead of `hasChild`.
+    ///
+    /// With --embed-ontology each predicate is declared a subproperty of `hasChild`, so
+    /// structural queries still work with RDFS reasoning.
+    #[arg(long)]
+    child_predicates: bool,
+
+    /// Keep leading and trailing whitespace in element text and attribute values instead of
+    /// trimming it.
+    #[arg(long)]
+    no_trim_text: bool,
+
+    /// Emit values for elements and attributes whose text consists only of whitespace.
+    #[arg(long)]
+    emit_whitespace_only: bool,
+
+    /// How element and attribute classes are keyed.
+    ///
+    /// `path` gives each distinct element path its own class; `name` merges equally named
+    /// elements into one class across all converted files.
+    #[arg(long, value_enum, default_value_t = convert::ClassKey::Path)]
+    class_key: convert::ClassKey,
+
+    /// Do not type element, attribute and JSON nodes with their classes via rdf:type.
+    #[arg(long)]
+    no_type_triples: bool,
+
+    /// Do not emit the rdfs:subClassOf triples placing nodes and their classes below
+    /// XmlNode, XmlAttribute and JsonValue.
+    #[arg(long)]
+    no_class_hierarchy: bool,
+
+    /// Delimiter between the steps of element paths in class IRIs: `dot`, `slash` or any
+    /// other non-empty string, used as is.
+    ///
+    /// Keys given to other options, such as --class-map, remain dotted paths.
+    #[arg(long, value_name = "DELIMITER", default_value = "dot", value_parser = parse_path_delimiter)]
+    path_delimiter: String,
+
+    /// Prepend the model ontology (class and property definitions) to the output.
+    #[arg(long)]
+    embed_ontology: bool,
+
+    /// Keep the output within OWL DL: place element classes rather than element nodes below
+    /// XmlNode, and declare every class and predicate used.
+    ///
+    /// Cannot be combined with --multi-value-policy list.
+    #[arg(long)]
+    owl_safe: bool,
+
+    /// Emit a document node per input file, linked to its root element and source file.
+    #[arg(long)]
+    document_node: bool,
+
+    /// IRI of the document node: `minted` like other nodes, `file-name` for the source's
+    /// file name under the namespace, or an explicit IRI for a single input document.
+    ///
+    /// Named document nodes keep their IRI across conversions, so they can be referred to.
+    /// Two documents named alike, such as files of the same name in different directories,
+    /// fail the conversion.
+    #[arg(long, value_name = "IRI", default_value = "minted", requires = "document_node", value_parser = parse_document_iri)]
+    document_iri: convert::DocumentIri,
+
+    /// Link the root element of every input file from the corpus node IRI, so merged files
+    /// form a single tree.
+    #[arg(long, value_name = "IRI", value_parser = parse_named_node)]
+    corpus_node: Option<oxrdf::NamedNode>,
+
+    /// Predicate linking the corpus node to root elements, instead of hasChild.
+    #[arg(long, value_name = "IRI", requires = "corpus_node", value_parser = parse_named_node)]
+    corpus_predicate: Option<oxrdf::NamedNode>,
+
+    /// Describe document sources with PROV-O (`prov:wasDerivedFrom` a `file://` IRI).
+    #[arg(long, requires = "document_node")]
+    prov: bool,
+
+    /// How --prov and --document-graph source identify source files: `file` for their
+    /// absolute `file://` IRI, `opaque` for a `urn:uuid:` IRI derived from it, or a base IRI to
+    /// resolve their path relative to the working directory against.
+    ///
+    /// `file` reveals the local directory layout; the others keep provenance shareable.
+    #[arg(long, value_name = "SCHEME", default_value = "file", value_parser = parse_source_iris)]
+    source_iri: convert::SourceIris,
+
+    /// Record when each document was converted: `now`, `omit`, or a fixed xsd:dateTime such as
+    /// `2024-01-01T00:00:00Z`.
+    ///
+    /// Omitted by default, as a wall-clock time makes otherwise identical output differ
+    /// between runs. Written with prov:generatedAtTime under --prov.
+    #[arg(long, value_name = "TIME", default_value = "omit", requires = "document_node", value_parser = parse_conversion_time)]
+    conversion_time: convert::ConversionTime,
+
+    /// Link each document node to a ConversionRun node recording the converter version, data
+    /// namespace, node ID strategy and hash, class key and enabled features.
+    ///
+    /// Off by default, as the version makes otherwise identical output differ between
+    /// releases.
+    #[arg(long, requires = "document_node")]
+    record_options: bool,
+
+    /// Link every element and attribute node to its document node with inDocument.
+    #[arg(long, requires = "document_node")]
+    link_document: bool,
+
+    /// Record a digest of each source on its document node (x2r:sha256 or x2r:md5).
+    #[cfg(feature = "digest")]
+    #[arg(long, value_enum, value_name = "ALGORITHM", requires = "document_node")]
+    digest: Option<digest::DigestAlgorithm>,
+
+    /// Also emit inverse links (hasParent, attributeOf), or declare them with owl:inverseOf.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "MODE",
+        num_args = 0..=1,
+        default_missing_value = "materialized"
+    )]
+    emit_inverse: Option<convert::InverseLinks>,
+
+    /// Mint the nodes of an element's subtree under another data namespace.
+    ///
+    /// KEY is matched like --class-map keys. The override applies to the element, its
+    /// attributes and its descendants, until a descendant matches an override of its own.
+    /// Repeat the option to add overrides.
+    #[arg(long, value_name = "KEY=NAMESPACE", value_parser = parse_namespace_override)]
+    namespace_for: Vec<(String, String)>,
+
+    /// Include element and attribute namespaces in class IRIs.
+    #[arg(long)]
+    preserve_namespaces: bool,
+
+    /// Named graph for document order metadata (sibling and attribute indices).
+    ///
+    /// Keeps the default graph free of ordering triples. N-Triples output becomes N-Quads;
+    /// --format trig writes the graphs in readable blocks instead.
+    #[arg(long, value_name = "IRI", value_parser = parse_named_node)]
+    reconstruction_graph: Option<oxrdf::NamedNode>,
+
+    /// Write the triples of each document to a named graph identified by the document:
+    /// `source` for its source file IRI (see --source-iri), `file-name` for its file name
+    /// under the namespace, or `@NAME` for the value of the NAME attribute of its root
+    /// element.
+    ///
+    /// Re-converting a changed document yields the same graph, which --replace-graphs
+    /// replaces in a store. Two files of the same name fail a `file-name` conversion.
+    /// N-Triples output becomes N-Quads; --format trig writes the graphs in readable blocks
+    /// instead.
+    #[arg(long, value_name = "IDENTITY", value_parser = parse_document_identity)]
+    document_graph: Option<convert::DocumentIdentity>,
+
+    /// Emit xmlns declarations as declaresNamespace triples on the declaring element.
+    #[arg(long)]
+    emit_namespace_decls: bool,
+
+    /// Emit each element's prefixed name as written (`dc:title`) with hasQName.
+    #[arg(long)]
+    emit_qnames: bool,
+
+    /// Emit only triples with these predicates, e.g. `hasValue,hasName`, dropping the
+    /// document structure, class hierarchy and everything else.
+    ///
+    /// Model predicates are given by their local name, others as IRIs or, with --prefixes,
+    /// prefixed names such as `rdf:type`.
+    #[arg(long, value_name = "PREDICATES", value_delimiter = ',', value_parser = parse_predicate)]
+    only_predicates: Option<Vec<oxrdf::NamedNode>>,
+
+    /// Annotate value triples with their source line and column, and where their datatype
+    /// came from, as RDF-star. The output is N-Triples-star.
+    #[cfg(feature = "rdf-star")]
+    #[arg(long)]
+    annotate_values: bool,
+
+    /// How element, attribute and document nodes are identified: `uuid` for random UUID IRIs
+    /// under the namespace, `blank` for blank nodes, `urn` for random `urn:x2r:` UUID URNs, or
+    /// `deterministic` for UUID IRIs derived from each node's position in its document.
+    ///
+    /// `deterministic:HASH` selects how positions are hashed: `sha1`, the default, for name-based
+    /// UUIDv5 in the URL namespace, or `fnv1a` and `sha256` (with the digest feature) for UUIDv8
+    /// holding the first 16 bytes of that hash. Converting the same file again yields the same
+    /// IRIs.
+    #[arg(long, value_name = "STRATEGY", default_value = "uuid", value_parser = parse_node_ids)]
+    node_ids: convert::NodeIds,
+
+    /// Fail before more than N bytes of input are read, e.g. for untrusted streams on stdin.
+    #[arg(long, value_name = "N")]
+    max_input_bytes: Option<u64>,
+
+    /// Stop after writing this many triples.
+    #[arg(long, value_name = "N")]
+    max_triples: Option<usize>,
+
+    /// Convert only the first N elements of each input file, e.g. to sample its first records.
+    #[arg(long, value_name = "N")]
+    max_elements: Option<usize>,
+
+    /// Type element text and attribute values as xsd:integer, xsd:decimal, xsd:boolean, etc.
+    #[arg(long)]
+    infer_datatypes: bool,
+
+    /// Link element text with hasText, keeping hasValue for attribute values only.
+    #[arg(long)]
+    distinct_text_predicate: bool,
+
+    /// Represent leaf elements with attributes and text as one rdf:JSON literal.
+    #[arg(long)]
+    json_leaves: bool,
+
+    /// Emit each run of element text as a TextNode child holding its value, as in the DOM,
+    /// instead of hasValue on the element.
+    ///
+    /// With --reconstruction-graph, the siblingIndex of child elements and text nodes gives
+    /// their order.
+    #[arg(long)]
+    text_as_node: bool,
+
+    /// Type elements with a domain class, in addition to their structural class.
+    ///
+    /// KEY is a dotted element path from the root (`people.person`) or an element name
+    /// (`person`). Repeat the option to map several elements.
+    #[arg(long, value_name = "KEY=IRI", value_parser = parse_class_mapping)]
+    class_map: Vec<(String, oxrdf::NamedNode)>,
+
+    /// Write an attribute as its element's value instead of as an attribute node.
+    ///
+    /// KEY is matched like --class-map keys, NAME is the attribute name: `ref@id` gives
+    /// `<ref id="x"/>` the value "x". Append `=IRI` to use that predicate instead of
+    /// hasValue. Elements with text keep it as their value, and the attribute as a node,
+    /// unless a predicate is given.
+    #[arg(long, value_name = "KEY@NAME[=IRI]", value_parser = parse_promotion)]
+    promote_attr: Vec<(String, convert::PromotedAttribute)>,
+
+    /// Flag the parent of an empty element with `true`, e.g. for `<premium/>`.
+    ///
+    /// KEY is matched like --class-map keys. An empty KEY element adds a triple linking its
+    /// parent with IRI to "true"^^xsd:boolean; the element is converted as usual.
+    #[arg(long = "flag", value_name = "KEY=IRI", value_parser = parse_class_mapping)]
+    flag_elements: Vec<(String, oxrdf::NamedNode)>,
+
+    /// Type an element's text or an attribute's value as binary content, validating it.
+    ///
+    /// KEY is matched like --class-map keys; attributes are keyed `element.-attribute`.
+    /// ENCODING is `base64` (xsd:base64Binary) or `hex` (xsd:hexBinary).
+    #[arg(long, value_name = "KEY=ENCODING", value_parser = parse_binary_mapping)]
+    binary: Vec<(String, datatype::BinaryEncoding)>,
+
+    /// How to handle --binary content that is not valid in its encoding.
+    ///
+    /// `strict` fails the conversion; `warn` keeps the content as a plain literal and reports
+    /// it with --warnings.
+    #[arg(long, value_enum, value_name = "POLICY", default_value_t = convert::BinaryPolicy::Strict)]
+    invalid_binary: convert::BinaryPolicy,
+
+    /// Type an element's text or an attribute's value with a datatype, where it is valid.
+    ///
+    /// KEY is matched like --binary keys. Use it for values inference cannot tell apart,
+    /// such as years: `--datatype year=http://www.w3.org/2001/XMLSchema#gYear`. Values not
+    /// valid for the datatype are typed as without the hint.
+    #[arg(long, value_name = "KEY=IRI", value_parser = parse_class_mapping)]
+    datatype: Vec<(String, oxrdf::NamedNode)>,
+
+    /// Convert JSON objects and arrays in the text of an element.
+    ///
+    /// KEY is matched like --class-map keys. MODE is `expand`, modeling the JSON with
+    /// further nodes as if written as child elements, or `literal`, typing the text
+    /// rdf:JSON. Text that is no JSON object or array is converted as usual.
+    #[arg(long, value_name = "KEY=MODE", value_parser = parse_embedded_json)]
+    parse_embedded_json: Vec<(String, convert::EmbeddedJson)>,
+
+    /// How JSON keys with characters not allowed in IRIs are encoded in class and path IRIs.
+    /// Element and attribute names cannot hold such characters, so they are used as written.
+    ///
+    /// `percent` percent-encodes them, `slug` replaces them with `-`, numbering names that
+    /// would share a slug, and `reject` fails the conversion.
+    #[arg(long, value_enum, default_value_t = convert::NameEncoding::Percent)]
+    name_encoding: convert::NameEncoding,
+
+    /// Emit the UTF-8 byte span of each element as startOffset and endOffset.
+    #[arg(long)]
+    source_offsets: bool,
+
+    /// How to convert the text of elements that also have child elements.
+    #[arg(long, value_enum, default_value_t = convert::BranchTextPolicy::Keep)]
+    branch_text: convert::BranchTextPolicy,
+
+    /// Describe mixed content with an ordered hasContent list of exact text runs and children.
+    ///
+    /// Shorthand for --branch-text mixed.
+    #[arg(long, conflicts_with = "branch_text")]
+    mixed_content: bool,
+
+    /// Emit attributes sorted by namespace URI and local name, for stable output.
+    #[arg(long = "sort-attrs")]
+    sort_attributes: bool,
+
+    /// Convert an element's subtree only if the element has an attribute with the given value.
+    ///
+    /// ELEMENT is a dotted element path from the root or an element name, e.g.
+    /// `--where order@status=paid`. Elements not named by any condition are converted as
+    /// usual. Repeat the option to add conditions; all must hold.
+    #[arg(long = "where", value_name = "ELEMENT@ATTR=VALUE")]
+    conditions: Vec<convert::AttributeCondition>,
+
+    /// How to handle byte sequences that are not valid UTF-8.
+    ///
+    /// `strict` fails at the first invalid sequence; `lossy` replaces each with U+FFFD and
+    /// keeps converting.
+    #[arg(long, value_enum, value_name = "POLICY", default_value_t = convert::Utf8Policy::Strict)]
+    invalid_utf8: convert::Utf8Policy,
+
+    /// Fold a wrapped scalar such as `<name><value>Alice</value></name>` into its wrapper.
+    ///
+    /// Applies to elements whose only content is one child element without attributes or
+    /// children of its own; the child's text becomes the element's value.
+    #[arg(long)]
+    collapse_wrappers: bool,
+
+    /// Convert only the first element of each distinct path, skipping later elements with a
+    /// path seen before together with their subtrees.
+    ///
+    /// Shrinks huge repetitive documents to one example of each structure.
+    #[arg(long)]
+    sample_per_path: bool,
+
+    /// Record on each sampled element how many elements share its path.
+    #[arg(long, requires = "sample_per_path")]
+    sample_counts: bool,
+
+    /// Record on each element class how many elements it types, as instanceCount.
+    ///
+    /// The counts span all input files and are written once they are converted.
+    #[arg(long, conflicts_with = "no_type_triples")]
+    instance_counts: bool,
+
+    /// Mark elements declared nil with xsi:nil="true" with isNil, and other elements without
+    /// child elements or text with isEmpty.
+    #[arg(long)]
+    empty_markers: bool,
+
+    /// Type elements without child elements as LeafElement, and the others as
+    /// BranchElement.
+    #[arg(long)]
+    element_kinds: bool,
+}
+
+impl ConversionArgs {
+    /// Returns the conversion options given on the command line.
+    fn options(&self) -> convert::ConversionOptions {
+        convert::ConversionOptions {
+            multi_value_policy: self.multi_value_policy,
+            split_values: self.split_value.iter().cloned().collect(),
+            attribute_bag: self.attr_bag,
+            property_style: self.property_style,
+            child_predicates: self.child_predicates,
+            trim_text: !self.no_trim_text,
+            emit_whitespace_only: self.emit_whitespace_only,
+            class_key: self.class_key,
+            type_triples: !self.no_type_triples,
+            class_hierarchy: !self.no_class_hierarchy,
+            path_delimiter: self.path_delimiter.clone(),
+            embed_ontology: self.embed_ontology,
+            owl_safe: self.owl_safe,
+            document_node: self.document_node,
+            document_iri: self.document_iri.clone(),
+            corpus_node: self.corpus_node.clone(),
+            corpus_predicate: self.corpus_predicate.clone(),
+            prov: self.prov,
+            source_iris: self.source_iri.clone(),
+            conversion_time: self.conversion_time.clone(),
+            record_options: self.record_options,
+            link_document: self.link_document,
+            #[cfg(feature = "digest")]
+            digest: self.digest,
+            inverse_links: self.emit_inverse,
+            namespace_overrides: self.namespace_for.iter().cloned().collect(),
+            preserve_namespaces: self.preserve_namespaces,
+            reconstruction_graph: self.reconstruction_graph.clone(),
+            document_graphs: self.document_graph.clone(),
+            emit_namespace_decls: self.emit_namespace_decls,
+            node_ids: self.node_ids,
+            max_input_bytes: self.max_input_bytes,
+            max_triples: self.max_triples,
+            max_elements: self.max_elements,
+            infer_datatypes: self.infer_datatypes,
+            vocabulary: if self.distinct_text_predicate {
+                vocab::ModelVocabulary::distinct_values()
+            } else {
+                vocab::ModelVocabulary::default()
+            },
+            json_leaves: self.json_leaves,
+            text_as_node: self.text_as_node,
+            class_map: self.class_map.iter().cloned().collect(),
+            promoted_attributes: self.promote_attr.iter().cloned().collect(),
+            flag_elements: self.flag_elements.iter().cloned().collect(),
+            binary: self.binary.iter().cloned().collect(),
+            invalid_binary: self.invalid_binary,
+            datatypes: self.datatype.iter().cloned().collect(),
+            embedded_json: self.parse_embedded_json.iter().cloned().collect(),
+            name_encoding: self.name_encoding,
+            source_offsets: self.source_offsets,
+            branch_text: if self.mixed_content {
+                convert::BranchTextPolicy::Mixed
+            } else {
+                self.branch_text
+            },
+            sort_attributes: self.sort_attributes,
+            conditions: self.conditions.clone(),
+            invalid_utf8: self.invalid_utf8,
+            collapse_wrappers: self.collapse_wrappers,
+            sample_per_path: self.sample_per_path,
+            sample_counts: self.sample_counts,
+            instance_counts: self.instance_counts,
+            empty_markers: self.empty_markers,
+            element_kinds: self.element_kinds,
+            emit_qnames: self.emit_qnames,
+            only_predicates: self.only_predicates.clone(),
+            #[cfg(feature = "rdf-star")]
+            value_annotations: self.annotate_values,
+            // Only `convert` reports warnings, with --warnings
+            collect_warnings: false,
+        }
+    }
+}
+
+/// How `convert` writes its output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum OutputFormat {
+    /// N-Triples, or N-Quads for triples in named graphs.
+    #[value(name = "ntriples")]
+    NTriples,
+    /// Turtle, abbreviated with the --prefixes.
+    Turtle,
+    /// TriG, abbreviated with the --prefixes, with a block per named graph.
+    #[value(name = "trig")]
+    TriG,
+    /// RDF/XML, with the --prefixes as XML namespaces.
+    #[value(name = "rdfxml")]
+    RdfXml,
+    /// One JSON object per triple and line.
+    #[value(name = "ndjson")]
+    NdJson,
+    /// JSON-LD, with a context for the model terms and the --prefixes.
+    #[value(name = "jsonld")]
+    JsonLd,
+}
+
+impl OutputFormat {
+    /// Returns whether output can be appended to an existing file. Formats holding a single
+    /// document replace the file instead, as a second document would make it invalid.
+    fn appends(self) -> bool {
+        !matches!(self, OutputFormat::RdfXml | OutputFormat::JsonLd)
+    }
+}
+
+/// Where `convert` reports progress, warnings and errors.
+#[cfg(all(unix, feature = "syslog"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum LogTarget {
+    /// Warnings and errors on stderr.
+    Stderr,
+    /// Every record to the local syslog socket, which journald also reads.
+    Syslog,
+}
+
+/// Severity of a [`Log`] record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+    Error,
+    Warning,
+    Info,
+}
+
+/// Reports conversion progress, warnings and errors.
+///
+/// On stderr only warnings and errors are printed, as plain messages. Syslog receives every
+/// record, followed by its structured fields.
+enum Log {
+    Stderr,
+    #[cfg(all(unix, feature = "syslog"))]
+    Syslog(syslog::Syslog),
+}
+
+impl Log {
+    fn info(&self, message: &str, fields: &[(&str, &str)]) {
+        self.send(Level::Info, message, fields);
+    }
+
+    fn warn(&self, message: &str, fields: &[(&str, &str)]) {
+        self.send(Level::Warning, message, fields);
+    }
+
+    fn error(&self, message: &str, fields: &[(&str, &str)]) {
+        self.send(Level::Error, message, fields);
+    }
+
+    #[cfg_attr(not(all(unix, feature = "syslog")), allow(unused_variables))]
+    fn send(&self, level: Level, message: &str, fields: &[(&str, &str)]) {
+        #[cfg(all(unix, feature = "syslog"))]
+        if let Log::Syslog(log) = self {
+            let severity = match level {
+                Level::Error => syslog::Severity::Error,
+                Level::Warning => syslog::Severity::Warning,
+                Level::Info => syslog::Severity::Info,
+            };
+            // Fall back to stderr rather than losing warnings and errors
+            if log.send(severity, &syslog::record(message, fields)).is_ok() {
+                return;
+            }
+        }
+        if level != Level::Info {
+            eprintln!("{message}");
+        }
+    }
+}
+
+/// Converts `xml` into `w` and reports the outcome, writing metrics to `metrics_file` if given.
+/// Returns the exit status if the conversion failed.
+///
+/// With the `ctrlc` feature, Ctrl-C stops the conversion between two triples and flushes the
+/// output written so far, so it stays valid, before failing with status 130. A second Ctrl-C
+/// exits immediately.
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+fn convert_and_report(
+    xml: Vec<String>,
+    w: &mut dyn writer::RdfWriter,
+    namespace: &str,
+    options: &convert::ConversionOptions,
+    warn_empty: bool,
+    metrics_file: Option<&str>,
+    log: &Log,
+) -> Result<(), i32> {
+    for input in &xml {
+        log.info("converting", &[("file", input)]);
+    }
+    #[cfg(feature = "metrics")]
+    let started = std::time::Instant::now();
+    #[cfg(feature = "ctrlc")]
+    let result = {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        let interrupted = Arc::new(AtomicBool::new(false));
+        let flag = interrupted.clone();
+        if let Err(e) = ctrlc::set_handler(move || {
+            if flag.swap(true, Ordering::SeqCst) {
+                std::process::exit(130);
+            }
+        }) {
+            log.warn(&format!("Warning: cannot handle Ctrl-C: {e}"), &[]);
+        }
+
+        let mut w = writer::InterruptibleWriter::new(w, interrupted.clone());
+        let result = convert::parse_xml_with_options(xml, &mut w, namespace, options);
+        if interrupted.load(Ordering::SeqCst) {
+            if let Err(e) = writer::RdfWriter::flush(&mut w) {
+                log.error(&format!("Error flushing output: {e}"), &[]);
+            }
+            log.warn(
+                "Interrupted, output written up to the last complete triple",
+                &[],
+            );
+            return Err(130);
+        }
+        result
+    };
+    #[cfg(not(feature = "ctrlc"))]
+    let result = convert::parse_xml_with_options(xml, w, namespace, options);
+
+    #[cfg(feature = "metrics")]
+    if let Some(path) = metrics_file {
+        let mut metrics = metrics::Metrics::default();
+        metrics.record(&result, started.elapsed());
+        if let Err(e) = std::fs::File::create(path).and_then(|file| metrics.write(file)) {
+            log.error(&format!("Error writing metrics to {path}: {e}"), &[]);
+        }
+    }
+    report(result, warn_empty, log)
+}
+
+/// Reports a failed or truncated conversion, and empty input documents. Returns the exit status
+/// if the conversion failed.
+fn report(
+    result: Result<convert::ConversionSummary, error::ConvertError>,
+    warn_empty: bool,
+    log: &Log,
+) -> Result<(), i32> {
+    match result {
+        Ok(summary) => {
+            for empty in &summary.empty_documents {
+                let source = empty.source.as_deref().unwrap_or("<input>");
+                if empty.zero_bytes {
+                    log.warn(
+                        &format!("Warning: {source} is empty (0 bytes)"),
+                        &[("file", source)],
+                    );
+                } else if warn_empty {
+                    log.warn(
+                        &format!("Warning: {source} contains no XML elements"),
+                        &[("file", source)],
+                    );
+                }
+            }
+            for warning in &summary.warnings {
+                // Truncation is reported below either way
+                if warning.kind == convert::WarningKind::Truncated {
+                    continue;
+                }
+                let kind = format!("{:?}", warning.kind);
+                let source = warning.source.as_deref().unwrap_or("<input>");
+                log.warn(
+                    &format!("Warning: {warning}"),
+                    &[("kind", &kind), ("path", &warning.path), ("file", source)],
+                );
+            }
+            let triples = summary.triples.to_string();
+            if summary.truncated {
+                log.warn(
+                    &format!("Stopped after {triples} triples (--max-triples)"),
+                    &[("triples", &triples)],
+                );
+            }
+            log.info("converted", &[("triples", &triples)]);
+            Ok(())
+        }
+        Err(e) => {
+            match hint(&e) {
+                Some(hint) => log.error(&format!("Error: {e} ({hint})"), &[]),
+                None => log.error(&format!("Error: {e}"), &[]),
+            }
+            Err(exit_code(&e))
+        }
+    }
+}
+
+/// Returns the option that works around a failed conversion, if any.
+fn hint(e: &error::ConvertError) -> Option<&'static str> {
+    use error::ConvertError;
+    match e {
+        ConvertError::InvalidBinary { .. } => Some("see --invalid-binary warn"),
+        ConvertError::InvalidUtf8 { .. } => Some("see --invalid-utf8 lossy"),
+        ConvertError::InputTooLarge { .. } => Some("see --max-input-bytes"),
+        ConvertError::UnsafeName { .. } => Some("see --name-encoding percent or slug"),
+        _ => None,
+    }
+}
+
+/// Data namespace used unless another is given or inferred.
+const DEFAULT_NAMESPACE: &str = "https://decisym.ai/xml2rdf/data";
+
+/// Exit status for invalid options, following the BSD `sysexits.h` convention.
+const EXIT_USAGE: i32 = 64;
+
+/// Returns the exit status for a failed conversion, following the BSD `sysexits.h` convention.
+fn exit_code(e: &error::ConvertError) -> i32 {
+    use error::ConvertError;
+    match e {
+        ConvertError::ReservedNamespace { .. } => EXIT_USAGE,
+        ConvertError::InvalidIri { .. }
+        | ConvertError::InvalidBinary { .. }
+        | ConvertError::InvalidUtf8 { .. }
+        | ConvertError::InputTooLarge { .. }
+        | ConvertError::MissingIdentity { .. }
+        | ConvertError::InvalidDocumentIri { .. }
+        | ConvertError::DuplicateGraph { .. }
+        | ConvertError::UnsafeName { .. } => 65,
+        ConvertError::Open { .. } => 66,
+        ConvertError::Io(_) => 74,
+    }
+}
+
+/// Returns the writer of `format` writing to `out`.
+fn output_writer(
+    out: impl std::io::Write + 'static,
+    format: OutputFormat,
+    prefixes: &prefixes::PrefixMap,
+) -> Box<dyn writer::RdfWriter> {
+    match format {
+        OutputFormat::NTriples => Box::new(writer::FileWriter::new(out)),
+        OutputFormat::Turtle => Box::new(writer::TurtleWriter::new(out, prefixes)),
+        OutputFormat::TriG => Box::new(writer::TriGWriter::new(out, prefixes)),
+        OutputFormat::RdfXml => Box::new(writer::RdfXmlWriter::new(out, prefixes)),
+        OutputFormat::NdJson => Box::new(writer::NdJsonWriter::new(out)),
+        OutputFormat::JsonLd => Box::new(writer::JsonLdWriter::new(out, prefixes)),
+    }
+}
+
+/// Returns the format `file` is named like, if it is Turtle, TriG, RDF/XML or JSON-LD.
+fn guess_format(file: &str) -> Option<OutputFormat> {
+    let extension = std::path::Path::new(file).extension()?.to_str()?;
+    if extension.eq_ignore_ascii_case("jsonld") {
+        return Some(OutputFormat::JsonLd);
+    }
+    match oxrdfio::RdfFormat::from_extension(extension)? {
+        oxrdfio::RdfFormat::Turtle => Some(OutputFormat::Turtle),
+        oxrdfio::RdfFormat::TriG => Some(OutputFormat::TriG),
+        oxrdfio::RdfFormat::RdfXml => Some(OutputFormat::RdfXml),
+        _ => None,
+    }
+}
+
+/// Parses an IRI given on the command line.
+fn parse_named_node(iri: &str) -> Result<oxrdf::NamedNode, oxrdf::IriParseError> {
+    oxrdf::NamedNode::new(iri)
+}
+
+/// Parses a `KEY=IRI` mapping, such as a class mapping, given on the command line.
+fn parse_class_mapping(mapping: &str) -> Result<(String, oxrdf::NamedNode), String> {
+    let (key, iri) = mapping
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=IRI, got '{mapping}'"))?;
+    let class = oxrdf::NamedNode::new(iri).map_err(|e| e.to_string())?;
+    Ok((key.to_string(), class))
+}
+
+/// Parses a predicate given on the command line: the local name of a model predicate such as
+/// `hasValue`, or an IRI.
+fn parse_predicate(predicate: &str) -> Result<oxrdf::NamedNode, String> {
+    let iri = if predicate.contains(':') {
+        predicate.to_string()
+    } else {
+        format!("{}{predicate}", vocab::X2R)
+    };
+    oxrdf::NamedNode::new(iri).map_err(|e| e.to_string())
+}
+
+/// Parses a class IRI path delimiter given on the command line.
+fn parse_path_delimiter(delimiter: &str) -> Result<String, String> {
+    match delimiter {
+        "" => Err("the path delimiter must not be empty".to_string()),
+        "dot" => Ok(".".to_string()),
+        "slash" => Ok("/".to_string()),
+        other => Ok(other.to_string()),
+    }
+}
+
+/// Parses a document identity given on the command line: `source`, `file-name` or `@NAME`.
+fn parse_document_identity(identity: &str) -> Result<convert::DocumentIdentity, String> {
+    match identity {
+        "source" => Ok(convert::DocumentIdentity::Source),
+        "file-name" => Ok(convert::DocumentIdentity::FileName),
+        _ => match identity.strip_prefix('@') {
+            Some(attribute) if !attribute.is_empty() => Ok(
+                convert::DocumentIdentity::RootAttribute(attribute.to_string()),
+            ),
+            _ => Err(format!(
+                "expected 'source', 'file-name' or '@NAME', got '{identity}'"
+            )),
+        },
+    }
+}
+
+/// Parses the --document-iri of document nodes.
+fn parse_document_iri(iri: &str) -> Result<convert::DocumentIri, String> {
+    match iri {
+        "minted" => Ok(convert::DocumentIri::Minted),
+        "file-name" => Ok(convert::DocumentIri::FileName),
+        iri => match oxrdf::NamedNode::new(iri) {
+            Ok(iri) => Ok(convert::DocumentIri::Explicit(iri)),
+            Err(e) => Err(format!("expected minted, file-name or an IRI: {e}")),
+        },
+    }
+}
+
+/// Parses the --node-ids strategy, with the hash of deterministic IDs after a colon.
+fn parse_node_ids(strategy: &str) -> Result<convert::NodeIds, String> {
+    let hash = match strategy {
+        "uuid" => return Ok(convert::NodeIds::Uuid),
+        "blank" => return Ok(convert::NodeIds::Blank),
+        "urn" => return Ok(convert::NodeIds::Urn),
+        "deterministic" => convert::IdHash::default(),
+        _ => match strategy.strip_prefix("deterministic:") {
+            Some(hash) => clap::ValueEnum::from_str(hash, false)
+                .map_err(|_| format!("unknown deterministic node ID hash '{hash}'"))?,
+            None => {
+                return Err(format!(
+                    "expected uuid, blank, urn or deterministic[:HASH], got '{strategy}'"
+                ))
+            }
+        },
+    };
+    Ok(convert::NodeIds::Deterministic { hash })
+}
+
+/// Parses a `KEY=DELIM` value list delimiter given on the command line.
+fn parse_split_value(mapping: &str) -> Result<(String, String), String> {
+    match mapping.split_once('=') {
+        Some((key, delimiter)) if !delimiter.is_empty() => {
+            Ok((key.to_string(), delimiter.to_string()))
+        }
+        _ => Err(format!("expected KEY=DELIM, got '{mapping}'")),
+    }
+}
+
+/// Parses a source file IRI scheme given on the command line.
+fn parse_source_iris(scheme: &str) -> Result<convert::SourceIris, String> {
+    match scheme {
+        "file" => Ok(convert::SourceIris::File),
+        "opaque" => Ok(convert::SourceIris::Opaque),
+        base => match oxrdf::NamedNode::new(base) {
+            Ok(_) => Ok(convert::SourceIris::Base(base.to_string())),
+            Err(e) => Err(format!("expected file, opaque or a base IRI: {e}")),
+        },
+    }
+}
+
+/// Parses the --conversion-time of document nodes.
+fn parse_conversion_time(time: &str) -> Result<convert::ConversionTime, String> {
+    match time {
+        "omit" => Ok(convert::ConversionTime::Omit),
+        "now" => Ok(convert::ConversionTime::Now),
+        time if datatype::is_valid(time, oxrdf::vocab::xsd::DATE_TIME) == Some(true) => {
+            Ok(convert::ConversionTime::Fixed(time.to_string()))
+        }
+        _ => Err(format!(
+            "expected now, omit or an xsd:dateTime, got '{time}'"
+        )),
+    }
+}
+
+/// Parses a `KEY@NAME` or `KEY@NAME=IRI` attribute promotion given on the command line.
+fn parse_promotion(promotion: &str) -> Result<(String, convert::PromotedAttribute), String> {
+    let (key, attribute) = promotion
+        .split_once('@')
+        .ok_or_else(|| format!("expected KEY@NAME, got '{promotion}'"))?;
+    let (attribute, predicate) = match attribute.split_once('=') {
+        Some((attribute, iri)) => (
+            attribute,
+            Some(oxrdf::NamedNode::new(iri).map_err(|e| e.to_string())?),
+        ),
+        None => (attribute, None),
+    };
+    Ok((
+        key.to_string(),
+        convert::PromotedAttribute {
+            attribute: attribute.to_string(),
+            predicate,
+        },
+    ))
+}
+
+/// Parses a `KEY=NAMESPACE` data namespace override given on the command line.
+fn parse_namespace_override(mapping: &str) -> Result<(String, String), String> {
+    let (key, namespace) = mapping
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=NAMESPACE, got '{mapping}'"))?;
+    Ok((key.to_string(), namespace.to_string()))
+}
+
+/// Parses a `KEY=MODE` embedded JSON mapping given on the command line.
+fn parse_embedded_json(mapping: &str) -> Result<(String, convert::EmbeddedJson), String> {
+    let (key, mode) = mapping
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=MODE, got '{mapping}'"))?;
+    let mode = clap::ValueEnum::from_str(mode, true)?;
+    Ok((key.to_string(), mode))
+}
+
+/// Parses a `KEY=ENCODING` binary content mapping given on the command line.
+fn parse_binary_mapping(mapping: &str) -> Result<(String, datatype::BinaryEncoding), String> {
+    let (key, encoding) = mapping
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=ENCODING, got '{mapping}'"))?;
+    let encoding = clap::ValueEnum::from_str(encoding, true)?;
+    Ok((key.to_string(), encoding))
+}
+
+/// Parses a chunk size given on the command line, which must be at least 1.
+fn parse_chunk_size(n: &str) -> Result<usize, String> {
+    match n.parse::<usize>() {
+        Ok(0) => Err("chunks must hold at least 1 triple".to_string()),
+        Ok(n) => Ok(n),
+        Err(e) => Err(e.to_string()),
+    }
 }
 
 fn main() {
@@ -75,25 +1215,461 @@ fn main() {
     match &cli.command {
         Some(Commands::Convert {
             namespace,
+            infer_namespace,
             xml,
             output_file,
+            format,
+            prefixes,
+            conversion,
+            max_triples_per_file,
+            dedup_existing,
+            cache,
+            atomic,
+            warn_empty,
+            warnings,
+            #[cfg(all(unix, feature = "syslog"))]
+            log_target,
+            #[cfg(all(unix, feature = "syslog"))]
+            syslog_socket,
+            #[cfg(feature = "metrics")]
+            metrics_file,
+            #[cfg(feature = "canonical")]
+            canonical,
+            #[cfg(feature = "compress")]
+            compress,
+            #[cfg(feature = "oxigraph")]
+            store,
+            #[cfg(feature = "oxigraph")]
+            store_batch_size,
+            #[cfg(feature = "oxigraph")]
+            replace_graphs,
         }) => {
-            let mut w: Box<dyn writer::RdfWriter> = if let Some(file) = output_file {
-                match writer::FileWriter::to_file(file.clone()) {
+            let mut options = conversion.options();
+            options.collect_warnings = *warnings;
+
+            #[cfg(all(unix, feature = "syslog"))]
+            let log = match log_target {
+                LogTarget::Stderr => Log::Stderr,
+                LogTarget::Syslog => match syslog::Syslog::connect(syslog_socket, "xml2rdf") {
                     Err(e) => {
-                        eprintln!("Error opening file for writing: {e}");
-                        return;
+                        eprintln!("Warning: cannot connect to syslog at {syslog_socket}: {e}");
+                        Log::Stderr
                     }
-                    Ok(v) => Box::new(v),
+                    Ok(v) => Log::Syslog(v),
+                },
+            };
+            #[cfg(not(all(unix, feature = "syslog")))]
+            let log = Log::Stderr;
+
+            // Report contradictions before any file is opened
+            let mut invalid = options.validate().err().unwrap_or_default();
+            let format = match (format, output_file) {
+                (Some(format), _) => *format,
+                (None, Some(file)) if max_triples_per_file.is_none() => {
+                    guess_format(file).unwrap_or(OutputFormat::NTriples)
                 }
+                (None, _) => OutputFormat::NTriples,
+            };
+            #[cfg(feature = "canonical")]
+            let format = if *canonical {
+                OutputFormat::NTriples
             } else {
-                Box::new(writer::FileWriter::to_stdout())
+                format
+            };
+            let single_graph = match format {
+                OutputFormat::Turtle => Some("Turtle"),
+                OutputFormat::RdfXml => Some("RDF/XML"),
+                _ => None,
+            };
+            if let (Some(name), Some(_)) = (single_graph, &options.reconstruction_graph) {
+                invalid.push(error::ConfigError::InvalidValue {
+                    option: "reconstruction_graph",
+                    message: format!("{name} output has no named graphs"),
+                });
+            }
+            if let (Some(name), Some(_)) = (single_graph, &options.document_graphs) {
+                invalid.push(error::ConfigError::InvalidValue {
+                    option: "document_graphs",
+                    message: format!("{name} output has no named graphs"),
+                });
+            }
+            #[cfg(feature = "compress")]
+            if compress.is_some() && format != OutputFormat::NTriples {
+                invalid.push(error::ConfigError::InvalidValue {
+                    option: "compress",
+                    message: "only N-Triples output is compressed".to_string(),
+                });
+            }
+            if !invalid.is_empty() {
+                for e in &invalid {
+                    log.error(&format!("Error: {e}"), &[]);
+                }
+                std::process::exit(EXIT_USAGE);
+            }
+
+            let prefixes = match prefixes {
+                Some(path) => match prefixes::PrefixMap::load(path) {
+                    Err(e) => {
+                        log.error(&format!("Error reading prefixes from {path}: {e}"), &[]);
+                        std::process::exit(66);
+                    }
+                    Ok(v) => v,
+                },
+                None => prefixes::PrefixMap::default(),
+            };
+            prefixes.expand_options(&mut options);
+            // Sample the first file as given, so skipping it as unchanged keeps the namespace
+            let namespace = match (namespace, xml.first()) {
+                (Some(namespace), _) => namespace.clone(),
+                (None, Some(sample)) if *infer_namespace => {
+                    let inferred = std::fs::File::open(sample)
+                        .ok()
+                        .and_then(convert::infer_namespace);
+                    match inferred {
+                        Some(namespace) => {
+                            log.info("inferred data namespace", &[("namespace", &namespace)]);
+                            namespace
+                        }
+                        None => DEFAULT_NAMESPACE.to_string(),
+                    }
+                }
+                (None, _) => DEFAULT_NAMESPACE.to_string(),
+            };
+            let namespace = namespace.as_str();
+            #[cfg(feature = "metrics")]
+            let metrics_file = metrics_file.as_deref();
+            #[cfg(not(feature = "metrics"))]
+            let metrics_file = None;
+
+            let mut cache = cache.as_deref().map(cache::ConversionCache::open);
+            let xml = match &mut cache {
+                Some(cache) => {
+                    if cache.discarded() {
+                        log.warn(
+                            "Warning: ignoring the unreadable conversion cache, converting every file",
+                            &[],
+                        );
+                    }
+                    let changed = cache.changed(xml.clone());
+                    let skipped = (xml.len() - changed.len()).to_string();
+                    log.info("skipping unchanged files", &[("count", &skipped)]);
+                    if changed.is_empty() {
+                        return;
+                    }
+                    changed
+                }
+                None => xml.clone(),
             };
 
-            match convert::parse_xml(xml.clone(), w.as_mut(), namespace) {
-                Ok(_) => {}
-                Err(e) => eprintln!("Error writing: {}", e),
+            let converted = 'convert: {
+                #[cfg(feature = "oxigraph")]
+                if let Some(path) = store {
+                    let store = match oxigraph::store::Store::open(path) {
+                        Err(e) => {
+                            log.error(&format!("Error opening store: {e}"), &[]);
+                            std::process::exit(73);
+                        }
+                        Ok(v) => v,
+                    };
+                    let mut w =
+                        writer::OxigraphStoreWriter::new(store).with_batch_size(*store_batch_size);
+                    if *replace_graphs {
+                        w = w.replacing_graphs();
+                    }
+                    break 'convert convert_and_report(
+                        xml.clone(),
+                        &mut w,
+                        namespace,
+                        &options,
+                        *warn_empty,
+                        metrics_file,
+                        &log,
+                    );
+                }
+
+                #[cfg(feature = "canonical")]
+                if *canonical {
+                    let out: Box<dyn std::io::Write> = if let Some(file) = output_file {
+                        // A canonical dataset is only canonical on its own, so replace the file
+                        match std::fs::File::create(file) {
+                            Err(e) => {
+                                log.error(&format!("Error opening file for writing: {e}"), &[]);
+                                std::process::exit(73);
+                            }
+                            Ok(v) => Box::new(std::io::BufWriter::new(v)),
+                        }
+                    } else {
+                        Box::new(std::io::stdout())
+                    };
+                    let mut w = canonical::CanonicalWriter::new(out);
+                    break 'convert convert_and_report(
+                        xml.clone(),
+                        &mut w,
+                        namespace,
+                        &options,
+                        *warn_empty,
+                        metrics_file,
+                        &log,
+                    );
+                }
+
+                #[cfg(feature = "compress")]
+                if let Some(compression) = compress {
+                    let convert = |w: &mut dyn writer::RdfWriter| {
+                        convert_and_report(
+                            xml.clone(),
+                            w,
+                            namespace,
+                            &options,
+                            *warn_empty,
+                            metrics_file,
+                            &log,
+                        )
+                    };
+                    let finished = if let (true, Some(file)) = (*atomic, output_file) {
+                        writer::AtomicFile::create(file)
+                            .and_then(|out| writer::CompressedWriter::new(out, *compression))
+                            .map(|out| {
+                                let mut w = writer::FileWriter::new(out);
+                                convert(&mut w)
+                                    .map(|()| w.finish().and_then(writer::AtomicFile::finish))
+                            })
+                    } else {
+                        let out: std::io::Result<Box<dyn std::io::Write>> = match output_file {
+                            Some(file) => std::fs::OpenOptions::new()
+                                .create(true)
+                                .append(true)
+                                .open(file)
+                                .map(|file| Box::new(file) as Box<dyn std::io::Write>),
+                            None => Ok(Box::new(std::io::stdout())),
+                        };
+                        // Failed and interrupted conversions end the stream too, so the output
+                        // written so far can be decompressed like uncompressed partial output
+                        out.and_then(|out| writer::CompressedWriter::new(out, *compression))
+                            .map(|out| {
+                                let mut w = writer::FileWriter::new(out);
+                                let converted = convert(&mut w);
+                                match w.finish() {
+                                    Ok(_) => converted.map(Ok),
+                                    Err(e) => converted.and(Ok(Err(e))),
+                                }
+                            })
+                    };
+                    let finished = match finished {
+                        Err(e) => {
+                            log.error(&format!("Error opening file for writing: {e}"), &[]);
+                            std::process::exit(73);
+                        }
+                        Ok(v) => v,
+                    };
+                    // A failed atomic conversion leaves the output file untouched
+                    break 'convert finished.and_then(|done| {
+                        done.map_err(|e| {
+                            log.error(&format!("Error finishing compressed output: {e}"), &[]);
+                            74
+                        })
+                    });
+                }
+
+                if let (true, Some(file)) = (*atomic, output_file) {
+                    let out = if format.appends() {
+                        writer::AtomicFile::create(file)
+                    } else {
+                        writer::AtomicFile::replace(file)
+                    };
+                    let out = match out {
+                        Err(e) => {
+                            log.error(&format!("Error opening file for writing: {e}"), &[]);
+                            std::process::exit(73);
+                        }
+                        Ok(v) => v,
+                    };
+                    let convert = |w: &mut dyn writer::RdfWriter| {
+                        convert_and_report(
+                            xml.clone(),
+                            w,
+                            namespace,
+                            &options,
+                            *warn_empty,
+                            metrics_file,
+                            &log,
+                        )
+                    };
+                    // A failed conversion drops its writer, which removes the temporary file
+                    let finished = match format {
+                        OutputFormat::Turtle => {
+                            let mut w = writer::TurtleWriter::new(out, &prefixes);
+                            convert(&mut w)
+                                .map(|()| w.into_inner().and_then(writer::AtomicFile::finish))
+                        }
+                        OutputFormat::TriG => {
+                            let mut w = writer::TriGWriter::new(out, &prefixes);
+                            convert(&mut w)
+                                .map(|()| w.into_inner().and_then(writer::AtomicFile::finish))
+                        }
+                        OutputFormat::RdfXml => {
+                            let mut w = writer::RdfXmlWriter::new(out, &prefixes);
+                            convert(&mut w)
+                                .map(|()| w.into_inner().and_then(writer::AtomicFile::finish))
+                        }
+                        OutputFormat::NdJson => {
+                            let mut w = writer::NdJsonWriter::new(out);
+                            convert(&mut w)
+                                .map(|()| w.into_inner().and_then(writer::AtomicFile::finish))
+                        }
+                        OutputFormat::JsonLd => {
+                            let mut w = writer::JsonLdWriter::new(out, &prefixes);
+                            convert(&mut w)
+                                .map(|()| w.into_inner().and_then(writer::AtomicFile::finish))
+                        }
+                        OutputFormat::NTriples => {
+                            let mut w = writer::FileWriter::new(out);
+                            convert(&mut w).map(|()| w.finish())
+                        }
+                    };
+                    break 'convert finished.and_then(|moved| {
+                        moved.map_err(|e| {
+                            log.error(&format!("Error moving output into place: {e}"), &[]);
+                            74
+                        })
+                    });
+                }
+
+                let mut w: Box<dyn writer::RdfWriter> = if let Some(file) = output_file {
+                    if let Some(max) = max_triples_per_file {
+                        Box::new(writer::RotatingFileWriter::new(file, *max))
+                    } else {
+                        match std::fs::OpenOptions::new()
+                            .create(true)
+                            .append(format.appends())
+                            .write(true)
+                            .truncate(!format.appends())
+                            .open(file)
+                        {
+                            Err(e) => {
+                                log.error(&format!("Error opening file for writing: {e}"), &[]);
+                                std::process::exit(73);
+                            }
+                            Ok(v) => output_writer(v, format, &prefixes),
+                        }
+                    }
+                } else {
+                    output_writer(std::io::stdout(), format, &prefixes)
+                };
+                if let (true, Some(file)) = (*dedup_existing, output_file) {
+                    w = match writer::DedupWriter::with_existing(w, file) {
+                        Err(e) => {
+                            log.error(&format!("Error reading existing output {file}: {e}"), &[]);
+                            std::process::exit(74);
+                        }
+                        Ok(v) => Box::new(v),
+                    };
+                }
+
+                convert_and_report(
+                    xml.clone(),
+                    w.as_mut(),
+                    namespace,
+                    &options,
+                    *warn_empty,
+                    metrics_file,
+                    &log,
+                )
+            };
+            if let Err(code) = converted {
+                std::process::exit(code);
+            }
+            if let Some(cache) = cache {
+                if let Err(e) = cache.save() {
+                    log.error(&format!("Error writing the conversion cache: {e}"), &[]);
+                    std::process::exit(74);
+                }
+            }
+        }
+        #[cfg(feature = "oxigraph")]
+        Some(Commands::Query {
+            namespace,
+            xml,
+            sparql,
+            format,
+            prefixes,
+            conversion,
+        }) => {
+            let mut options = conversion.options();
+            if let Err(invalid) = options.validate() {
+                for e in &invalid {
+                    eprintln!("Error: {e}");
+                }
+                std::process::exit(EXIT_USAGE);
+            }
+            if let Some(path) = prefixes {
+                match prefixes::PrefixMap::load(path) {
+                    Err(e) => {
+                        eprintln!("Error reading prefixes from {path}: {e}");
+                        std::process::exit(66);
+                    }
+                    Ok(prefixes) => prefixes.expand_options(&mut options),
+                }
+            }
+            match query::query_xml(xml.clone(), namespace, &options, sparql) {
+                Ok(solutions) => {
+                    if let Err(e) = solutions.write(std::io::stdout().lock(), *format) {
+                        eprintln!("Error writing results: {e}");
+                        std::process::exit(74);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error querying: {e}");
+                    std::process::exit(match &e {
+                        query::QueryError::Convert(e) => exit_code(e),
+                        query::QueryError::Storage(_) => 74,
+                        query::QueryError::Evaluation(_) | query::QueryError::NotSelect => {
+                            EXIT_USAGE
+                        }
+                    });
+                }
+            }
+        }
+        Some(Commands::Stats { input }) => match stats::load_graph(input) {
+            Ok(graph) => print!("{}", stats::graph_stats(&graph)),
+            Err(e) => {
+                eprintln!("Error reading {input}: {e}");
+                std::process::exit(2);
+            }
+        },
+        Some(Commands::Diff { old, new, summary }) => {
+            let load = |path: &String| {
+                stats::load_graph(path).unwrap_or_else(|e| {
+                    eprintln!("Error reading {path}: {e}");
+                    std::process::exit(2);
+                })
+            };
+            let diff = diff::diff_graphs(&load(old), &load(new));
+            if *summary {
+                println!("{}", diff.summary());
+            } else {
+                print!("{diff}");
+            }
+            if !diff.is_empty() {
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Check { xml }) => {
+            let mut status = 0;
+            for file in xml {
+                match check::check_file(file) {
+                    Ok(Ok(())) => println!("{file}: ok"),
+                    Ok(Err(e)) => {
+                        println!("{file}: {e}");
+                        status = status.max(1);
+                    }
+                    Err(e) => {
+                        eprintln!("Error reading {file}: {e}");
+                        status = 2;
+                    }
+                }
             }
+            std::process::exit(status);
         }
         None => {}
     }
@@ -0,0 +1,104 @@
+// Copyright (c) 2024-2025, Decisym, LLC
+// Licensed under the BSD 3-Clause License (see LICENSE file in the project root).
+
+//! # XML2RDF Metrics
+//!
+//! Exports conversion counters in the
+//! [Prometheus text format](https://prometheus.io/docs/instrumenting/exposition_formats/), for
+//! conversions running as a batch service.
+//!
+//! ## Overview
+//! - [`Metrics::record`] adds the outcome of a conversion to the counters, so a long running
+//!   process can accumulate several conversions.
+//! - [`Metrics::write`] writes the counters, e.g. to a file read by the node exporter's textfile
+//!   collector.
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+use crate::convert::ConversionSummary;
+use crate::error::ConvertError;
+
+/// Counters of the conversions recorded so far.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Metrics {
+    /// Number of input documents converted.
+    pub documents: u64,
+    /// Number of triples written.
+    pub triples: u64,
+    /// Number of conversions that failed.
+    pub errors: u64,
+    /// Number of input bytes read.
+    pub input_bytes: u64,
+    /// Time spent converting.
+    pub duration: Duration,
+}
+
+impl Metrics {
+    /// Adds the outcome of a conversion that took `duration`. A failed conversion counts as an
+    /// error; the documents, triples and bytes it got through before failing are not known.
+    pub fn record(&mut self, result: &Result<ConversionSummary, ConvertError>, duration: Duration) {
+        match result {
+            Ok(summary) => {
+                self.documents += summary.documents as u64;
+                self.triples += summary.triples as u64;
+                self.input_bytes += summary.input_bytes;
+            }
+            Err(_) => self.errors += 1,
+        }
+        self.duration += duration;
+    }
+
+    /// Writes the counters in the Prometheus text format:
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use xml2rdf::metrics::Metrics;
+    ///
+    /// let metrics = Metrics {
+    ///     triples: 28,
+    ///     duration: Duration::from_millis(1500),
+    ///     ..Default::default()
+    /// };
+    /// let mut out = Vec::new();
+    /// metrics.write(&mut out).unwrap();
+    /// let text = String::from_utf8(out).unwrap();
+    /// assert!(text.contains("\nxml2rdf_triples_total 28\n"));
+    /// assert!(text.contains("\nxml2rdf_duration_seconds_total 1.5\n"));
+    /// ```
+    pub fn write(&self, mut out: impl Write) -> io::Result<()> {
+        let counters = [
+            (
+                "xml2rdf_documents_total",
+                "Number of input documents converted.",
+                self.documents.to_string(),
+            ),
+            (
+                "xml2rdf_triples_total",
+                "Number of triples written.",
+                self.triples.to_string(),
+            ),
+            (
+                "xml2rdf_errors_total",
+                "Number of conversions that failed.",
+                self.errors.to_string(),
+            ),
+            (
+                "xml2rdf_input_bytes_total",
+                "Number of input bytes read.",
+                self.input_bytes.to_string(),
+            ),
+            (
+                "xml2rdf_duration_seconds_total",
+                "Time spent converting, in seconds.",
+                self.duration.as_secs_f64().to_string(),
+            ),
+        ];
+        for (name, help, value) in counters {
+            writeln!(out, "# HELP {name} {help}")?;
+            writeln!(out, "# TYPE {name} counter")?;
+            writeln!(out, "{name} {value}")?;
+        }
+        Ok(())
+    }
+}
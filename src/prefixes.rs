@@ -0,0 +1,220 @@
+// Copyright (c) 2024-2025, Decisym, LLC
+// Licensed under the BSD 3-Clause License (see LICENSE file in the project root).
+
+//! # XML2RDF Prefixes
+//!
+//! Reads namespace prefixes from a single file, so they are configured in one place.
+//!
+//! ## Overview
+//! - [`PrefixMap::parse`] reads Turtle `@prefix` / `PREFIX` declarations, or a JSON object
+//!   mapping each prefix to its namespace IRI.
+//! - [`PrefixMap::expand_options`] expands prefixed names such as `xsd:gYear` in the IRI-valued
+//!   conversion options.
+//! - [`crate::writer::TurtleWriter`] declares the prefixes and abbreviates IRIs with them.
+
+use oxrdf::NamedNode;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::convert::{ConversionOptions, DocumentIri};
+use crate::json;
+
+/// Namespace prefixes, in declaration order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrefixMap {
+    prefixes: Vec<(String, String)>,
+}
+
+impl PrefixMap {
+    /// Reads a prefix file, see [`PrefixMap::parse`].
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Parses prefix declarations, either as Turtle or as a JSON object:
+    ///
+    /// ```
+    /// use xml2rdf::prefixes::PrefixMap;
+    ///
+    /// let turtle = PrefixMap::parse(
+    ///     "@prefix x2r: <https://decisym.ai/xml2rdf/model#> .\n\
+    ///      PREFIX xsd: <http://www.w3.org/2001/XMLSchema#>",
+    /// )
+    /// .unwrap();
+    /// let json = PrefixMap::parse(
+    ///     r#"{"x2r": "https://decisym.ai/xml2rdf/model#",
+    ///         "xsd": "http://www.w3.org/2001/XMLSchema#"}"#,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(turtle, json);
+    /// assert_eq!(turtle.get("xsd"), Some("http://www.w3.org/2001/XMLSchema#"));
+    /// ```
+    ///
+    /// A prefix declared again replaces its earlier namespace. Fails with
+    /// [`io::ErrorKind::InvalidData`] on malformed declarations and invalid namespace IRIs.
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let declarations = if text.trim_start().starts_with('{') {
+            parse_json(text)?
+        } else {
+            parse_turtle(text)?
+        };
+        let mut map = PrefixMap::default();
+        for (prefix, namespace) in declarations {
+            if !prefix
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.')
+            {
+                return Err(invalid(format!("invalid prefix '{prefix}'")));
+            }
+            NamedNode::new(&namespace)
+                .map_err(|e| invalid(format!("invalid namespace for '{prefix}': {e}")))?;
+            map.insert(prefix, namespace);
+        }
+        Ok(map)
+    }
+
+    /// Declares `prefix` for `namespace`, replacing any earlier declaration of `prefix`.
+    pub fn insert(&mut self, prefix: impl Into<String>, namespace: impl Into<String>) {
+        let (prefix, namespace) = (prefix.into(), namespace.into());
+        match self.prefixes.iter_mut().find(|(p, _)| *p == prefix) {
+            Some(declared) => declared.1 = namespace,
+            None => self.prefixes.push((prefix, namespace)),
+        }
+    }
+
+    /// Returns the namespace declared for `prefix`.
+    pub fn get(&self, prefix: &str) -> Option<&str> {
+        self.prefixes
+            .iter()
+            .find(|(p, _)| p == prefix)
+            .map(|(_, namespace)| namespace.as_str())
+    }
+
+    /// Returns the prefixes and their namespaces, in declaration order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.prefixes.iter().map(|(p, n)| (p.as_str(), n.as_str()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prefixes.is_empty()
+    }
+
+    /// Expands a prefixed name such as `xsd:gYear` to a full IRI. Returns `None` if `name` has
+    /// no declared prefix, or is already an IRI such as `http://...`.
+    pub fn expand(&self, name: &str) -> Option<String> {
+        let (prefix, local) = name.split_once(':')?;
+        if local.starts_with("//") {
+            return None;
+        }
+        Some(format!("{}{local}", self.get(prefix)?))
+    }
+
+    /// Expands the prefixed names given for the class map, attribute promotion predicates, flag
+    /// element predicates, datatype hints, predicate whitelist, reconstruction graph, document
+    /// IRI, corpus node and corpus predicate of `options`. IRIs without a declared prefix are kept as they are.
+    pub fn expand_options(&self, options: &mut ConversionOptions) {
+        let expand = |node: &mut NamedNode| {
+            if let Some(iri) = self.expand(node.as_str()) {
+                // A valid namespace followed by the path of a valid IRI
+                *node = NamedNode::new_unchecked(iri);
+            }
+        };
+        options.class_map.values_mut().for_each(expand);
+        options
+            .promoted_attributes
+            .values_mut()
+            .filter_map(|p| p.predicate.as_mut())
+            .for_each(expand);
+        options.flag_elements.values_mut().for_each(expand);
+        options.datatypes.values_mut().for_each(expand);
+        options
+            .only_predicates
+            .iter_mut()
+            .flatten()
+            .for_each(expand);
+        if let Some(graph) = &mut options.reconstruction_graph {
+            expand(graph);
+        }
+        if let DocumentIri::Explicit(iri) = &mut options.document_iri {
+            expand(iri);
+        }
+        options
+            .corpus_node
+            .iter_mut()
+            .chain(&mut options.corpus_predicate)
+            .for_each(expand);
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Reads a JSON object whose members map prefixes to namespace IRIs.
+fn parse_json(text: &str) -> io::Result<Vec<(String, String)>> {
+    let Some(json::Value::Object(members)) = json::parse(text) else {
+        return Err(invalid("expected a JSON object of prefixes".to_string()));
+    };
+    members
+        .into_iter()
+        .map(|(prefix, namespace)| match namespace {
+            json::Value::String(namespace) => Ok((prefix, namespace)),
+            _ => Err(invalid(format!(
+                "expected a namespace string for '{prefix}'"
+            ))),
+        })
+        .collect()
+}
+
+/// Reads `@prefix p: <iri> .` and `PREFIX p: <iri>` declarations, with `#` comments.
+fn parse_turtle(text: &str) -> io::Result<Vec<(String, String)>> {
+    let mut declarations = Vec::new();
+    let mut rest = text;
+    loop {
+        rest = skip_space(rest);
+        if rest.is_empty() {
+            return Ok(declarations);
+        }
+        let (sparql, after) = if let Some(after) = rest.strip_prefix("@prefix") {
+            (false, after)
+        } else if rest
+            .get(..6)
+            .is_some_and(|k| k.eq_ignore_ascii_case("PREFIX"))
+        {
+            (true, &rest[6..])
+        } else {
+            let line = rest.lines().next().unwrap_or_default();
+            return Err(invalid(format!(
+                "expected a prefix declaration, got '{line}'"
+            )));
+        };
+        let after = skip_space(after);
+        let (prefix, after) = after
+            .split_once(':')
+            .ok_or_else(|| invalid("expected 'prefix:' after @prefix".to_string()))?;
+        let after = skip_space(after);
+        let (namespace, after) = after
+            .strip_prefix('<')
+            .and_then(|a| a.split_once('>'))
+            .ok_or_else(|| invalid(format!("expected <namespace> for '{prefix}'")))?;
+        rest = after;
+        if !sparql {
+            rest = skip_space(rest)
+                .strip_prefix('.')
+                .ok_or_else(|| invalid(format!("expected '.' after the '{prefix}' prefix")))?;
+        }
+        declarations.push((prefix.trim().to_string(), namespace.to_string()));
+    }
+}
+
+/// Skips whitespace and comments.
+fn skip_space(mut text: &str) -> &str {
+    loop {
+        text = text.trim_start();
+        match text.strip_prefix('#') {
+            Some(comment) => text = comment.split_once('\n').map_or("", |(_, rest)| rest),
+            None => return text,
+        }
+    }
+}
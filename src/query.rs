@@ -0,0 +1,192 @@
+// Copyright (c) 2024-2025, Decisym, LLC
+// Licensed under the BSD 3-Clause License (see LICENSE file in the project root).
+
+//! # XML2RDF Queries
+//!
+//! Runs SPARQL SELECT queries over converted XML without persisting any intermediate output.
+//!
+//! ## Overview
+//! - With the `oxigraph` feature, [`query_xml`] converts XML files into an in-memory Oxigraph
+//!   store and evaluates a query against it.
+//! - [`Solutions`] holds the rows of a SELECT query and writes them as an aligned text table,
+//!   or, with Oxigraph's result serializers,
+//!   [SPARQL CSV](https://www.w3.org/TR/sparql11-results-csv-tsv/) or
+//!   [SPARQL JSON](https://www.w3.org/TR/sparql11-results-json/).
+
+use oxrdf::{Term, Variable};
+use std::io::{self, Write};
+
+/// Output format of SELECT query results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ResultFormat {
+    /// A text table with one column per variable, terms written as in N-Triples.
+    #[default]
+    Table,
+    /// SPARQL 1.1 Query Results CSV: plain values, without datatypes or language tags.
+    Csv,
+    /// SPARQL 1.1 Query Results JSON.
+    Json,
+}
+
+/// The results of a SELECT query: one row per solution, one column per variable.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Solutions {
+    /// The projected variables, in query order.
+    pub variables: Vec<Variable>,
+    /// The value bound to each variable, `None` where it is unbound.
+    pub rows: Vec<Vec<Option<Term>>>,
+}
+
+impl Solutions {
+    /// Writes the solutions to `out` as a text table, see [`ResultFormat::Table`].
+    pub fn write_table(&self, mut out: impl Write) -> io::Result<()> {
+        let header: Vec<String> = self.variables.iter().map(|v| v.to_string()).collect();
+        let rows: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|term| term.as_ref().map(Term::to_string).unwrap_or_default())
+                    .collect()
+            })
+            .collect();
+        let widths: Vec<usize> = (0..header.len())
+            .map(|i| {
+                rows.iter()
+                    .map(|row| row[i].chars().count())
+                    .chain([header[i].chars().count()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let line = |out: &mut dyn Write, cells: &[String]| -> io::Result<()> {
+            for (cell, width) in cells.iter().zip(&widths) {
+                write!(out, "| {cell:width$} ")?;
+            }
+            writeln!(out, "|")
+        };
+        line(&mut out, &header)?;
+        let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+        line(&mut out, &rule)?;
+        for row in &rows {
+            line(&mut out, row)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "oxigraph")]
+pub use store::{query_xml, QueryError};
+
+#[cfg(feature = "oxigraph")]
+mod store {
+    use super::{ResultFormat, Solutions};
+    use oxigraph::sparql::results::QueryResultsFormat;
+    use oxigraph::sparql::{EvaluationError, QueryResults, QuerySolutionIter};
+    use oxigraph::store::{StorageError, Store};
+    use std::fmt;
+    use std::io::{self, Write};
+    use std::sync::Arc;
+
+    use crate::convert::{parse_xml_with_options, ConversionOptions};
+    use crate::error::ConvertError;
+    use crate::writer::OxigraphStoreWriter;
+
+    /// Errors that can occur while converting XML and querying the result.
+    #[derive(Debug)]
+    pub enum QueryError {
+        /// Converting the XML input failed.
+        Convert(ConvertError),
+        /// Creating the in-memory store failed.
+        Storage(StorageError),
+        /// The query could not be parsed or evaluated.
+        Evaluation(EvaluationError),
+        /// The query is an ASK, CONSTRUCT or DESCRIBE query rather than a SELECT query.
+        NotSelect,
+    }
+
+    impl fmt::Display for QueryError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                QueryError::Convert(e) => write!(f, "{e}"),
+                QueryError::Storage(e) => write!(f, "{e}"),
+                QueryError::Evaluation(e) => write!(f, "{e}"),
+                QueryError::NotSelect => f.write_str("only SELECT queries are supported"),
+            }
+        }
+    }
+
+    impl std::error::Error for QueryError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                QueryError::Convert(e) => Some(e),
+                QueryError::Storage(e) => Some(e),
+                QueryError::Evaluation(e) => Some(e),
+                QueryError::NotSelect => None,
+            }
+        }
+    }
+
+    impl From<ConvertError> for QueryError {
+        fn from(e: ConvertError) -> Self {
+            QueryError::Convert(e)
+        }
+    }
+
+    impl From<StorageError> for QueryError {
+        fn from(e: StorageError) -> Self {
+            QueryError::Storage(e)
+        }
+    }
+
+    impl From<EvaluationError> for QueryError {
+        fn from(e: EvaluationError) -> Self {
+            QueryError::Evaluation(e)
+        }
+    }
+
+    impl Solutions {
+        /// Writes the solutions to `out` in the given format, CSV and JSON with Oxigraph's
+        /// result serializers.
+        pub fn write(&self, out: impl Write, format: ResultFormat) -> io::Result<()> {
+            let format = match format {
+                ResultFormat::Table => return self.write_table(out),
+                ResultFormat::Csv => QueryResultsFormat::Csv,
+                ResultFormat::Json => QueryResultsFormat::Json,
+            };
+            let rows = self.rows.clone().into_iter().map(Ok);
+            let solutions = QuerySolutionIter::new(Arc::from(self.variables.clone()), rows);
+            QueryResults::Solutions(solutions)
+                .write(out, format)
+                .map(drop)
+                .map_err(|e| io::Error::other(e.to_string()))
+        }
+    }
+
+    /// Converts `files` into a fresh in-memory Oxigraph store and evaluates the SELECT query
+    /// `sparql` against it.
+    ///
+    /// The store only lives for the duration of the call, so nothing is written to disk.
+    pub fn query_xml(
+        files: Vec<String>,
+        namespace: &str,
+        options: &ConversionOptions,
+        sparql: &str,
+    ) -> Result<Solutions, QueryError> {
+        let store = Store::new()?;
+        let mut writer = OxigraphStoreWriter::new(store.clone());
+        parse_xml_with_options(files, &mut writer, namespace, options)?;
+
+        let QueryResults::Solutions(solutions) = store.query(sparql)? else {
+            return Err(QueryError::NotSelect);
+        };
+        let variables = solutions.variables().to_vec();
+        let mut rows = Vec::new();
+        for solution in solutions {
+            let solution = solution?;
+            rows.push(variables.iter().map(|v| solution.get(v).cloned()).collect());
+        }
+        Ok(Solutions { variables, rows })
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright (c) 2024-2025, Decisym, LLC
+// Licensed under the BSD 3-Clause License (see LICENSE file in the project root).
+
+//! # XML2RDF Graph Statistics
+//!
+//! This module reports structural statistics over RDF produced by the XML2RDF converter.
+//! It interprets the graph using the model vocabulary in [`crate::vocab`].
+//!
+//! ## Overview
+//! - Loads a converted RDF file with `oxrdfio`.
+//! - Counts elements and attributes, leaf and branch elements, and the most common element names.
+//! - Computes the maximum element depth by following `hasChild` links from the root elements.
+
+use oxrdf::vocab::rdfs::SUB_CLASS_OF;
+use oxrdf::{Graph, SubjectRef, TermRef};
+use oxrdfio::{RdfFormat, RdfParser};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::Path;
+
+use crate::vocab::*;
+
+/// Number of element names listed by the `Display` report.
+const TOP_NAMES: usize = 10;
+
+/// Structural statistics of a converted XML graph.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphStats {
+    /// Number of element nodes.
+    pub elements: usize,
+    /// Number of attribute nodes.
+    pub attributes: usize,
+    /// Length of the longest `hasChild` chain, counting the root element as depth 1.
+    pub max_depth: usize,
+    /// Number of elements without child elements.
+    pub leaf_elements: usize,
+    /// Number of elements with at least one child element.
+    pub branch_elements: usize,
+    /// Element names with their occurrence counts, most common first.
+    pub element_names: Vec<(String, usize)>,
+}
+
+impl fmt::Display for GraphStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "elements: {}", self.elements)?;
+        writeln!(f, "attributes: {}", self.attributes)?;
+        writeln!(f, "max depth: {}", self.max_depth)?;
+        writeln!(f, "leaf elements: {}", self.leaf_elements)?;
+        writeln!(f, "branch elements: {}", self.branch_elements)?;
+        writeln!(f, "most common element names:")?;
+        for (name, count) in self.element_names.iter().take(TOP_NAMES) {
+            writeln!(f, "  {name}: {count}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Loads an RDF file into a graph, guessing the format from the file extension.
+///
+/// Files with an unknown extension are parsed as N-Triples.
+pub fn load_graph(path: &str) -> io::Result<Graph> {
+    let format = Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(RdfFormat::from_extension)
+        .unwrap_or(RdfFormat::NTriples);
+    let reader = BufReader::new(File::open(path)?);
+
+    let mut graph = Graph::new();
+    for quad in RdfParser::from_format(format).for_reader(reader) {
+        let quad = quad?;
+        graph.insert(quad.as_ref());
+    }
+    Ok(graph)
+}
+
+/// Computes structural statistics for a graph produced by the XML2RDF converter.
+pub fn graph_stats(graph: &Graph) -> GraphStats {
+    let elements: Vec<SubjectRef> = graph
+        .subjects_for_predicate_object(SUB_CLASS_OF, XML_ELEMENT)
+        .collect();
+    let attributes: HashSet<TermRef> = graph
+        .triples_for_predicate(HAS_ATTRIBUTE)
+        .map(|t| t.object)
+        .collect();
+
+    let mut stats = GraphStats {
+        elements: elements.len(),
+        attributes: attributes.len(),
+        ..Default::default()
+    };
+
+    let mut names: HashMap<String, usize> = HashMap::new();
+    let mut roots = Vec::new();
+    for element in elements.iter() {
+        if let Some(TermRef::Literal(name)) = graph.object_for_subject_predicate(*element, HAS_NAME)
+        {
+            *names.entry(name.value().to_string()).or_default() += 1;
+        }
+        if graph
+            .objects_for_subject_predicate(*element, HAS_CHILD)
+            .next()
+            .is_some()
+        {
+            stats.branch_elements += 1;
+        } else {
+            stats.leaf_elements += 1;
+        }
+        if graph
+            .subject_for_predicate_object(HAS_CHILD, TermRef::from(*element))
+            .is_none()
+        {
+            roots.push(*element);
+        }
+    }
+
+    // Walk down from each root with an explicit stack to find the deepest element
+    let mut pending: Vec<(SubjectRef, usize)> = roots.into_iter().map(|r| (r, 1)).collect();
+    while let Some((node, depth)) = pending.pop() {
+        stats.max_depth = stats.max_depth.max(depth);
+        for child in graph.objects_for_subject_predicate(node, HAS_CHILD) {
+            match child {
+                TermRef::NamedNode(child) => pending.push((child.into(), depth + 1)),
+                TermRef::BlankNode(child) => pending.push((child.into(), depth + 1)),
+                _ => {}
+            }
+        }
+    }
+
+    stats.element_names = names.into_iter().collect();
+    stats
+        .element_names
+        .sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    stats
+}
@@ -0,0 +1,87 @@
+// Copyright (c) 2024-2025, Decisym, LLC
+// Licensed under the BSD 3-Clause License (see LICENSE file in the project root).
+
+//! # XML2RDF Syslog
+//!
+//! Sends log records to the local syslog daemon over its Unix datagram socket. journald listens
+//! on the same socket (`/dev/log`), so records reach the journal as well.
+//!
+//! ## Overview
+//! - [`Syslog`] writes [RFC 3164](https://www.rfc-editor.org/rfc/rfc3164) style messages with
+//!   the `user` facility, leaving the timestamp and host name to the daemon.
+//! - [`record`] formats a message followed by `key="value"` fields, so operators can filter
+//!   records by file, triple count or event.
+
+use std::io;
+use std::os::unix::net::UnixDatagram;
+use std::path::Path;
+
+/// The socket the syslog daemon, or journald, listens on.
+pub const DEFAULT_SOCKET: &str = "/dev/log";
+
+/// The `user` facility, for messages from user processes.
+const FACILITY_USER: u8 = 1;
+
+/// Severity of a log record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error = 3,
+    Warning = 4,
+    Info = 6,
+}
+
+/// A connection to a syslog socket.
+pub struct Syslog {
+    socket: UnixDatagram,
+    ident: String,
+}
+
+impl Syslog {
+    /// Connects to the syslog socket at `path`, tagging records with `ident`, usually the program
+    /// name.
+    pub fn connect(path: impl AsRef<Path>, ident: &str) -> io::Result<Self> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(path)?;
+        Ok(Self {
+            socket,
+            ident: ident.to_string(),
+        })
+    }
+
+    /// Sends `message` with the given severity.
+    pub fn send(&self, severity: Severity, message: &str) -> io::Result<()> {
+        let priority = FACILITY_USER * 8 + severity as u8;
+        let line = format!(
+            "<{priority}>{}[{}]: {message}",
+            self.ident,
+            std::process::id()
+        );
+        self.socket.send(line.as_bytes()).map(|_| ())
+    }
+}
+
+/// Returns `message` followed by each field as `key="value"`, with quotes and backslashes in the
+/// value escaped.
+///
+/// ```
+/// use xml2rdf::syslog::record;
+///
+/// let line = record("converted", &[("file", "a b.xml"), ("triples", "28")]);
+/// assert_eq!(line, r#"converted file="a b.xml" triples="28""#);
+/// ```
+pub fn record(message: &str, fields: &[(&str, &str)]) -> String {
+    let mut line = message.to_string();
+    for (key, value) in fields {
+        line.push(' ');
+        line.push_str(key);
+        line.push_str("=\"");
+        for c in value.chars() {
+            if c == '"' || c == '\\' {
+                line.push('\\');
+            }
+            line.push(c);
+        }
+        line.push('"');
+    }
+    line
+}
@@ -0,0 +1,562 @@
+// Copyright (c) 2024-2025, Decisym, LLC
+// Licensed under the BSD 3-Clause License (see LICENSE file in the project root).
+
+//! # XML2RDF Model Vocabulary
+//!
+//! IRIs of the classes and predicates used to model XML documents in RDF.
+
+use const_format::concatcp;
+use oxrdf::vocab::{rdf, rdfs};
+use oxrdf::{Literal, NamedNode, NamedNodeRef, Triple};
+
+/// Namespace of the XML2RDF model vocabulary.
+pub const X2R: &str = "https://decisym.ai/xml2rdf/model#";
+
+/// Class of XML element nodes.
+pub const XML_ELEMENT: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "XmlNode"));
+/// Class of XML element nodes without child elements, see
+/// [`crate::convert::ConversionOptions::element_kinds`].
+pub const LEAF_ELEMENT: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "LeafElement"));
+/// Class of XML element nodes with child elements, see
+/// [`crate::convert::ConversionOptions::element_kinds`].
+pub const BRANCH_ELEMENT: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "BranchElement"));
+/// Class of the nodes holding a run of element text, see
+/// [`crate::convert::ConversionOptions::text_as_node`].
+pub const TEXT_NODE: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "TextNode"));
+/// Class of XML attribute nodes.
+pub const XML_ATTRIBUTE: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "XmlAttribute"));
+/// Class of the nodes grouping an element's attributes.
+pub const XML_ATTRIBUTE_BAG: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "XmlAttributeBag"));
+/// Class of the nodes describing a converted XML document.
+pub const XML_DOCUMENT: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "XmlDocument"));
+/// Class of the nodes modeling JSON embedded in the text of an element.
+pub const JSON_VALUE: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "JsonValue"));
+/// Class of the nodes describing how a conversion was run, see
+/// [`crate::convert::ConversionOptions::record_options`].
+pub const CONVERSION_RUN: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "ConversionRun"));
+/// Links a parent element to a child element.
+pub const HAS_CHILD: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "hasChild"));
+/// Links a child element to its parent element, the inverse of [`HAS_CHILD`].
+pub const HAS_PARENT: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "hasParent"));
+/// Links an element (or attribute bag) to an attribute.
+pub const HAS_ATTRIBUTE: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "hasAttribute"));
+/// Links an attribute to its element (or attribute bag), the inverse of [`HAS_ATTRIBUTE`].
+pub const ATTRIBUTE_OF: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "attributeOf"));
+/// Links an element to its attribute bag.
+pub const HAS_ATTRIBUTE_BAG: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "hasAttributeBag"));
+/// Links a document node to its root element.
+pub const HAS_ROOT: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "hasRoot"));
+/// Links an element, attribute or attribute bag node to the document node it belongs to.
+pub const IN_DOCUMENT: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "inDocument"));
+/// IRI of the file a document was read from, as a literal, see
+/// [`crate::convert::ConversionOptions::source_iris`].
+pub const SOURCE_FILE: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "sourceFile"));
+/// When a document was converted, as an `xsd:dateTime`.
+pub const CONVERTED_AT: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "convertedAt"));
+/// Links a document node to the conversion run that produced it.
+pub const CONVERTED_BY: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "convertedBy"));
+/// Version of the converter of a conversion run.
+pub const TOOL_VERSION: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "toolVersion"));
+/// Data namespace node IRIs were minted under in a conversion run.
+pub const DATA_NAMESPACE: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "dataNamespace"));
+/// How nodes were identified in a conversion run, e.g. `deterministic`.
+pub const NODE_IDS: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "nodeIds"));
+/// Hash function deriving deterministic node IRIs in a conversion run, e.g. `sha1`.
+pub const NODE_ID_HASH: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "nodeIdHash"));
+/// How classes were keyed in a conversion run, e.g. `path`.
+pub const CLASS_KEY: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "classKey"));
+/// Optional feature the converter of a conversion run was built with, e.g. `zip`.
+pub const ENABLED_FEATURE: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "enabledFeature"));
+/// Position of an element among its parent's child elements, starting at 0.
+pub const SIBLING_INDEX: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "siblingIndex"));
+/// Position of an attribute among its element's attributes, starting at 0.
+pub const ATTRIBUTE_INDEX: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "attributeIndex"));
+/// UTF-8 byte offset of the `<` opening an element's start tag, inclusive.
+pub const START_OFFSET: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "startOffset"));
+/// UTF-8 byte offset just past the `>` closing an element's end tag, exclusive.
+pub const END_OFFSET: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "endOffset"));
+/// Number of elements sharing the path of a sampled element, the sample included.
+pub const OCCURRENCES: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "occurrences"));
+/// Number of elements typed with a class, written on the class.
+pub const INSTANCE_COUNT: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "instanceCount"));
+/// Line of the start tag of the element a value belongs to, starting at 1; annotates quoted
+/// value triples in RDF-star output.
+pub const SOURCE_LINE: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "sourceLine"));
+/// Column of the start tag of the element a value belongs to, starting at 1.
+pub const SOURCE_COLUMN: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "sourceColumn"));
+/// Where the datatype of a typed value came from: `encoding`, `hint` or `inference`; annotates
+/// quoted value triples in RDF-star output.
+pub const TYPED_BY: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "typedBy"));
+/// Marks an element declared nil with `xsi:nil="true"`: its value is known to be absent.
+pub const IS_NIL: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "isNil"));
+/// Marks an element without content that is not declared nil: no value was provided.
+pub const IS_EMPTY: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "isEmpty"));
+/// Lowercase hex SHA-256 digest of the bytes a document was converted from.
+pub const SHA256: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "sha256"));
+/// Lowercase hex MD5 digest of the bytes a document was converted from.
+pub const MD5: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "md5"));
+/// Links an element to a namespace declaration it makes.
+pub const DECLARES_NAMESPACE: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "declaresNamespace"));
+/// Prefix bound by a namespace declaration, empty for the default namespace.
+pub const NAMESPACE_PREFIX: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "namespacePrefix"));
+/// URI bound by a namespace declaration.
+pub const NAMESPACE_URI: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(X2R, "namespaceUri"));
+/// Local name of an element, or name of a node modeling embedded JSON, exactly as written.
+/// Unlike the class IRIs built from names, it is never percent-encoded, so the original name can
+/// be restored verbatim.
+pub const HAS_NAME: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "hasName"));
+/// Qualified name of an element as written, with its namespace prefix if it has one, e.g.
+/// `dc:title`.
+pub const HAS_QNAME: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "hasQName"));
+/// Text content of an element, or value of an attribute.
+pub const HAS_VALUE: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "hasValue"));
+
+/// Ordered list of the text runs and child elements of an element with mixed content.
+pub const HAS_CONTENT: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "hasContent"));
+
+/// Text content of an element, when distinguished from attribute values.
+pub const HAS_TEXT: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(X2R, "hasText"));
+
+/// `rdf:JSON`, the datatype of JSON literals (RDF 1.2).
+pub const RDF_JSON: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("http://www.w3.org/1999/02/22-rdf-syntax-ns#JSON");
+
+/// Namespace of the OWL vocabulary.
+pub const OWL: &str = "http://www.w3.org/2002/07/owl#";
+
+/// `owl:equivalentClass`, linking path-keyed classes to their canonical name-keyed class.
+pub const OWL_EQUIVALENT_CLASS: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(OWL, "equivalentClass"));
+
+/// `owl:inverseOf`, declaring the model's inverse properties.
+pub const OWL_INVERSE_OF: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(OWL, "inverseOf"));
+
+/// `owl:Class`, declaring the model and element classes in OWL-safe output.
+pub const OWL_CLASS: NamedNodeRef<'_> = NamedNodeRef::new_unchecked(concatcp!(OWL, "Class"));
+
+/// `owl:ObjectProperty`, declaring predicates linking nodes in OWL-safe output.
+pub const OWL_OBJECT_PROPERTY: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(OWL, "ObjectProperty"));
+
+/// `owl:DatatypeProperty`, declaring predicates with literal values in OWL-safe output.
+pub const OWL_DATATYPE_PROPERTY: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(OWL, "DatatypeProperty"));
+
+/// Namespace of the PROV-O vocabulary.
+pub const PROV: &str = "http://www.w3.org/ns/prov#";
+
+/// `prov:wasDerivedFrom`, linking a document node to the IRI of its source.
+pub const PROV_WAS_DERIVED_FROM: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(PROV, "wasDerivedFrom"));
+
+/// `prov:generatedAtTime`, recording when a document node was converted.
+pub const PROV_GENERATED_AT_TIME: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked(concatcp!(PROV, "generatedAtTime"));
+
+/// Namespace of XML Schema instance attributes such as `xsi:nil`.
+pub const XSI: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
+/// Predicates the converter links values with.
+///
+/// The default uses `hasValue` for both element text and attribute values.
+/// [`ModelVocabulary::distinct_values`] keeps `hasValue` for attributes and uses `hasText` for
+/// element text, so queries can tell them apart without inspecting node types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelVocabulary {
+    /// Links an element to its text content.
+    pub text: NamedNode,
+    /// Links an attribute node to its value.
+    pub attribute_value: NamedNode,
+}
+
+impl Default for ModelVocabulary {
+    fn default() -> Self {
+        Self {
+            text: HAS_VALUE.into_owned(),
+            attribute_value: HAS_VALUE.into_owned(),
+        }
+    }
+}
+
+impl ModelVocabulary {
+    /// Uses `hasText` for element text and `hasValue` for attribute values.
+    pub fn distinct_values() -> Self {
+        Self {
+            text: HAS_TEXT.into_owned(),
+            ..Self::default()
+        }
+    }
+
+    /// Returns an `rdfs:subPropertyOf hasValue` declaration for each distinct predicate other
+    /// than `hasValue` itself, so queries and reasoners relying on `hasValue` still find all
+    /// values.
+    pub fn sub_properties(&self) -> Vec<Triple> {
+        let mut triples = Vec::new();
+        for predicate in [&self.text, &self.attribute_value] {
+            let triple = Triple::new(predicate.clone(), rdfs::SUB_PROPERTY_OF, HAS_VALUE);
+            if predicate.as_ref() != HAS_VALUE && !triples.contains(&triple) {
+                triples.push(triple);
+            }
+        }
+        triples
+    }
+}
+
+/// Returns the model's TBox: a class or property declaration for each term of the vocabulary,
+/// with an `rdfs:label` and `rdfs:comment`.
+///
+/// Prepending these triples to converted data makes the output interpretable on its own.
+pub fn ontology() -> Vec<Triple> {
+    let classes = [
+        (XML_DOCUMENT, "XML document", "A converted XML document."),
+        (XML_ELEMENT, "XML node", "An element of an XML document."),
+        (
+            LEAF_ELEMENT,
+            "leaf element",
+            "An element of an XML document without child elements.",
+        ),
+        (
+            BRANCH_ELEMENT,
+            "branch element",
+            "An element of an XML document with child elements.",
+        ),
+        (
+            TEXT_NODE,
+            "text node",
+            "A run of text between the tags of an XML element.",
+        ),
+        (
+            XML_ATTRIBUTE,
+            "XML attribute",
+            "An attribute of an XML element.",
+        ),
+        (
+            XML_ATTRIBUTE_BAG,
+            "XML attribute bag",
+            "Groups all attributes of a single XML element.",
+        ),
+        (
+            JSON_VALUE,
+            "JSON value",
+            "A member or array item of JSON embedded in the text of an XML element.",
+        ),
+        (
+            CONVERSION_RUN,
+            "conversion run",
+            "A run of the converter, with the options that shaped its output.",
+        ),
+    ];
+    let properties = [
+        (
+            HAS_CHILD,
+            "has child",
+            "Links an XML element to a child element.",
+        ),
+        (
+            HAS_PARENT,
+            "has parent",
+            "Links an XML element to its parent element.",
+        ),
+        (
+            HAS_ATTRIBUTE,
+            "has attribute",
+            "Links an XML element, or its attribute bag, to an attribute.",
+        ),
+        (
+            ATTRIBUTE_OF,
+            "attribute of",
+            "Links an XML attribute to its element, or the element's attribute bag.",
+        ),
+        (
+            HAS_ATTRIBUTE_BAG,
+            "has attribute bag",
+            "Links an XML element to the bag grouping its attributes.",
+        ),
+        (
+            HAS_ROOT,
+            "has root",
+            "Links an XML document to its root element.",
+        ),
+        (
+            IN_DOCUMENT,
+            "in document",
+            "Links a node to the XML document it belongs to.",
+        ),
+        (
+            SOURCE_FILE,
+            "source file",
+            "The path of the file an XML document was read from.",
+        ),
+        (
+            CONVERTED_AT,
+            "converted at",
+            "When an XML document was converted.",
+        ),
+        (
+            CONVERTED_BY,
+            "converted by",
+            "Links an XML document to the conversion run that converted it.",
+        ),
+        (
+            TOOL_VERSION,
+            "tool version",
+            "The version of the converter of a conversion run.",
+        ),
+        (
+            DATA_NAMESPACE,
+            "data namespace",
+            "The namespace node IRIs were minted under in a conversion run.",
+        ),
+        (
+            NODE_IDS,
+            "node IDs",
+            "How the nodes of a conversion run were identified.",
+        ),
+        (
+            NODE_ID_HASH,
+            "node ID hash",
+            "The hash function deriving deterministic node IRIs in a conversion run.",
+        ),
+        (
+            CLASS_KEY,
+            "class key",
+            "How the classes of a conversion run were keyed.",
+        ),
+        (
+            ENABLED_FEATURE,
+            "enabled feature",
+            "An optional feature the converter of a conversion run was built with.",
+        ),
+        (
+            SHA256,
+            "SHA-256",
+            "The SHA-256 digest of the bytes an XML document was converted from, in hex.",
+        ),
+        (
+            MD5,
+            "MD5",
+            "The MD5 digest of the bytes an XML document was converted from, in hex.",
+        ),
+        (
+            SIBLING_INDEX,
+            "sibling index",
+            "The position of an XML element among its parent's child elements.",
+        ),
+        (
+            ATTRIBUTE_INDEX,
+            "attribute index",
+            "The position of an XML attribute among its element's attributes.",
+        ),
+        (
+            START_OFFSET,
+            "start offset",
+            "The byte offset in its source at which an XML element starts, inclusive.",
+        ),
+        (
+            END_OFFSET,
+            "end offset",
+            "The byte offset in its source at which an XML element ends, exclusive.",
+        ),
+        (
+            OCCURRENCES,
+            "occurrences",
+            "The number of XML elements sharing the path of a sampled element, itself included.",
+        ),
+        (
+            INSTANCE_COUNT,
+            "instance count",
+            "The number of XML elements of a class in the converted documents.",
+        ),
+        (
+            SOURCE_LINE,
+            "source line",
+            "The line of the start tag of the XML element a value belongs to.",
+        ),
+        (
+            SOURCE_COLUMN,
+            "source column",
+            "The column of the start tag of the XML element a value belongs to.",
+        ),
+        (
+            TYPED_BY,
+            "typed by",
+            "Whether the datatype of a value came from a binary encoding, a hint or inference.",
+        ),
+        (
+            IS_NIL,
+            "is nil",
+            "Whether an XML element is declared nil with xsi:nil, its value known to be absent.",
+        ),
+        (
+            IS_EMPTY,
+            "is empty",
+            "Whether an XML element not declared nil has no content, no value having been provided.",
+        ),
+        (
+            DECLARES_NAMESPACE,
+            "declares namespace",
+            "Links an XML element to a namespace declaration it makes.",
+        ),
+        (
+            NAMESPACE_PREFIX,
+            "namespace prefix",
+            "The prefix bound by a namespace declaration, empty for the default namespace.",
+        ),
+        (
+            NAMESPACE_URI,
+            "namespace URI",
+            "The URI bound by a namespace declaration.",
+        ),
+        (
+            HAS_NAME,
+            "has name",
+            "The local name of an XML element, or the key of an embedded JSON member, as written.",
+        ),
+        (
+            HAS_QNAME,
+            "has QName",
+            "The qualified name of an XML element as written, including its namespace prefix.",
+        ),
+        (
+            HAS_VALUE,
+            "has value",
+            "The text content of an XML element, or the value of an XML attribute.",
+        ),
+        (HAS_TEXT, "has text", "The text content of an XML element."),
+        (
+            HAS_CONTENT,
+            "has content",
+            "The ordered list of text runs and child elements of an XML element with mixed content.",
+        ),
+    ];
+
+    let mut triples = Vec::new();
+    let terms = classes
+        .iter()
+        .map(|(term, label, comment)| (term, rdfs::CLASS, label, comment))
+        .chain(
+            properties
+                .iter()
+                .map(|(term, label, comment)| (term, rdf::PROPERTY, label, comment)),
+        );
+    for (term, kind, label, comment) in terms {
+        triples.push(Triple::new(*term, rdf::TYPE, kind));
+        triples.push(Triple::new(
+            *term,
+            rdfs::LABEL,
+            Literal::new_simple_literal(*label),
+        ));
+        triples.push(Triple::new(
+            *term,
+            rdfs::COMMENT,
+            Literal::new_simple_literal(*comment),
+        ));
+    }
+    triples
+}
+
+/// Returns OWL declarations for the model: `owl:Class` for each class, and
+/// `owl:ObjectProperty` or `owl:DatatypeProperty` for each predicate, depending on whether it
+/// links nodes or literals.
+///
+/// ```
+/// use oxrdf::vocab::rdf;
+/// use oxrdf::Triple;
+/// use xml2rdf::vocab::*;
+///
+/// let declarations = owl_declarations();
+/// assert!(declarations.contains(&Triple::new(HAS_CHILD, rdf::TYPE, OWL_OBJECT_PROPERTY)));
+/// assert!(declarations.contains(&Triple::new(HAS_VALUE, rdf::TYPE, OWL_DATATYPE_PROPERTY)));
+/// ```
+pub fn owl_declarations() -> Vec<Triple> {
+    let classes = [
+        XML_DOCUMENT,
+        XML_ELEMENT,
+        LEAF_ELEMENT,
+        BRANCH_ELEMENT,
+        TEXT_NODE,
+        XML_ATTRIBUTE,
+        XML_ATTRIBUTE_BAG,
+        JSON_VALUE,
+        CONVERSION_RUN,
+    ];
+    let object_properties = [
+        HAS_CHILD,
+        HAS_PARENT,
+        HAS_ATTRIBUTE,
+        ATTRIBUTE_OF,
+        HAS_ATTRIBUTE_BAG,
+        HAS_ROOT,
+        IN_DOCUMENT,
+        CONVERTED_BY,
+        DECLARES_NAMESPACE,
+        HAS_CONTENT,
+    ];
+    let datatype_properties = [
+        SOURCE_FILE,
+        CONVERTED_AT,
+        TOOL_VERSION,
+        DATA_NAMESPACE,
+        NODE_IDS,
+        NODE_ID_HASH,
+        CLASS_KEY,
+        ENABLED_FEATURE,
+        SHA256,
+        MD5,
+        SIBLING_INDEX,
+        ATTRIBUTE_INDEX,
+        START_OFFSET,
+        END_OFFSET,
+        OCCURRENCES,
+        INSTANCE_COUNT,
+        SOURCE_LINE,
+        SOURCE_COLUMN,
+        TYPED_BY,
+        IS_NIL,
+        IS_EMPTY,
+        NAMESPACE_PREFIX,
+        NAMESPACE_URI,
+        HAS_NAME,
+        HAS_QNAME,
+        HAS_VALUE,
+        HAS_TEXT,
+    ];
+    let kinds = [
+        (&classes[..], OWL_CLASS),
+        (&object_properties[..], OWL_OBJECT_PROPERTY),
+        (&datatype_properties[..], OWL_DATATYPE_PROPERTY),
+    ];
+    kinds
+        .into_iter()
+        .flat_map(|(terms, kind)| {
+            terms
+                .iter()
+                .map(move |term| Triple::new(*term, rdf::TYPE, kind))
+        })
+        .collect()
+}
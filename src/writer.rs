@@ -7,21 +7,223 @@
 //! It uses `oxrdf` to build and manage RDF graphs or output the data direct to a file.
 //!
 //! ## Overview
-//! - Adds XML RDF triples to a graph or file.
+//! - Adds XML RDF triples to a graph, dataset, file (optionally split into chunks), or in-memory
+//!   string.
+//! - Writes Turtle abbreviated with the prefixes of a prefix file, TriG for datasets with named
+//!   graphs, or RDF/XML for legacy consumers.
+//! - Writes one JSON object per triple and line (NDJSON), for line-oriented tools.
+//! - Writes JSON-LD, with a context naming the model terms by their local names.
+//! - Streams XML RDF triples over a channel to a consumer thread.
+//! - Replaces an output file atomically once the conversion has finished.
+//! - With the `compress` feature, compresses the output with gzip or Zstandard.
+//! - Drops duplicate triples, including those already in an existing output file.
+//! - Stops a conversion at a triple boundary once interrupted, e.g. from a signal handler.
+//! - With the `oxigraph` feature, inserts triples directly into an `oxigraph::store::Store`.
 
-use oxrdf::{Graph, TripleRef};
+use oxrdf::vocab::{rdf, xsd};
+use oxrdf::{
+    Dataset, Graph, GraphName, GraphNameRef, Literal, NamedNode, NamedOrBlankNode, QuadRef,
+    SubjectRef, TermRef, Triple, TripleRef,
+};
+use oxrdfio::{RdfFormat, RdfParser, RdfSerializer, WriterQuadSerializer};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
 use std::fs::File;
 use std::fs::OpenOptions;
-use std::io::{self, BufWriter, Write};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::Arc;
+
+use crate::convert::push_json_string;
+use crate::prefixes::PrefixMap;
+use crate::vocab;
 
 pub trait RdfWriter {
     fn add_triple(&mut self, triple: TripleRef) -> std::io::Result<()>;
+
+    /// Adds a triple to a graph of a dataset. Writers holding a single graph accept quads in the
+    /// default graph only, and fail with [`io::ErrorKind::Unsupported`] otherwise.
+    fn add_quad(&mut self, quad: QuadRef) -> std::io::Result<()> {
+        if quad.graph_name.is_default_graph() {
+            self.add_triple(quad.into())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "writer does not support named graphs",
+            ))
+        }
+    }
+
+    /// Flushes any buffered output. Called once a conversion has finished.
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    /// Hints that about `additional` more triples are about to be added, so buffering writers can
+    /// grow once instead of repeatedly.
+    fn reserve(&mut self, _additional: usize) {}
+}
+
+macro_rules! forward_rdf_writer {
+    ($ty:ty) => {
+        impl<W: RdfWriter + ?Sized> RdfWriter for $ty {
+            fn add_triple(&mut self, triple: TripleRef) -> std::io::Result<()> {
+                (**self).add_triple(triple)
+            }
+
+            fn add_quad(&mut self, quad: QuadRef) -> std::io::Result<()> {
+                (**self).add_quad(quad)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                (**self).flush()
+            }
+
+            fn reserve(&mut self, additional: usize) {
+                (**self).reserve(additional)
+            }
+        }
+    };
+}
+
+forward_rdf_writer!(&mut W);
+forward_rdf_writer!(Box<W>);
+
+/// Drops triples that were already written, so each distinct triple reaches the wrapped writer
+/// once.
+///
+/// [`DedupWriter::with_existing`] also seeds it from an existing output file, so appending to
+/// that file skips the triples of earlier runs, such as the class and ontology triples every run
+/// shares. The existing file is read in the format guessed from its extension, defaulting to
+/// N-Quads. Every distinct triple is kept in memory.
+pub struct DedupWriter<W: RdfWriter> {
+    writer: W,
+    seen: Dataset,
+}
+
+impl<W: RdfWriter> DedupWriter<W> {
+    pub fn new(writer: W) -> Self {
+        DedupWriter {
+            writer,
+            seen: Dataset::new(),
+        }
+    }
+
+    /// Wraps `writer`, treating the N-Triples or N-Quads already in the file at `path` as
+    /// written. A missing file counts as empty.
+    pub fn with_existing(writer: W, path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut dedup = Self::new(writer);
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(dedup),
+            Err(e) => return Err(e),
+        };
+        let format = path
+            .as_ref()
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(RdfFormat::from_extension)
+            .unwrap_or(RdfFormat::NQuads);
+        for quad in RdfParser::from_format(format).for_reader(BufReader::new(file)) {
+            dedup.seen.insert(&quad?);
+        }
+        Ok(dedup)
+    }
+}
+
+impl<W: RdfWriter> RdfWriter for DedupWriter<W> {
+    fn add_triple(&mut self, triple: TripleRef) -> std::io::Result<()> {
+        if self
+            .seen
+            .insert(triple.in_graph(GraphNameRef::DefaultGraph))
+        {
+            self.writer.add_triple(triple)?;
+        }
+        Ok(())
+    }
+
+    fn add_quad(&mut self, quad: QuadRef) -> std::io::Result<()> {
+        if self.seen.insert(quad) {
+            self.writer.add_quad(quad)?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.writer.reserve(additional)
+    }
+}
+
+/// Fails every triple with [`io::ErrorKind::Interrupted`] once `interrupted` is set, so a
+/// conversion stops between two triples, e.g. when a signal handler sets the flag.
+///
+/// Flushing still reaches the wrapped writer, so flushing after the conversion stopped leaves
+/// the output valid up to the last complete triple.
+pub struct InterruptibleWriter<W: RdfWriter> {
+    writer: W,
+    interrupted: Arc<AtomicBool>,
+}
+
+impl<W: RdfWriter> InterruptibleWriter<W> {
+    pub fn new(writer: W, interrupted: Arc<AtomicBool>) -> Self {
+        InterruptibleWriter {
+            writer,
+            interrupted,
+        }
+    }
+
+    fn check(&self) -> io::Result<()> {
+        if self.interrupted.load(Ordering::SeqCst) {
+            Err(io::Error::new(
+                io::ErrorKind::Interrupted,
+                "conversion interrupted",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<W: RdfWriter> RdfWriter for InterruptibleWriter<W> {
+    fn add_triple(&mut self, triple: TripleRef) -> std::io::Result<()> {
+        self.check()?;
+        self.writer.add_triple(triple)
+    }
+
+    fn add_quad(&mut self, quad: QuadRef) -> std::io::Result<()> {
+        self.check()?;
+        self.writer.add_quad(quad)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.writer.reserve(additional)
+    }
 }
 
+/// Writes N-Triples (or N-Quads) lines, buffered until [`RdfWriter::flush`] or drop.
 pub struct FileWriter<W: Write> {
     writer: BufWriter<W>,
 }
 
+impl<W: Write> FileWriter<W> {
+    /// Writes N-Triples to any `Write` implementation, buffered.
+    pub fn new(writer: W) -> Self {
+        FileWriter {
+            writer: BufWriter::new(writer),
+        }
+    }
+}
+
 impl FileWriter<io::Stdout> {
     pub fn to_stdout() -> Self {
         FileWriter {
@@ -42,20 +244,1013 @@ impl FileWriter<File> {
     }
 }
 
+impl FileWriter<AtomicFile> {
+    /// Like [`FileWriter::to_file`], but writes to a temporary file that only replaces
+    /// `output_file` once [`FileWriter::finish`] is called, see [`AtomicFile`].
+    pub fn to_file_atomic(output_file: String) -> io::Result<Self> {
+        Ok(FileWriter::new(AtomicFile::create(output_file)?))
+    }
+
+    /// Flushes the output and moves it into place.
+    pub fn finish(self) -> io::Result<()> {
+        self.writer
+            .into_inner()
+            .map_err(|e| e.into_error())?
+            .finish()
+    }
+}
+
 impl<W: Write> RdfWriter for FileWriter<W> {
     fn add_triple(&mut self, triple: TripleRef) -> std::io::Result<()> {
         self.writer.write_all(triple.to_string().as_bytes())?;
-        self.writer.write_all(b" .\n")?;
-        let _ = self.writer.flush();
+        self.writer.write_all(b" .\n")
+    }
+
+    /// Writes an N-Quads line; triples in the default graph stay valid N-Triples.
+    fn add_quad(&mut self, quad: QuadRef) -> std::io::Result<()> {
+        self.writer.write_all(quad.to_string().as_bytes())?;
+        self.writer.write_all(b" .\n")
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// A file written under a temporary name in the same directory and renamed into place by
+/// [`AtomicFile::finish`], so readers of the destination never see a partial output: it holds
+/// either its previous content or the complete new one.
+///
+/// The temporary file created by [`AtomicFile::create`] starts as a copy of any existing
+/// destination, so output is appended as with [`FileWriter::to_file`], while that of
+/// [`AtomicFile::replace`] starts empty. It is removed if the `AtomicFile` is dropped without
+/// being finished, e.g. after a failed conversion.
+pub struct AtomicFile {
+    file: Option<File>,
+    path: PathBuf,
+    temp: PathBuf,
+}
+
+impl AtomicFile {
+    /// Creates the temporary file for `path`, named `.{name}.{pid}.tmp`, holding a copy of
+    /// `path` if it exists.
+    pub fn create(path: impl Into<PathBuf>) -> io::Result<Self> {
+        Self::open(path.into(), true)
+    }
+
+    /// Like [`AtomicFile::create`], but the temporary file starts empty, so finishing replaces
+    /// the content of `path`, e.g. for formats that hold a single document.
+    pub fn replace(path: impl Into<PathBuf>) -> io::Result<Self> {
+        Self::open(path.into(), false)
+    }
+
+    /// Creates the temporary file for `path`, copying `path` into it if `keep` is set.
+    fn open(path: PathBuf, keep: bool) -> io::Result<Self> {
+        let name = path
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "not a file path"))?;
+        let mut temp = std::ffi::OsString::from(".");
+        temp.push(name);
+        temp.push(format!(".{}.tmp", std::process::id()));
+        let temp = path.with_file_name(temp);
+        let mut atomic = AtomicFile {
+            file: None,
+            path,
+            temp,
+        };
+        if keep {
+            match std::fs::copy(&atomic.path, &atomic.temp) {
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+        atomic.file = Some(
+            OpenOptions::new()
+                .create(true)
+                .append(keep)
+                .write(true)
+                .truncate(!keep)
+                .open(&atomic.temp)?,
+        );
+        Ok(atomic)
+    }
+
+    /// Returns the path of the temporary file.
+    pub fn temp_path(&self) -> &Path {
+        &self.temp
+    }
+
+    /// Syncs the temporary file to disk and renames it to the destination. The temporary file is
+    /// removed if this fails.
+    pub fn finish(mut self) -> io::Result<()> {
+        if let Some(file) = self.file.take() {
+            file.sync_all()?;
+        }
+        std::fs::rename(&self.temp, &self.path)
+    }
+
+    fn file(&mut self) -> &mut File {
+        // Only taken by `finish`, which consumes the AtomicFile
+        self.file.as_mut().expect("temporary file is open")
+    }
+}
+
+impl Write for AtomicFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file().flush()
+    }
+}
+
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        // Still there unless finished
+        let _ = std::fs::remove_file(&self.temp);
+    }
+}
+
+/// Writes N-Triples split into numbered files of at most `max_triples_per_file` triples each:
+/// `out.nt` becomes `out.0.nt`, `out.1.nt`, ...
+///
+/// Files are split strictly by count, so the triples of one element, or of an `rdf:List`, may
+/// end up in consecutive files. Blank node labels are scoped to a single N-Triples document, so
+/// load all chunks into one store with `--node-ids uuid` if blank nodes must stay connected.
+/// Chunk files are created as needed and overwrite any existing file of the same name.
+pub struct RotatingFileWriter {
+    output_file: PathBuf,
+    max_triples_per_file: usize,
+    /// Index of the current chunk
+    index: usize,
+    /// Triples written to the current chunk
+    written: usize,
+    writer: Option<FileWriter<File>>,
+}
+
+impl RotatingFileWriter {
+    /// Creates a writer for chunks of `output_file`, see [`RotatingFileWriter::chunk_path`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_triples_per_file` is 0.
+    pub fn new(output_file: impl Into<PathBuf>, max_triples_per_file: usize) -> Self {
+        assert!(
+            max_triples_per_file > 0,
+            "chunks must hold at least one triple"
+        );
+        RotatingFileWriter {
+            output_file: output_file.into(),
+            max_triples_per_file,
+            index: 0,
+            written: 0,
+            writer: None,
+        }
+    }
+
+    /// Returns the path of chunk `index`, numbered before the extension of the output file.
+    pub fn chunk_path(&self, index: usize) -> PathBuf {
+        let stem = self.output_file.file_stem().unwrap_or_default();
+        let mut name = stem.to_os_string();
+        name.push(format!(".{index}"));
+        if let Some(extension) = self.output_file.extension() {
+            name.push(".");
+            name.push(extension);
+        }
+        self.output_file.with_file_name(name)
+    }
+
+    /// Returns the writer for the next triple, moving on to a new chunk when the current one is
+    /// full.
+    fn next_writer(&mut self) -> io::Result<&mut FileWriter<File>> {
+        if self.written == self.max_triples_per_file {
+            if let Some(mut writer) = self.writer.take() {
+                writer.flush()?;
+            }
+            self.index += 1;
+            self.written = 0;
+        }
+        if self.writer.is_none() {
+            let file = File::create(self.chunk_path(self.index))?;
+            self.writer = Some(FileWriter::new(file));
+        }
+        self.written += 1;
+        Ok(self.writer.as_mut().expect("chunk writer was just opened"))
+    }
+}
+
+impl RdfWriter for RotatingFileWriter {
+    fn add_triple(&mut self, triple: TripleRef) -> std::io::Result<()> {
+        self.next_writer()?.add_triple(triple)
+    }
+
+    fn add_quad(&mut self, quad: QuadRef) -> std::io::Result<()> {
+        self.next_writer()?.add_quad(quad)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match &mut self.writer {
+            Some(writer) => writer.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Writes Turtle, declaring the prefixes of a [`PrefixMap`] up front and abbreviating the IRIs
+/// in their namespaces.
+///
+/// Each flush completes the Turtle document written so far. Triples added afterwards start a new
+/// one that declares the prefixes again, so the output as a whole stays valid Turtle.
+pub struct TurtleWriter<W: Write> {
+    output: SerializedOutput<W>,
+}
+
+impl<W: Write> TurtleWriter<W> {
+    /// Writes Turtle to any `Write` implementation, buffered.
+    pub fn new(writer: W, prefixes: &PrefixMap) -> Self {
+        TurtleWriter {
+            output: SerializedOutput::new(RdfFormat::Turtle, writer, prefixes),
+        }
+    }
+
+    /// Completes the Turtle document and returns the wrapped writer.
+    pub fn into_inner(self) -> io::Result<W> {
+        self.output.into_inner()
+    }
+}
+
+impl<W: Write> RdfWriter for TurtleWriter<W> {
+    fn add_triple(&mut self, triple: TripleRef) -> std::io::Result<()> {
+        self.output.serializer()?.serialize_triple(triple)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.output.flush()
+    }
+}
+
+/// Writes TriG, the Turtle of datasets, keeping triples in named graphs apart in a block per
+/// graph:
+///
+/// ```
+/// use oxrdf::{Literal, NamedNodeRef, QuadRef};
+/// use xml2rdf::prefixes::PrefixMap;
+/// use xml2rdf::vocab::{HAS_NAME, X2R};
+/// use xml2rdf::writer::{RdfWriter, TriGWriter};
+///
+/// let node = NamedNodeRef::new_unchecked("https://example.com/n");
+/// let graph = NamedNodeRef::new_unchecked("https://example.com/g");
+/// let mut prefixes = PrefixMap::default();
+/// prefixes.insert("x2r", X2R);
+/// let mut w = TriGWriter::new(Vec::new(), &prefixes);
+/// let name = Literal::new_simple_literal("note");
+/// w.add_quad(QuadRef::new(node, HAS_NAME, &name, graph)).unwrap();
+/// let trig = String::from_utf8(w.into_inner().unwrap()).unwrap();
+/// assert!(trig.starts_with("@prefix x2r: <https://decisym.ai/xml2rdf/model#> .\n"));
+/// assert!(trig.contains("<https://example.com/g> {"));
+/// assert!(trig.contains("x2r:hasName \"note\""));
+/// ```
+///
+/// Like [`TurtleWriter`], each flush completes the document written so far, and triples added
+/// afterwards start a new one that declares the prefixes again.
+pub struct TriGWriter<W: Write> {
+    output: SerializedOutput<W>,
+}
+
+impl<W: Write> TriGWriter<W> {
+    /// Writes TriG to any `Write` implementation, buffered.
+    pub fn new(writer: W, prefixes: &PrefixMap) -> Self {
+        TriGWriter {
+            output: SerializedOutput::new(RdfFormat::TriG, writer, prefixes),
+        }
+    }
+
+    /// Completes the TriG document and returns the wrapped writer.
+    pub fn into_inner(self) -> io::Result<W> {
+        self.output.into_inner()
+    }
+}
+
+impl<W: Write> RdfWriter for TriGWriter<W> {
+    fn add_triple(&mut self, triple: TripleRef) -> std::io::Result<()> {
+        self.output.serializer()?.serialize_triple(triple)
+    }
+
+    fn add_quad(&mut self, quad: QuadRef) -> std::io::Result<()> {
+        self.output.serializer()?.serialize_quad(quad)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.output.flush()
+    }
+}
+
+/// Writes RDF/XML, declaring the prefixes of a [`PrefixMap`] as XML namespaces:
+///
+/// ```
+/// use oxrdf::{Literal, NamedNodeRef, TripleRef};
+/// use xml2rdf::prefixes::PrefixMap;
+/// use xml2rdf::vocab::{HAS_NAME, X2R};
+/// use xml2rdf::writer::{RdfWriter, RdfXmlWriter};
+///
+/// let node = NamedNodeRef::new_unchecked("https://example.com/n");
+/// let mut prefixes = PrefixMap::default();
+/// prefixes.insert("x2r", X2R);
+/// let mut w = RdfXmlWriter::new(Vec::new(), &prefixes);
+/// let name = Literal::new_simple_literal("note");
+/// w.add_triple(TripleRef::new(node, HAS_NAME, &name)).unwrap();
+/// let xml = String::from_utf8(w.into_inner().unwrap()).unwrap();
+/// assert!(xml.contains("<rdf:Description rdf:about=\"https://example.com/n\">"));
+/// assert!(xml.contains("<x2r:hasName>note</x2r:hasName>"));
+/// ```
+///
+/// RDF/XML names predicates by XML element names, so a predicate IRI must end with a name, such
+/// as `hasName`, and others fail with [`io::ErrorKind::InvalidInput`]. Like [`TurtleWriter`],
+/// each flush completes the document written so far. The converters flush once done, so a
+/// conversion into an empty output yields a single valid document, while appending to earlier
+/// output does not.
+pub struct RdfXmlWriter<W: Write> {
+    output: SerializedOutput<W>,
+}
+
+impl<W: Write> RdfXmlWriter<W> {
+    /// Writes RDF/XML to any `Write` implementation, buffered.
+    pub fn new(writer: W, prefixes: &PrefixMap) -> Self {
+        RdfXmlWriter {
+            output: SerializedOutput::new(RdfFormat::RdfXml, writer, prefixes),
+        }
+    }
+
+    /// Completes the RDF/XML document and returns the wrapped writer.
+    pub fn into_inner(self) -> io::Result<W> {
+        self.output.into_inner()
+    }
+}
+
+impl<W: Write> RdfWriter for RdfXmlWriter<W> {
+    fn add_triple(&mut self, triple: TripleRef) -> std::io::Result<()> {
+        if !ends_with_xml_name(triple.predicate.as_str()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "predicate {} does not end with an XML name, so RDF/XML cannot express it",
+                    triple.predicate
+                ),
+            ));
+        }
+        self.output.serializer()?.serialize_triple(triple)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.output.flush()
+    }
+}
+
+/// Returns whether `iri` ends with a name RDF/XML can split off as the local part of an element
+/// name: a letter or `_` followed by letters, digits, `-`, `.` and `_`.
+fn ends_with_xml_name(iri: &str) -> bool {
+    let tail = iri
+        .rsplit(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | '.' | '_' | '\u{b7}')))
+        .next()
+        .unwrap_or_default();
+    tail.contains(|c: char| c.is_alphabetic() || c == '_')
+}
+
+/// Output serialized by `oxrdfio` in a format declaring prefixes up front, one document per
+/// flush.
+struct SerializedOutput<W: Write> {
+    format: RdfFormat,
+    prefixes: PrefixMap,
+    writer: Option<BufWriter<W>>,
+    serializer: Option<WriterQuadSerializer<BufWriter<W>>>,
+}
+
+impl<W: Write> SerializedOutput<W> {
+    fn new(format: RdfFormat, writer: W, prefixes: &PrefixMap) -> Self {
+        SerializedOutput {
+            format,
+            prefixes: prefixes.clone(),
+            writer: Some(BufWriter::new(writer)),
+            serializer: None,
+        }
+    }
+
+    /// Completes the current document and returns the wrapped writer.
+    fn into_inner(mut self) -> io::Result<W> {
+        self.flush()?;
+        let writer = self.writer.take().ok_or_else(lost_output)?;
+        writer.into_inner().map_err(|e| e.into_error())
+    }
+
+    /// Returns the serializer of the current document, starting one if needed.
+    fn serializer(&mut self) -> io::Result<&mut WriterQuadSerializer<BufWriter<W>>> {
+        if self.serializer.is_none() {
+            let mut serializer = RdfSerializer::from_format(self.format);
+            for (prefix, namespace) in self.prefixes.iter() {
+                serializer = serializer
+                    .with_prefix(prefix, namespace)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            }
+            let writer = self.writer.take().ok_or_else(lost_output)?;
+            self.serializer = Some(serializer.for_writer(writer));
+        }
+        Ok(self
+            .serializer
+            .as_mut()
+            .expect("serializer was just started"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if let Some(serializer) = self.serializer.take() {
+            let mut writer = serializer.finish()?;
+            writer.flush()?;
+            self.writer = Some(writer);
+        }
         Ok(())
     }
 }
 
+/// The error for writes after a failed flush, which loses the wrapped writer.
+fn lost_output() -> io::Error {
+    io::Error::other("output was lost by an earlier failed write")
+}
+
+/// Writes each triple as a line holding a JSON object (NDJSON), for line-oriented tools such as
+/// `jq`:
+///
+/// ```
+/// use oxrdf::{Literal, NamedNodeRef, TripleRef};
+/// use xml2rdf::writer::{NdJsonWriter, RdfWriter};
+///
+/// let node = NamedNodeRef::new_unchecked("https://example.com/n");
+/// let name = NamedNodeRef::new_unchecked("https://example.com/name");
+/// let mut w = NdJsonWriter::new(Vec::new());
+/// let value = Literal::new_language_tagged_literal_unchecked("Tove", "en");
+/// w.add_triple(TripleRef::new(node, name, &value)).unwrap();
+/// assert_eq!(
+///     String::from_utf8(w.into_inner().unwrap()).unwrap(),
+///     "{\"s\":\"https://example.com/n\",\"p\":\"https://example.com/name\",\"o\":\"Tove\",\
+///      \"ot\":\"literal\",\"dt\":\"http://www.w3.org/1999/02/22-rdf-syntax-ns#langString\",\
+///      \"lang\":\"en\"}\n"
+/// );
+/// ```
+///
+/// `s`, `p` and `o` hold IRIs as they are and blank nodes as `_:id`. `ot` is the kind of the
+/// object: `iri`, `bnode`, `literal`, or `triple` for quoted triples, written in N-Triples. The
+/// object of a literal is its lexical value, with its datatype IRI in `dt` and, if
+/// language-tagged, its language tag in `lang`. Triples in a named graph carry its name in `g`.
+pub struct NdJsonWriter<W: Write> {
+    writer: BufWriter<W>,
+}
+
+impl<W: Write> NdJsonWriter<W> {
+    /// Writes NDJSON to any `Write` implementation, buffered.
+    pub fn new(writer: W) -> Self {
+        NdJsonWriter {
+            writer: BufWriter::new(writer),
+        }
+    }
+
+    /// Flushes the output and returns the wrapped writer.
+    pub fn into_inner(self) -> io::Result<W> {
+        self.writer.into_inner().map_err(|e| e.into_error())
+    }
+
+    fn write_line(&mut self, triple: TripleRef, graph: GraphNameRef) -> io::Result<()> {
+        let mut line = String::from("{\"s\":");
+        push_json_string(&mut line, &json_term(triple.subject.into()).1);
+        line.push_str(",\"p\":");
+        push_json_string(&mut line, triple.predicate.as_str());
+        let (kind, object) = json_term(triple.object);
+        line.push_str(",\"o\":");
+        push_json_string(&mut line, &object);
+        line.push_str(",\"ot\":");
+        push_json_string(&mut line, kind);
+        if let TermRef::Literal(literal) = triple.object {
+            line.push_str(",\"dt\":");
+            push_json_string(&mut line, literal.datatype().as_str());
+            if let Some(language) = literal.language() {
+                line.push_str(",\"lang\":");
+                push_json_string(&mut line, language);
+            }
+        }
+        let graph = match graph {
+            GraphNameRef::NamedNode(n) => Some(n.as_str().to_string()),
+            GraphNameRef::BlankNode(b) => Some(b.to_string()),
+            GraphNameRef::DefaultGraph => None,
+        };
+        if let Some(graph) = graph {
+            line.push_str(",\"g\":");
+            push_json_string(&mut line, &graph);
+        }
+        line.push_str("}\n");
+        self.writer.write_all(line.as_bytes())
+    }
+}
+
+/// Returns the NDJSON kind of `term` and its string form.
+fn json_term(term: TermRef) -> (&'static str, String) {
+    match term {
+        TermRef::NamedNode(n) => ("iri", n.as_str().to_string()),
+        TermRef::BlankNode(b) => ("bnode", b.to_string()),
+        TermRef::Literal(l) => ("literal", l.value().to_string()),
+        // Quoted triples, when RDF-star is enabled
+        #[allow(unreachable_patterns)]
+        other => ("triple", other.to_string()),
+    }
+}
+
+impl<W: Write> RdfWriter for NdJsonWriter<W> {
+    fn add_triple(&mut self, triple: TripleRef) -> std::io::Result<()> {
+        self.write_line(triple, GraphNameRef::DefaultGraph)
+    }
+
+    fn add_quad(&mut self, quad: QuadRef) -> std::io::Result<()> {
+        self.write_line(quad.into(), quad.graph_name)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Returns a standalone JSON-LD context document, `{"@context": {...}}`, for converted data.
+///
+/// It declares the `x2r`, `rdf`, `rdfs`, `xsd`, `owl` and `prov` prefixes followed by those of
+/// `prefixes`, and a term for each class and property of the model named by its local name, such
+/// as `hasName` for `x2r:hasName`. The object properties, such as `hasChild`, take IRIs:
+///
+/// ```
+/// use xml2rdf::prefixes::PrefixMap;
+/// use xml2rdf::writer::json_ld_context;
+///
+/// let context = json_ld_context(&PrefixMap::default());
+/// assert!(context.contains("\n    \"hasName\": \"https://decisym.ai/xml2rdf/model#hasName\",\n"));
+/// assert!(context.contains(
+///     "\n    \"hasChild\": {\"@id\": \"https://decisym.ai/xml2rdf/model#hasChild\", \
+///      \"@type\": \"@id\"},\n"
+/// ));
+/// ```
+///
+/// Publishing it lets other documents refer to the context by URL instead of embedding it.
+pub fn json_ld_context(prefixes: &PrefixMap) -> String {
+    let mut text = String::from("{\n  ");
+    JsonLdContext::new(prefixes).push_context(&mut text);
+    text.push_str("\n}\n");
+    text
+}
+
+/// Prefixes and model terms of a JSON-LD context, and the compaction of IRIs with them.
+struct JsonLdContext {
+    prefixes: PrefixMap,
+    /// Term, IRI, and whether the term takes IRIs
+    terms: Vec<(String, String, bool)>,
+    /// Position in `terms` of each term IRI
+    by_iri: HashMap<String, usize>,
+}
+
+impl JsonLdContext {
+    fn new(prefixes: &PrefixMap) -> Self {
+        let mut declared = PrefixMap::default();
+        for (prefix, namespace) in [
+            ("x2r", vocab::X2R),
+            ("rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#"),
+            ("rdfs", "http://www.w3.org/2000/01/rdf-schema#"),
+            ("xsd", "http://www.w3.org/2001/XMLSchema#"),
+            ("owl", vocab::OWL),
+            ("prov", vocab::PROV),
+        ]
+        .into_iter()
+        .chain(prefixes.iter())
+        {
+            // `_:` starts a blank node identifier
+            if prefix != "_" {
+                declared.insert(prefix, namespace);
+            }
+        }
+        let terms: Vec<(String, String, bool)> = vocab::owl_declarations()
+            .into_iter()
+            .filter_map(|declaration| {
+                let SubjectRef::NamedNode(term) = declaration.subject.as_ref() else {
+                    return None;
+                };
+                let name = term.as_str().strip_prefix(vocab::X2R)?;
+                let takes_iris = declaration.object == vocab::OWL_OBJECT_PROPERTY.into();
+                Some((name.to_string(), term.as_str().to_string(), takes_iris))
+            })
+            // A prefix of the same name takes precedence
+            .filter(|(name, _, _)| declared.get(name).is_none())
+            .collect();
+        let by_iri = terms
+            .iter()
+            .enumerate()
+            .map(|(i, (_, iri, _))| (iri.clone(), i))
+            .collect();
+        JsonLdContext {
+            prefixes: declared,
+            terms,
+            by_iri,
+        }
+    }
+
+    /// Appends the `"@context"` member, indented as the first member of a top-level object.
+    fn push_context(&self, text: &mut String) {
+        text.push_str("\"@context\": {");
+        let mut first = true;
+        let mut separator = |text: &mut String| {
+            text.push_str(if first { "\n    " } else { ",\n    " });
+            first = false;
+        };
+        for (prefix, namespace) in self.prefixes.iter() {
+            separator(text);
+            push_json_string(text, prefix);
+            text.push_str(": ");
+            // Other namespaces only act as prefixes when flagged as such
+            if namespace.ends_with([':', '/', '?', '#', '[', ']', '@']) {
+                push_json_string(text, namespace);
+            } else {
+                text.push_str("{\"@id\": ");
+                push_json_string(text, namespace);
+                text.push_str(", \"@prefix\": true}");
+            }
+        }
+        for (name, iri, takes_iris) in &self.terms {
+            separator(text);
+            push_json_string(text, name);
+            text.push_str(": ");
+            if *takes_iris {
+                text.push_str("{\"@id\": ");
+                push_json_string(text, iri);
+                text.push_str(", \"@type\": \"@id\"}");
+            } else {
+                push_json_string(text, iri);
+            }
+        }
+        text.push_str("\n  }");
+    }
+
+    /// Returns the model term for `iri` if `vocab`, for keys and types, or else `iri` abbreviated
+    /// with the longest matching namespace, if any.
+    fn compact(&self, iri: &str, vocab: bool) -> String {
+        if let Some(&i) = self.by_iri.get(iri).filter(|_| vocab) {
+            return self.terms[i].0.clone();
+        }
+        self.prefixes
+            .iter()
+            .filter_map(|(prefix, namespace)| Some((prefix, iri.strip_prefix(namespace)?)))
+            // `prefix://...` reads as an absolute IRI
+            .filter(|(_, local)| !local.is_empty() && !local.starts_with("//"))
+            .min_by_key(|(_, local)| local.len())
+            .map_or_else(
+                || iri.to_string(),
+                |(prefix, local)| format!("{prefix}:{local}"),
+            )
+    }
+
+    /// Returns the `@id` of `node`.
+    fn id(&self, node: &NamedOrBlankNode) -> String {
+        match node {
+            NamedOrBlankNode::NamedNode(n) => self.compact(n.as_str(), false),
+            NamedOrBlankNode::BlankNode(b) => b.to_string(),
+        }
+    }
+
+    /// Returns whether the values of `predicate` are IRIs by default.
+    fn takes_iris(&self, predicate: &NamedNode) -> bool {
+        self.by_iri
+            .get(predicate.as_str())
+            .is_some_and(|&i| self.terms[i].2)
+    }
+}
+
+/// A value of a JSON-LD node property.
+#[derive(PartialEq)]
+enum JsonLdValue {
+    Node(NamedOrBlankNode),
+    Literal(Literal),
+}
+
+/// The triples of a subject in a graph.
+struct JsonLdNode {
+    graph: GraphName,
+    id: NamedOrBlankNode,
+    types: Vec<NamedOrBlankNode>,
+    properties: Vec<(NamedNode, Vec<JsonLdValue>)>,
+}
+
+/// Buffers triples in memory and, on flush, writes them as a JSON-LD document whose `@context`
+/// is that of [`json_ld_context`]:
+///
+/// ```
+/// use oxrdf::{Literal, NamedNodeRef, TripleRef};
+/// use xml2rdf::prefixes::PrefixMap;
+/// use xml2rdf::vocab::HAS_NAME;
+/// use xml2rdf::writer::{JsonLdWriter, RdfWriter};
+///
+/// let node = NamedNodeRef::new_unchecked("https://example.com/n");
+/// let mut w = JsonLdWriter::new(Vec::new(), &PrefixMap::default());
+/// let name = Literal::new_simple_literal("note");
+/// w.add_triple(TripleRef::new(node, HAS_NAME, &name)).unwrap();
+/// w.flush().unwrap();
+/// let json = String::from_utf8(w.into_inner().unwrap()).unwrap();
+/// assert!(json.ends_with(
+///     "  \"@graph\": [\n    {\"@id\": \"https://example.com/n\", \"hasName\": [\"note\"]}\n  ]\n}\n"
+/// ));
+/// ```
+///
+/// There is a node object per subject, in the order first seen, holding the values of each
+/// property in an array. Triples in a named graph go into the `@graph` of a node object for that
+/// graph, after the nodes of the default graph. Each flush writes a document of the triples
+/// added since the previous one, so the converters, which flush once done, write one document
+/// per conversion. Quoted triples cannot be written and fail with
+/// [`io::ErrorKind::Unsupported`].
+pub struct JsonLdWriter<W: Write> {
+    writer: W,
+    context: JsonLdContext,
+    nodes: Vec<JsonLdNode>,
+    /// Position in `nodes` of each graph and subject
+    positions: HashMap<(GraphName, NamedOrBlankNode), usize>,
+    /// Whether a document was written
+    written: bool,
+}
+
+impl<W: Write> JsonLdWriter<W> {
+    /// Writes JSON-LD to any `Write` implementation, declaring the `prefixes` in its context and
+    /// abbreviating IRIs with them.
+    pub fn new(writer: W, prefixes: &PrefixMap) -> Self {
+        JsonLdWriter {
+            writer,
+            context: JsonLdContext::new(prefixes),
+            nodes: Vec::new(),
+            positions: HashMap::new(),
+            written: false,
+        }
+    }
+
+    /// Returns the wrapped writer, dropping triples added since the last flush.
+    pub fn into_inner(self) -> io::Result<W> {
+        Ok(self.writer)
+    }
+
+    fn add(&mut self, quad: QuadRef) -> io::Result<()> {
+        let subject = match quad.subject {
+            SubjectRef::NamedNode(n) => NamedOrBlankNode::from(n.into_owned()),
+            SubjectRef::BlankNode(b) => b.into_owned().into(),
+            #[allow(unreachable_patterns)]
+            _ => return Err(quoted_triple()),
+        };
+        let value = match quad.object {
+            TermRef::NamedNode(n) => JsonLdValue::Node(n.into_owned().into()),
+            TermRef::BlankNode(b) => JsonLdValue::Node(b.into_owned().into()),
+            TermRef::Literal(l) => JsonLdValue::Literal(l.into_owned()),
+            #[allow(unreachable_patterns)]
+            _ => return Err(quoted_triple()),
+        };
+        let graph = quad.graph_name.into_owned();
+        let position = *self
+            .positions
+            .entry((graph.clone(), subject.clone()))
+            .or_insert_with(|| {
+                self.nodes.push(JsonLdNode {
+                    graph,
+                    id: subject,
+                    types: Vec::new(),
+                    properties: Vec::new(),
+                });
+                self.nodes.len() - 1
+            });
+        let node = &mut self.nodes[position];
+        match value {
+            JsonLdValue::Node(class) if quad.predicate == rdf::TYPE => {
+                if !node.types.contains(&class) {
+                    node.types.push(class);
+                }
+            }
+            value => {
+                let predicate = quad.predicate;
+                let values = match node.properties.iter().position(|(p, _)| *p == predicate) {
+                    Some(i) => &mut node.properties[i].1,
+                    None => {
+                        node.properties.push((predicate.into_owned(), Vec::new()));
+                        &mut node.properties.last_mut().expect("just pushed").1
+                    }
+                };
+                if !values.contains(&value) {
+                    values.push(value);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Appends `node` as a node object on one line.
+    fn push_node(&self, text: &mut String, node: &JsonLdNode) {
+        text.push_str("{\"@id\": ");
+        push_json_string(text, &self.context.id(&node.id));
+        if !node.types.is_empty() {
+            text.push_str(", \"@type\": [");
+            for (i, class) in node.types.iter().enumerate() {
+                if i > 0 {
+                    text.push_str(", ");
+                }
+                let class = match class {
+                    NamedOrBlankNode::NamedNode(n) => self.context.compact(n.as_str(), true),
+                    NamedOrBlankNode::BlankNode(b) => b.to_string(),
+                };
+                push_json_string(text, &class);
+            }
+            text.push(']');
+        }
+        for (predicate, values) in &node.properties {
+            text.push_str(", ");
+            push_json_string(text, &self.context.compact(predicate.as_str(), true));
+            text.push_str(": [");
+            let takes_iris = self.context.takes_iris(predicate);
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    text.push_str(", ");
+                }
+                self.push_value(text, value, takes_iris);
+            }
+            text.push(']');
+        }
+        text.push('}');
+    }
+
+    /// Appends `value`, as a plain string where the term allows.
+    fn push_value(&self, text: &mut String, value: &JsonLdValue, takes_iris: bool) {
+        match value {
+            JsonLdValue::Node(node) if takes_iris => push_json_string(text, &self.context.id(node)),
+            JsonLdValue::Node(node) => {
+                text.push_str("{\"@id\": ");
+                push_json_string(text, &self.context.id(node));
+                text.push('}');
+            }
+            JsonLdValue::Literal(literal) if literal.datatype() == xsd::STRING && !takes_iris => {
+                push_json_string(text, literal.value())
+            }
+            JsonLdValue::Literal(literal) => {
+                text.push_str("{\"@value\": ");
+                push_json_string(text, literal.value());
+                if let Some(language) = literal.language() {
+                    text.push_str(", \"@language\": ");
+                    push_json_string(text, language);
+                } else if literal.datatype() != xsd::STRING {
+                    text.push_str(", \"@type\": ");
+                    push_json_string(
+                        text,
+                        &self.context.compact(literal.datatype().as_str(), true),
+                    );
+                }
+                text.push('}');
+            }
+        }
+    }
+
+    /// Returns the document of the buffered triples.
+    fn document(&self) -> String {
+        let mut text = String::from("{\n  ");
+        self.context.push_context(&mut text);
+        text.push_str(",\n  \"@graph\": [");
+        let mut graphs: Vec<&GraphName> = Vec::new();
+        let mut first = true;
+        for node in &self.nodes {
+            if !node.graph.is_default_graph() {
+                if !graphs.contains(&&node.graph) {
+                    graphs.push(&node.graph);
+                }
+                continue;
+            }
+            text.push_str(if first { "\n    " } else { ",\n    " });
+            first = false;
+            self.push_node(&mut text, node);
+        }
+        for graph in graphs {
+            text.push_str(if first { "\n    " } else { ",\n    " });
+            first = false;
+            text.push_str("{\"@id\": ");
+            let id = match graph {
+                GraphName::NamedNode(n) => self.context.compact(n.as_str(), false),
+                GraphName::BlankNode(b) => b.to_string(),
+                GraphName::DefaultGraph => unreachable!("default graph nodes come first"),
+            };
+            push_json_string(&mut text, &id);
+            text.push_str(", \"@graph\": [");
+            for (i, node) in self.nodes.iter().filter(|n| n.graph == *graph).enumerate() {
+                text.push_str(if i == 0 { "\n      " } else { ",\n      " });
+                self.push_node(&mut text, node);
+            }
+            text.push_str("\n    ]}");
+        }
+        text.push_str(if first { "]\n}\n" } else { "\n  ]\n}\n" });
+        text
+    }
+}
+
+/// The error for quoted triples, which JSON-LD cannot express.
+fn quoted_triple() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "JSON-LD output does not support quoted triples",
+    )
+}
+
+impl<W: Write> RdfWriter for JsonLdWriter<W> {
+    fn add_triple(&mut self, triple: TripleRef) -> std::io::Result<()> {
+        self.add(triple.in_graph(GraphNameRef::DefaultGraph))
+    }
+
+    fn add_quad(&mut self, quad: QuadRef) -> std::io::Result<()> {
+        self.add(quad)
+    }
+
+    /// Writes the triples added since the previous flush as a document. A conversion without
+    /// triples still writes one, with an empty `@graph`.
+    fn flush(&mut self) -> std::io::Result<()> {
+        if !self.nodes.is_empty() || !self.written {
+            let document = self.document();
+            self.writer.write_all(document.as_bytes())?;
+            self.nodes.clear();
+            self.positions.clear();
+            self.written = true;
+        }
+        self.writer.flush()
+    }
+}
+
+/// Accumulates N-Triples output in memory, or N-Quads once triples in named graphs are added.
+///
+/// # Example
+/// ```rust
+/// use xml2rdf::convert::{parse_xml_reader, ConversionOptions};
+/// use xml2rdf::writer::StringWriter;
+///
+/// let xml = "<note><to>Tove</to></note>";
+/// let mut w = StringWriter::new();
+/// parse_xml_reader(
+///     xml.as_bytes(),
+///     &mut w,
+///     "https://decisym.ai/xml2rdf/data",
+///     &ConversionOptions::default(),
+/// )
+/// .unwrap();
+///
+/// let nt = w.into_string();
+/// assert_eq!(nt.lines().count(), 8);
+/// assert!(nt.contains("<https://decisym.ai/xml2rdf/model#hasValue> \"Tove\" ."));
+/// ```
+#[derive(Debug, Default)]
+pub struct StringWriter {
+    buffer: String,
+}
+
+impl StringWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.buffer
+    }
+
+    pub fn into_string(self) -> String {
+        self.buffer
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buffer.into_bytes()
+    }
+}
+
+impl RdfWriter for StringWriter {
+    fn add_triple(&mut self, triple: TripleRef) -> std::io::Result<()> {
+        writeln!(self.buffer, "{triple} .").map_err(io::Error::other)
+    }
+
+    /// Appends an N-Quads line; triples in the default graph stay valid N-Triples.
+    fn add_quad(&mut self, quad: QuadRef) -> std::io::Result<()> {
+        writeln!(self.buffer, "{quad} .").map_err(io::Error::other)
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        // Rough size of an N-Triples line with two UUID IRIs
+        self.buffer.reserve(additional * 128);
+    }
+}
+
+/// Adds triples to an `oxrdf::Graph`.
+///
+/// The graph is never cleared: triples accumulate on top of whatever it already holds, across
+/// any number of conversions. As the graph is a set, a triple that is already present, such as
+/// a class declaration shared by several documents, is stored only once.
 pub struct GraphWriter<'a> {
     graph: &'a mut Graph,
 }
 
 impl<'a> GraphWriter<'a> {
+    /// Creates a writer adding to `graph`, keeping the triples it already holds.
     pub fn new(graph: &'a mut Graph) -> Self {
         Self { graph }
     }
@@ -67,3 +1262,303 @@ impl RdfWriter for GraphWriter<'_> {
         Ok(())
     }
 }
+
+/// Adds triples to an `oxrdf::Dataset`, keeping named graphs apart.
+pub struct DatasetWriter<'a> {
+    dataset: &'a mut Dataset,
+    /// Named graphs written so far, if they replace their earlier content
+    replaced: Option<HashSet<GraphName>>,
+}
+
+impl<'a> DatasetWriter<'a> {
+    pub fn new(dataset: &'a mut Dataset) -> Self {
+        Self {
+            dataset,
+            replaced: None,
+        }
+    }
+
+    /// Removes what a named graph holds the first time the writer adds to it, so converting a
+    /// document again with [`crate::convert::ConversionOptions::document_graphs`] replaces its
+    /// earlier version instead of adding to it. The default graph is never cleared.
+    pub fn replacing_graphs(mut self) -> Self {
+        self.replaced = Some(HashSet::new());
+        self
+    }
+}
+
+impl RdfWriter for DatasetWriter<'_> {
+    fn add_triple(&mut self, triple: TripleRef) -> std::io::Result<()> {
+        self.dataset
+            .insert(triple.in_graph(oxrdf::GraphNameRef::DefaultGraph));
+        Ok(())
+    }
+
+    fn add_quad(&mut self, quad: QuadRef) -> std::io::Result<()> {
+        if let Some(replaced) = &mut self.replaced {
+            if !quad.graph_name.is_default_graph() && replaced.insert(quad.graph_name.into_owned())
+            {
+                let stale: Vec<_> = self
+                    .dataset
+                    .quads_for_graph_name(quad.graph_name)
+                    .map(QuadRef::into_owned)
+                    .collect();
+                for quad in &stale {
+                    self.dataset.remove(quad);
+                }
+            }
+        }
+        self.dataset.insert(quad);
+        Ok(())
+    }
+}
+
+/// Sends each triple over a bounded channel so a consumer thread can process triples as they are
+/// produced.
+///
+/// The conversion blocks while the channel is full, giving the consumer backpressure. Adding a
+/// triple fails with [`io::ErrorKind::BrokenPipe`] once the receiver has been dropped.
+///
+/// # Example
+/// ```rust
+/// use xml2rdf::convert::{parse_xml_reader, ConversionOptions};
+/// use xml2rdf::writer::ChannelWriter;
+///
+/// let (mut w, triples) = ChannelWriter::new(64);
+/// let producer = std::thread::spawn(move || {
+///     let xml = "<note><to>Tove</to></note>";
+///     let namespace = "https://decisym.ai/xml2rdf/data";
+///     parse_xml_reader(xml.as_bytes(), &mut w, namespace, &ConversionOptions::default())
+/// });
+///
+/// assert_eq!(triples.iter().count(), 8);
+/// producer.join().unwrap().unwrap();
+/// ```
+pub struct ChannelWriter {
+    sender: SyncSender<Triple>,
+}
+
+impl ChannelWriter {
+    /// Creates a writer whose channel holds at most `capacity` triples, returning it with the
+    /// receiving end of the channel.
+    pub fn new(capacity: usize) -> (Self, Receiver<Triple>) {
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+        (Self { sender }, receiver)
+    }
+}
+
+impl RdfWriter for ChannelWriter {
+    fn add_triple(&mut self, triple: TripleRef) -> std::io::Result<()> {
+        self.sender
+            .send(triple.into_owned())
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "triple receiver was dropped"))
+    }
+}
+
+#[cfg(feature = "oxigraph")]
+pub use store::OxigraphStoreWriter;
+
+#[cfg(feature = "oxigraph")]
+mod store {
+    use super::RdfWriter;
+    use oxigraph::store::Store;
+    use oxrdf::{GraphName, Quad, QuadRef, TripleRef};
+    use std::collections::HashSet;
+    use std::io;
+
+    const DEFAULT_BATCH_SIZE: usize = 10_000;
+
+    /// Inserts triples into an Oxigraph store, committing them in batched transactions.
+    ///
+    /// Triples are buffered until `batch_size` is reached and then added to the store
+    /// atomically. Any remaining triples are committed by [`RdfWriter::flush`], which
+    /// `parse_xml` calls once conversion completes.
+    pub struct OxigraphStoreWriter {
+        store: Store,
+        graph_name: GraphName,
+        batch_size: usize,
+        batch: Vec<Quad>,
+        /// Named graphs written so far, if they replace their earlier content
+        replaced: Option<HashSet<GraphName>>,
+    }
+
+    impl OxigraphStoreWriter {
+        pub fn new(store: Store) -> Self {
+            Self {
+                store,
+                graph_name: GraphName::DefaultGraph,
+                batch_size: DEFAULT_BATCH_SIZE,
+                batch: Vec::new(),
+                replaced: None,
+            }
+        }
+
+        /// Inserts triples into the given named graph instead of the default graph.
+        pub fn with_graph_name(mut self, graph_name: impl Into<GraphName>) -> Self {
+            self.graph_name = graph_name.into();
+            self
+        }
+
+        /// Sets the number of triples committed per transaction.
+        pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+            self.batch_size = batch_size.max(1);
+            self
+        }
+
+        /// Clears a named graph of the store the first time the writer adds to it, see
+        /// [`super::DatasetWriter::replacing_graphs`]. The default graph is never cleared.
+        pub fn replacing_graphs(mut self) -> Self {
+            self.replaced = Some(HashSet::new());
+            self
+        }
+
+        pub fn store(&self) -> &Store {
+            &self.store
+        }
+
+        fn commit(&mut self) -> io::Result<()> {
+            if self.batch.is_empty() {
+                return Ok(());
+            }
+            self.store
+                .extend(self.batch.drain(..))
+                .map_err(io::Error::other)
+        }
+    }
+
+    impl RdfWriter for OxigraphStoreWriter {
+        fn add_triple(&mut self, triple: TripleRef) -> io::Result<()> {
+            self.batch
+                .push(triple.into_owned().in_graph(self.graph_name.clone()));
+            if self.batch.len() >= self.batch_size {
+                self.commit()?;
+            }
+            Ok(())
+        }
+
+        /// Quads in the default graph go to the writer's graph, named graphs are kept as is.
+        fn add_quad(&mut self, quad: QuadRef) -> io::Result<()> {
+            if quad.graph_name.is_default_graph() {
+                return self.add_triple(quad.into());
+            }
+            if let Some(replaced) = &mut self.replaced {
+                if replaced.insert(quad.graph_name.into_owned()) {
+                    self.store
+                        .clear_graph(quad.graph_name)
+                        .map_err(io::Error::other)?;
+                }
+            }
+            self.batch.push(quad.into_owned());
+            if self.batch.len() >= self.batch_size {
+                self.commit()?;
+            }
+            Ok(())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.commit()
+        }
+    }
+}
+
+#[cfg(feature = "compress")]
+pub use compress::{CompressedWriter, Compression};
+
+#[cfg(feature = "compress")]
+mod compress {
+    use super::FileWriter;
+    use flate2::write::GzEncoder;
+    use std::io::{self, Write};
+
+    /// Compression format of an output stream.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+    pub enum Compression {
+        /// gzip, read by `gunzip` and `zcat`.
+        Gzip,
+        /// Zstandard, faster and smaller than gzip, read by `zstd -d` and `zstdcat`.
+        Zstd,
+    }
+
+    enum Encoder<W: Write> {
+        Gzip(GzEncoder<W>),
+        Zstd(zstd::stream::write::Encoder<'static, W>),
+    }
+
+    /// Compresses what is written to it before passing it on to the wrapped writer:
+    ///
+    /// ```
+    /// use oxrdf::{Literal, NamedNodeRef, TripleRef};
+    /// use std::io::Read;
+    /// use xml2rdf::vocab::HAS_NAME;
+    /// use xml2rdf::writer::{CompressedWriter, Compression, FileWriter, RdfWriter};
+    ///
+    /// let node = NamedNodeRef::new_unchecked("https://example.com/n");
+    /// let out = CompressedWriter::new(Vec::new(), Compression::Gzip).unwrap();
+    /// let mut w = FileWriter::new(out);
+    /// let name = Literal::new_simple_literal("note");
+    /// w.add_triple(TripleRef::new(node, HAS_NAME, &name)).unwrap();
+    /// let gz = w.finish().unwrap();
+    ///
+    /// let mut nt = String::new();
+    /// flate2::read::GzDecoder::new(gz.as_slice()).read_to_string(&mut nt).unwrap();
+    /// assert_eq!(
+    ///     nt,
+    ///     "<https://example.com/n> <https://decisym.ai/xml2rdf/model#hasName> \"note\" .\n"
+    /// );
+    /// ```
+    ///
+    /// The output is only complete once [`CompressedWriter::finish`] has written the end of the
+    /// stream, so call it even after a failed or interrupted conversion to keep what was written.
+    /// Flushing passes on nothing, as ending a compressed block early would hurt compression.
+    /// Appending to an existing compressed file adds a stream, which decompresses as the
+    /// concatenation of both.
+    pub struct CompressedWriter<W: Write> {
+        encoder: Encoder<W>,
+    }
+
+    impl<W: Write> CompressedWriter<W> {
+        /// Compresses to `writer` at the default level of `compression`.
+        pub fn new(writer: W, compression: Compression) -> io::Result<Self> {
+            let encoder = match compression {
+                Compression::Gzip => {
+                    Encoder::Gzip(GzEncoder::new(writer, flate2::Compression::default()))
+                }
+                Compression::Zstd => Encoder::Zstd(zstd::stream::write::Encoder::new(writer, 0)?),
+            };
+            Ok(CompressedWriter { encoder })
+        }
+
+        /// Writes the end of the compressed stream and returns the wrapped writer, flushed.
+        pub fn finish(self) -> io::Result<W> {
+            let mut writer = match self.encoder {
+                Encoder::Gzip(encoder) => encoder.finish()?,
+                Encoder::Zstd(encoder) => encoder.finish()?,
+            };
+            writer.flush()?;
+            Ok(writer)
+        }
+    }
+
+    impl<W: Write> Write for CompressedWriter<W> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            match &mut self.encoder {
+                Encoder::Gzip(encoder) => encoder.write(buf),
+                Encoder::Zstd(encoder) => encoder.write(buf),
+            }
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<W: Write> FileWriter<CompressedWriter<W>> {
+        /// Flushes the output, ends the compressed stream and returns the wrapped writer.
+        pub fn finish(self) -> io::Result<W> {
+            self.writer
+                .into_inner()
+                .map_err(|e| e.into_error())?
+                .finish()
+        }
+    }
+}
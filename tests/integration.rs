@@ -1,12 +1,50 @@
 // Copyright (c) 2024-2025, Decisym, LLC
 // Licensed under the BSD 3-Clause License (see LICENSE file in the project root).
 
-use oxrdf::Graph;
+use oxrdf::vocab::rdf;
+use oxrdf::{Graph, NamedNodeRef, TermRef};
 use oxrdfio::{RdfFormat, RdfParser};
 use std::fs;
 use std::fs::File;
 use xml2rdf::*;
 
+// The crate's JSON parser is internal; the tests share it to read JSON output
+#[allow(dead_code)]
+#[path = "../src/json.rs"]
+mod json;
+
+const HAS_VALUE: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("https://decisym.ai/xml2rdf/model#hasValue");
+const HAS_NAME: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("https://decisym.ai/xml2rdf/model#hasName");
+const HAS_ATTRIBUTE: NamedNodeRef<'_> =
+    NamedNodeRef::new_unchecked("https://decisym.ai/xml2rdf/model#hasAttribute");
+
+fn convert_to_graph(file: &str, options: &convert::ConversionOptions) -> Graph {
+    let mut g = Graph::new();
+    let mut w = writer::GraphWriter::new(&mut g);
+    convert::parse_xml_with_options(
+        vec![file.to_string()],
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        options,
+    )
+    .expect("conversion failed");
+    g
+}
+
+fn literal_values(g: &Graph) -> Vec<String> {
+    let mut values: Vec<String> = g
+        .triples_for_predicate(HAS_VALUE)
+        .filter_map(|t| match t.object {
+            TermRef::Literal(l) => Some(l.value().to_string()),
+            _ => None,
+        })
+        .collect();
+    values.sort();
+    values
+}
+
 #[test]
 fn test_graph_writer() {
     let mut g = Graph::new();
@@ -43,3 +81,4700 @@ fn test_file_writer() {
 
     assert_eq!(quads.len(), 273)
 }
+
+#[cfg(feature = "oxigraph")]
+#[test]
+fn test_oxigraph_store_writer() {
+    use oxigraph::sparql::QueryResults;
+    use oxigraph::store::Store;
+    use oxrdf::Literal;
+
+    let store = Store::new().expect("failed to create in-memory store");
+    let mut w = writer::OxigraphStoreWriter::new(store.clone()).with_batch_size(100);
+
+    let res = convert::parse_xml(
+        vec!["tests/resources/people.xml".to_string()],
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+    );
+    assert!(res.is_ok());
+
+    let QueryResults::Solutions(mut solutions) = store
+        .query("SELECT (COUNT(*) AS ?c) WHERE { ?s ?p ?o }")
+        .expect("failed to query store")
+    else {
+        panic!("expected solutions from SELECT query");
+    };
+    let solution = solutions
+        .next()
+        .expect("missing count solution")
+        .expect("failed to evaluate count");
+    assert_eq!(solution.get("c"), Some(&Literal::from(273).into()))
+}
+
+fn multi_value_graph(policy: convert::MultiValuePolicy) -> Graph {
+    convert_to_graph(
+        "tests/resources/fragments.xml",
+        &convert::ConversionOptions {
+            multi_value_policy: policy,
+            ..Default::default()
+        },
+    )
+}
+
+#[test]
+fn test_multi_value_concatenate() {
+    let g = multi_value_graph(convert::MultiValuePolicy::Concatenate);
+    assert_eq!(literal_values(&g), vec!["foobar"]);
+}
+
+#[test]
+fn test_multi_value_first_only() {
+    let g = multi_value_graph(convert::MultiValuePolicy::FirstOnly);
+    assert_eq!(literal_values(&g), vec!["foo"]);
+}
+
+#[test]
+fn test_multi_value_allow_multiple() {
+    let g = multi_value_graph(convert::MultiValuePolicy::AllowMultiple);
+    assert_eq!(literal_values(&g), vec!["bar", "foo"]);
+}
+
+#[test]
+fn test_multi_value_list() {
+    let g = multi_value_graph(convert::MultiValuePolicy::List);
+    assert!(literal_values(&g).is_empty());
+
+    let head = g
+        .triples_for_predicate(HAS_VALUE)
+        .next()
+        .expect("missing hasValue list")
+        .object;
+    let TermRef::BlankNode(first_cell) = head else {
+        panic!("hasValue should point at an rdf:List");
+    };
+    assert_eq!(
+        g.object_for_subject_predicate(first_cell, rdf::FIRST),
+        Some(TermRef::Literal(oxrdf::LiteralRef::new_simple_literal(
+            "foo"
+        )))
+    );
+    let Some(TermRef::BlankNode(second_cell)) =
+        g.object_for_subject_predicate(first_cell, rdf::REST)
+    else {
+        panic!("list should have a second cell");
+    };
+    assert_eq!(
+        g.object_for_subject_predicate(second_cell, rdf::FIRST),
+        Some(TermRef::Literal(oxrdf::LiteralRef::new_simple_literal(
+            "bar"
+        )))
+    );
+    assert_eq!(
+        g.object_for_subject_predicate(second_cell, rdf::REST),
+        Some(TermRef::NamedNode(rdf::NIL))
+    );
+}
+
+#[test]
+fn test_invalid_iri_reports_position() {
+    let mut g = Graph::new();
+    let mut w = writer::GraphWriter::new(&mut g);
+
+    let res = convert::parse_xml(
+        vec!["tests/resources/invalid_name.xml".to_string()],
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+    );
+    let Err(error::ConvertError::InvalidIri {
+        source,
+        path,
+        iri,
+        position,
+    }) = res
+    else {
+        panic!("expected an InvalidIri error, got {res:?}");
+    };
+    assert!(
+        path.ends_with("root.bad\u{FFFA}name"),
+        "unexpected path {path}"
+    );
+    assert!(iri.contains("bad\u{FFFA}name"), "unexpected IRI {iri}");
+    assert_eq!(position.row + 1, 3);
+    assert_eq!(position.column + 1, 3);
+    assert_eq!(source.as_deref(), Some("tests/resources/invalid_name.xml"));
+}
+
+#[test]
+fn test_attribute_bag() {
+    let g = convert_to_graph(
+        "tests/resources/attributes.xml",
+        &convert::ConversionOptions {
+            attribute_bag: true,
+            ..Default::default()
+        },
+    );
+    let has_bag = NamedNodeRef::new_unchecked("https://decisym.ai/xml2rdf/model#hasAttributeBag");
+
+    let widget = g
+        .subject_for_predicate_object(HAS_NAME, oxrdf::LiteralRef::new_simple_literal("item"))
+        .expect("missing item element");
+    let bags: Vec<_> = g
+        .triples_for_predicate(has_bag)
+        .map(|t| t.object.into_owned())
+        .collect();
+    assert_eq!(bags.len(), 2);
+
+    // Every attribute hangs off a bag, never directly off an element
+    let attribute_parents: Vec<_> = g
+        .triples_for_predicate(HAS_ATTRIBUTE)
+        .map(|t| oxrdf::Term::from(t.subject.into_owned()))
+        .collect();
+    assert_eq!(attribute_parents.len(), 4);
+    assert!(attribute_parents.iter().all(|p| bags.contains(p)));
+    assert!(g
+        .triples_for_subject(widget)
+        .all(|t| t.predicate != HAS_ATTRIBUTE));
+
+    // The three attributes of the first item share a single bag
+    let first_bag = bags
+        .iter()
+        .find(|b| attribute_parents.iter().filter(|p| p == b).count() == 3);
+    assert!(first_bag.is_some());
+}
+
+#[test]
+fn test_convert_into_graph() {
+    let files = [
+        "tests/resources/people_a.xml",
+        "tests/resources/people_b.xml",
+    ];
+    // Deterministic IDs make a file converted twice produce the same triples
+    let options = convert::ConversionOptions {
+        node_ids: convert::NodeIds::Deterministic {
+            hash: convert::IdHash::default(),
+        },
+        ..Default::default()
+    };
+    let separate: Vec<Graph> = files
+        .iter()
+        .map(|file| convert_to_graph(file, &options))
+        .collect();
+    let shared = separate[0]
+        .iter()
+        .filter(|t| separate[1].contains(*t))
+        .count();
+
+    let existing = oxrdf::Triple::new(
+        NamedNodeRef::new("https://example.com/s").unwrap(),
+        NamedNodeRef::new("https://example.com/p").unwrap(),
+        oxrdf::Literal::new_simple_literal("kept"),
+    );
+    let mut g = Graph::new();
+    g.insert(&existing);
+    let mut convert_into = |files: &[&str]| {
+        convert::convert_into_graph(
+            files.iter().map(|f| f.to_string()).collect(),
+            &mut g,
+            "https://decisym.ai/xml2rdf/data",
+            &options,
+        )
+        .expect("conversion failed");
+    };
+    convert_into(&files[..1]);
+    // Files converted again merge with their earlier triples
+    convert_into(&files);
+    convert_into(&files);
+
+    assert!(g.contains(&existing));
+    assert_eq!(g.len(), 1 + separate[0].len() + separate[1].len() - shared);
+}
+
+#[test]
+fn test_graph_stats() {
+    use std::process::Command;
+
+    let output = std::env::temp_dir()
+        .join(format!("xml2rdf_stats_{}.nt", std::process::id()))
+        .to_string_lossy()
+        .into_owned();
+    let _ = fs::remove_file(output.clone());
+
+    let mut w = writer::FileWriter::to_file(output.clone()).expect("Failed to open output file");
+    let res = convert::parse_xml(
+        vec!["tests/resources/attributes.xml".to_string()],
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+    );
+    assert!(res.is_ok());
+
+    let g = stats::load_graph(&output).expect("failed to load converted output");
+    let _ = fs::remove_file(output);
+    let report = stats::graph_stats(&g);
+    assert_eq!(report.elements, 3);
+    assert_eq!(report.attributes, 4);
+    assert_eq!(report.max_depth, 2);
+    assert_eq!(report.leaf_elements, 2);
+    assert_eq!(report.branch_elements, 1);
+    assert_eq!(
+        report.element_names,
+        vec![("item".to_string(), 2), ("catalog".to_string(), 1)]
+    );
+
+    // Blank element nodes are walked alike
+    let g = convert_to_graph(
+        "tests/resources/attributes.xml",
+        &convert::ConversionOptions {
+            node_ids: convert::NodeIds::Blank,
+            ..Default::default()
+        },
+    );
+    let blank = stats::graph_stats(&g);
+    assert_eq!(blank.max_depth, report.max_depth);
+    assert_eq!(blank.leaf_elements, report.leaf_elements);
+
+    // An unreadable input fails like `diff`
+    let output = Command::new(env!("CARGO_BIN_EXE_xml2rdf"))
+        .args(["stats", "--input", "tests/resources/missing.nt"])
+        .output()
+        .expect("failed to run xml2rdf");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+}
+
+fn whitespace_values(trim_text: bool, emit_whitespace_only: bool) -> Vec<String> {
+    let g = convert_to_graph(
+        "tests/resources/whitespace.xml",
+        &convert::ConversionOptions {
+            trim_text,
+            emit_whitespace_only,
+            ..Default::default()
+        },
+    );
+    literal_values(&g)
+}
+
+#[test]
+fn test_trim_without_whitespace_only() {
+    assert_eq!(whitespace_values(true, false), vec!["hello world"]);
+}
+
+#[test]
+fn test_trim_with_whitespace_only() {
+    assert_eq!(whitespace_values(true, true), vec!["", "hello world"]);
+}
+
+#[test]
+fn test_no_trim_without_whitespace_only() {
+    assert_eq!(whitespace_values(false, false), vec!["  hello world  "]);
+}
+
+#[test]
+fn test_no_trim_with_whitespace_only() {
+    assert_eq!(
+        whitespace_values(false, true),
+        vec!["   ", "  hello world  "]
+    );
+}
+
+#[test]
+fn test_string_writer() {
+    let xml = fs::read("tests/resources/people.xml").expect("failed to read fixture");
+    let mut w = writer::StringWriter::new();
+    let res = convert::parse_xml_reader(
+        xml.as_slice(),
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions::default(),
+    );
+    assert!(res.is_ok());
+
+    let triples = RdfParser::from_format(RdfFormat::NTriples)
+        .for_reader(w.into_bytes().as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .expect("failed to parse generated string");
+    assert_eq!(triples.len(), 273)
+}
+
+fn person_classes(class_key: convert::ClassKey) -> (Graph, Vec<oxrdf::Term>) {
+    let mut g = Graph::new();
+    let mut w = writer::GraphWriter::new(&mut g);
+    convert::parse_xml_with_options(
+        vec![
+            "tests/resources/people_a.xml".to_string(),
+            "tests/resources/people_b.xml".to_string(),
+        ],
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            class_key,
+            ..Default::default()
+        },
+    )
+    .expect("conversion failed");
+
+    let mut classes: Vec<oxrdf::Term> = g
+        .subjects_for_predicate_object(HAS_NAME, oxrdf::LiteralRef::new_simple_literal("person"))
+        .filter_map(|person| g.object_for_subject_predicate(person, rdf::TYPE))
+        .map(|class| class.into_owned())
+        .collect();
+    classes.dedup();
+    (g, classes)
+}
+
+#[test]
+fn test_class_key_path() {
+    let (_, classes) = person_classes(convert::ClassKey::Path);
+    assert_eq!(classes.len(), 2);
+}
+
+#[test]
+fn test_class_key_name() {
+    let (g, classes) = person_classes(convert::ClassKey::Name);
+    let person = oxrdf::NamedNode::new_unchecked("https://decisym.ai/xml2rdf/model#person");
+    assert_eq!(classes, vec![person.clone().into()]);
+
+    // Both path classes are declared equivalent to the shared class
+    let equivalent = NamedNodeRef::new_unchecked("http://www.w3.org/2002/07/owl#equivalentClass");
+    let mut path_classes: Vec<String> = g
+        .subjects_for_predicate_object(equivalent, person.as_ref())
+        .map(|c| c.to_string())
+        .collect();
+    path_classes.sort();
+    assert_eq!(
+        path_classes,
+        vec![
+            "<https://decisym.ai/xml2rdf/model#people.person>",
+            "<https://decisym.ai/xml2rdf/model#staff.person>"
+        ]
+    );
+}
+
+fn class_iris(path_delimiter: &str) -> Vec<String> {
+    let mut g = Graph::new();
+    let mut w = writer::GraphWriter::new(&mut g);
+    convert::parse_xml_with_options(
+        vec!["tests/resources/attributes.xml".to_string()],
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            path_delimiter: path_delimiter.to_string(),
+            ..Default::default()
+        },
+    )
+    .expect("conversion failed");
+
+    let mut classes: Vec<String> = g
+        .triples_for_predicate(rdf::TYPE)
+        .map(|t| t.object.to_string())
+        .collect();
+    classes.sort();
+    classes.dedup();
+    classes
+}
+
+#[test]
+fn test_path_delimiter() {
+    let model = |class: &str| format!("<https://decisym.ai/xml2rdf/model#{class}>");
+    let expected = |classes: &[&str]| {
+        let mut classes: Vec<String> = classes.iter().map(|c| model(c)).collect();
+        classes.sort();
+        classes
+    };
+
+    assert_eq!(
+        class_iris("."),
+        expected(&[
+            "catalog",
+            "catalog.item",
+            "catalog.item.-color",
+            "catalog.item.-id",
+            "catalog.item.-name",
+        ])
+    );
+    assert_eq!(
+        class_iris("/"),
+        expected(&[
+            "catalog",
+            "catalog/item",
+            "catalog/item/-color",
+            "catalog/item/-id",
+            "catalog/item/-name",
+        ])
+    );
+}
+
+#[test]
+fn test_channel_writer() {
+    let (mut w, triples) = writer::ChannelWriter::new(16);
+    let producer = std::thread::spawn(move || {
+        convert::parse_xml(
+            vec!["tests/resources/people.xml".to_string()],
+            &mut w,
+            "https://decisym.ai/xml2rdf/data",
+        )
+    });
+
+    assert_eq!(triples.iter().count(), 273);
+    assert!(producer.join().expect("conversion thread panicked").is_ok());
+}
+
+#[test]
+fn test_embed_ontology() {
+    let mut w = writer::StringWriter::new();
+    convert::parse_xml_with_options(
+        vec![
+            "tests/resources/people_a.xml".to_string(),
+            "tests/resources/people_b.xml".to_string(),
+        ],
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            embed_ontology: true,
+            ..Default::default()
+        },
+    )
+    .expect("conversion failed");
+    let output = w.into_string();
+    let lines: Vec<&str> = output.lines().collect();
+
+    // The ontology is emitted once, ahead of all converted data
+    let ontology: Vec<String> = vocab::ontology().iter().map(|t| format!("{t} .")).collect();
+    assert!(!ontology.is_empty());
+    assert_eq!(lines[..ontology.len()], ontology[..]);
+    assert!(lines[ontology.len()..]
+        .iter()
+        .all(|l| !ontology.contains(&l.to_string())));
+    assert!(lines[ontology.len()].starts_with("<https://decisym.ai/xml2rdf/data/"));
+}
+
+#[test]
+fn test_wide_element() {
+    let mut xml = String::from("<root><wide");
+    for i in 0..5_000 {
+        xml.push_str(&format!(" attr{i}=\"value {i}\""));
+    }
+    xml.push_str("/></root>");
+
+    let mut g = Graph::new();
+    let mut w = writer::GraphWriter::new(&mut g);
+    convert::parse_xml_reader(
+        xml.as_bytes(),
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions::default(),
+    )
+    .expect("conversion failed");
+
+    assert_eq!(
+        g.triples_for_predicate(HAS_ATTRIBUTE).count(),
+        5_000,
+        "every attribute should be attached to the element"
+    );
+    let mut expected: Vec<String> = (0..5_000).map(|i| format!("value {i}")).collect();
+    expected.sort();
+    assert_eq!(literal_values(&g), expected);
+}
+
+#[test]
+fn test_document_node_prov() {
+    let file = "tests/resources/people_a.xml";
+    let g = convert_to_graph(
+        file,
+        &convert::ConversionOptions {
+            document_node: true,
+            prov: true,
+            ..Default::default()
+        },
+    );
+
+    let documents: Vec<_> = g
+        .subjects_for_predicate_object(rdf::TYPE, vocab::XML_DOCUMENT)
+        .collect();
+    assert_eq!(documents.len(), 1);
+    let source = format!("file://{}", fs::canonicalize(file).unwrap().display());
+    let derived: Vec<_> = g
+        .objects_for_subject_predicate(documents[0], vocab::PROV_WAS_DERIVED_FROM)
+        .collect();
+    assert_eq!(
+        derived,
+        vec![TermRef::NamedNode(NamedNodeRef::new(&source).unwrap())]
+    );
+    assert!(g.triples_for_predicate(vocab::SOURCE_FILE).next().is_none());
+}
+
+#[test]
+fn test_source_iris() {
+    let file = "tests/resources/people_a.xml";
+    let derived_from = |source_iris| {
+        let g = convert_to_graph(
+            file,
+            &convert::ConversionOptions {
+                document_node: true,
+                prov: true,
+                source_iris,
+                ..Default::default()
+            },
+        );
+        let derived: Vec<String> = g
+            .triples_for_predicate(vocab::PROV_WAS_DERIVED_FROM)
+            .map(|t| match t.object {
+                TermRef::NamedNode(n) => n.as_str().to_string(),
+                other => panic!("unexpected source {other}"),
+            })
+            .collect();
+        assert_eq!(derived.len(), 1);
+        derived[0].clone()
+    };
+    let local = fs::canonicalize(file).unwrap().display().to_string();
+
+    let relative = derived_from(convert::SourceIris::Base(
+        "https://example.com/data/".to_string(),
+    ));
+    assert_eq!(
+        relative,
+        "https://example.com/data/tests/resources/people_a.xml"
+    );
+
+    let opaque = derived_from(convert::SourceIris::Opaque);
+    assert!(opaque.starts_with("urn:uuid:"));
+    assert!(!opaque.contains("people_a"));
+    // Stable across runs
+    assert_eq!(opaque, derived_from(convert::SourceIris::Opaque));
+
+    assert_eq!(
+        derived_from(convert::SourceIris::File),
+        format!("file://{local}")
+    );
+}
+
+#[test]
+fn test_preserve_default_namespaces() {
+    let mut g = Graph::new();
+    let mut w = writer::GraphWriter::new(&mut g);
+    convert::parse_xml_with_options(
+        vec![
+            "tests/resources/default_ns_a.xml".to_string(),
+            "tests/resources/default_ns_b.xml".to_string(),
+        ],
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            preserve_namespaces: true,
+            ..Default::default()
+        },
+    )
+    .expect("conversion failed");
+
+    let classes_of = |subjects: Vec<oxrdf::SubjectRef>| {
+        let mut classes: Vec<String> = subjects
+            .into_iter()
+            .filter_map(|s| g.object_for_subject_predicate(s, rdf::TYPE))
+            .map(|c| c.to_string())
+            .collect();
+        classes.sort();
+        classes
+    };
+
+    // Items in different default namespaces get different classes
+    let items = g
+        .subjects_for_predicate_object(HAS_NAME, oxrdf::LiteralRef::new_simple_literal("item"))
+        .collect();
+    assert_eq!(
+        classes_of(items),
+        vec![
+            "<https://decisym.ai/xml2rdf/model#%7Bhttp%3A%2F%2Fa%2F%7Dcatalog.%7Bhttp%3A%2F%2Fa%2F%7Ditem>",
+            "<https://decisym.ai/xml2rdf/model#%7Bhttp%3A%2F%2Fb%2F%7Dcatalog.%7Bhttp%3A%2F%2Fb%2F%7Ditem>",
+        ]
+    );
+
+    // The unprefixed attribute is in no namespace, the prefixed one keeps its own
+    let attributes = g
+        .triples_for_predicate(HAS_ATTRIBUTE)
+        .filter_map(|t| match t.object {
+            TermRef::NamedNode(n) => Some(n.into()),
+            _ => None,
+        })
+        .collect();
+    let classes = classes_of(attributes);
+    assert_eq!(classes.len(), 4);
+    assert_eq!(
+        classes.iter().filter(|c| c.ends_with("item.-id>")).count(),
+        2
+    );
+    assert_eq!(
+        classes
+            .iter()
+            .filter(|c| c.ends_with("item.-%7Bhttp%3A%2F%2Fx%2F%7Dcode>"))
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn test_reconstruction_graph() {
+    let graph = oxrdf::NamedNode::new_unchecked("https://example.com/reconstruction");
+    let mut dataset = oxrdf::Dataset::new();
+    let mut w = writer::DatasetWriter::new(&mut dataset);
+    convert::parse_xml_with_options(
+        vec!["tests/resources/attributes.xml".to_string()],
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            reconstruction_graph: Some(graph.clone()),
+            ..Default::default()
+        },
+    )
+    .expect("conversion failed");
+
+    let order = [vocab::SIBLING_INDEX, vocab::ATTRIBUTE_INDEX];
+    let (indices, content): (Vec<_>, Vec<_>) =
+        dataset.iter().partition(|q| order.contains(&q.predicate));
+
+    // 3 elements and 4 attributes, all in the reconstruction graph
+    assert_eq!(indices.len(), 7);
+    assert!(indices
+        .iter()
+        .all(|q| q.graph_name == graph.as_ref().into()));
+    assert!(!content.is_empty());
+    assert!(content.iter().all(|q| q.graph_name.is_default_graph()));
+
+    // The second item is the second child of the catalog
+    let second = dataset
+        .quads_for_predicate(vocab::SIBLING_INDEX)
+        .filter(|q| q.object == oxrdf::Literal::from(1u64).as_ref().into())
+        .count();
+    assert_eq!(second, 1);
+
+    // Writers holding a single graph refuse the reconstruction graph
+    let mut g = Graph::new();
+    let mut w = writer::GraphWriter::new(&mut g);
+    assert!(convert::parse_xml_with_options(
+        vec!["tests/resources/attributes.xml".to_string()],
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            reconstruction_graph: Some(graph),
+            ..Default::default()
+        },
+    )
+    .is_err());
+}
+
+#[cfg(feature = "zip")]
+#[test]
+fn test_zip_archive() {
+    use std::io::{Cursor, Write};
+    use zip::write::{SimpleFileOptions, ZipWriter};
+
+    let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+    let entries = [
+        ("a.xml", "<note><to>Tove</to></note>"),
+        ("nested/dir/b.xml", "<note><to>Jani</to></note>"),
+        ("readme.txt", "<note><to>Skipped</to></note>"),
+    ];
+    archive
+        .add_directory("nested/dir/", SimpleFileOptions::default())
+        .unwrap();
+    for (name, xml) in entries {
+        archive
+            .start_file(name, SimpleFileOptions::default())
+            .unwrap();
+        archive.write_all(xml.as_bytes()).unwrap();
+    }
+    let bytes = archive.finish().unwrap().into_inner();
+
+    let mut g = Graph::new();
+    let mut w = writer::GraphWriter::new(&mut g);
+    convert::parse_zip_reader(
+        Cursor::new(bytes),
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions::default(),
+    )
+    .expect("conversion failed");
+
+    assert_eq!(literal_values(&g), vec!["Jani", "Tove"]);
+}
+
+#[test]
+fn test_emit_namespace_decls() {
+    let file = "tests/resources/default_ns_a.xml";
+    let g = convert_to_graph(file, &convert::ConversionOptions::default());
+    assert!(g
+        .triples_for_predicate(vocab::DECLARES_NAMESPACE)
+        .next()
+        .is_none());
+
+    let g = convert_to_graph(
+        file,
+        &convert::ConversionOptions {
+            emit_namespace_decls: true,
+            ..Default::default()
+        },
+    );
+    let catalog = g
+        .subject_for_predicate_object(HAS_NAME, oxrdf::LiteralRef::new_simple_literal("catalog"))
+        .unwrap();
+    let mut declarations: Vec<(String, String)> = g
+        .objects_for_subject_predicate(catalog, vocab::DECLARES_NAMESPACE)
+        .map(|d| {
+            let d = match d {
+                TermRef::BlankNode(b) => b,
+                _ => panic!("declaration should be a blank node"),
+            };
+            let value = |p| match g.object_for_subject_predicate(d, p) {
+                Some(TermRef::Literal(l)) => l.value().to_string(),
+                _ => panic!("declaration is missing {p}"),
+            };
+            (value(vocab::NAMESPACE_PREFIX), value(vocab::NAMESPACE_URI))
+        })
+        .collect();
+    declarations.sort();
+    assert_eq!(
+        declarations,
+        vec![
+            ("".to_string(), "http://a/".to_string()),
+            ("x".to_string(), "http://x/".to_string())
+        ]
+    );
+
+    // The item inherits the declarations without repeating them
+    assert_eq!(
+        g.triples_for_predicate(vocab::DECLARES_NAMESPACE).count(),
+        2
+    );
+}
+
+#[cfg(feature = "canonical")]
+fn canonicalize(nquads: &str) -> String {
+    let mut dataset = oxrdf::Dataset::new();
+    for quad in RdfParser::from_format(RdfFormat::NQuads).for_reader(nquads.as_bytes()) {
+        dataset.insert(&quad.unwrap());
+    }
+    canonical::canonical_nquads(&dataset)
+}
+
+#[cfg(feature = "canonical")]
+#[test]
+fn test_canonical_spec_examples() {
+    // Examples from the RDF Dataset Canonicalization specification
+    let unique = "\
+<http://example.com/#p> <http://example.com/#q> _:e0 .
+<http://example.com/#p> <http://example.com/#r> _:e1 .
+_:e0 <http://example.com/#s> <http://example.com/#u> .
+_:e1 <http://example.com/#t> <http://example.com/#u> .
+";
+    assert_eq!(
+        canonicalize(unique),
+        "\
+<http://example.com/#p> <http://example.com/#q> _:c14n0 .
+<http://example.com/#p> <http://example.com/#r> _:c14n1 .
+_:c14n0 <http://example.com/#s> <http://example.com/#u> .
+_:c14n1 <http://example.com/#t> <http://example.com/#u> .
+"
+    );
+
+    let shared = "\
+<http://example.com/#p> <http://example.com/#q> _:e0 .
+<http://example.com/#p> <http://example.com/#q> _:e1 .
+_:e0 <http://example.com/#p> _:e2 .
+_:e1 <http://example.com/#p> _:e3 .
+_:e2 <http://example.com/#r> _:e3 .
+";
+    assert_eq!(
+        canonicalize(shared),
+        "\
+<http://example.com/#p> <http://example.com/#q> _:c14n2 .
+<http://example.com/#p> <http://example.com/#q> _:c14n3 .
+_:c14n0 <http://example.com/#r> _:c14n1 .
+_:c14n2 <http://example.com/#p> _:c14n1 .
+_:c14n3 <http://example.com/#p> _:c14n0 .
+"
+    );
+}
+
+#[cfg(feature = "canonical")]
+#[test]
+fn test_canonical_conversions_match() {
+    let options = convert::ConversionOptions {
+        node_ids: convert::NodeIds::Blank,
+        multi_value_policy: convert::MultiValuePolicy::List,
+        ..Default::default()
+    };
+    let convert = || {
+        let mut out = Vec::new();
+        let mut w = canonical::CanonicalWriter::new(&mut out);
+        convert::parse_xml_with_options(
+            vec!["tests/resources/people_a.xml".to_string()],
+            &mut w,
+            "https://decisym.ai/xml2rdf/data",
+            &options,
+        )
+        .expect("conversion failed");
+        String::from_utf8(out).unwrap()
+    };
+
+    let first = convert();
+    assert!(first.contains("_:c14n0 "));
+    assert_eq!(first, convert());
+
+    // Converting into the same file again replaces it
+    let output = std::env::temp_dir().join(format!("xml2rdf_canonical_{}.nq", std::process::id()));
+    for _ in 0..2 {
+        let status = std::process::Command::new(env!("CARGO_BIN_EXE_xml2rdf"))
+            .args(["convert", "--xml", "tests/resources/people_a.xml"])
+            .args([
+                "--node-ids",
+                "blank",
+                "--multi-value-policy",
+                "list",
+                "--canonical",
+            ])
+            .arg("--output-file")
+            .arg(&output)
+            .status()
+            .expect("failed to run xml2rdf");
+        assert!(status.success());
+    }
+    assert_eq!(fs::read_to_string(&output).unwrap(), first);
+    fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn test_urn_node_ids() {
+    let nt = convert::convert_bytes(
+        b"<catalog><item id=\"1\">Widget</item></catalog>",
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            node_ids: convert::NodeIds::Urn,
+            ..Default::default()
+        },
+    )
+    .expect("conversion failed");
+    assert!(!nt.contains("https://decisym.ai/xml2rdf/data"));
+
+    let parse = |nt: &str| -> Graph {
+        RdfParser::from_format(RdfFormat::NTriples)
+            .for_reader(nt.as_bytes())
+            .map(|q| oxrdf::Triple::from(q.expect("output does not parse")))
+            .collect()
+    };
+    let g = parse(&nt);
+    assert_eq!(g.len(), nt.lines().count());
+    // Every node is a URN; only model classes keep their IRIs
+    let subjects: Vec<String> = g.iter().map(|t| t.subject.to_string()).collect();
+    assert!(subjects.iter().any(|s| s.starts_with("<urn:x2r:")));
+    assert!(subjects
+        .iter()
+        .all(|s| s.starts_with("<urn:x2r:") || s.starts_with(&format!("<{}", vocab::X2R))));
+
+    // The URNs survive serializing and parsing again
+    let again: String = g.iter().map(|t| format!("{t} .\n")).collect();
+    assert_eq!(parse(&again), g);
+}
+
+#[test]
+fn test_convert_bytes() {
+    let xml = b"<catalog><item id=\"1\">Widget</item></catalog>";
+    let nt = convert::convert_bytes(
+        xml,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions::default(),
+    )
+    .expect("conversion failed");
+
+    let mut g = Graph::new();
+    for quad in RdfParser::from_format(RdfFormat::NTriples).for_reader(nt.as_bytes()) {
+        g.insert(quad.expect("failed to parse generated string").as_ref());
+    }
+    assert_eq!(literal_values(&g), vec!["1", "Widget"]);
+}
+
+#[test]
+fn test_max_triples() {
+    let mut w = writer::StringWriter::new();
+    let summary = convert::parse_xml_with_options(
+        vec!["tests/resources/people.xml".to_string()],
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            max_triples: Some(50),
+            ..Default::default()
+        },
+    )
+    .expect("conversion failed");
+    assert_eq!(
+        summary,
+        convert::ConversionSummary {
+            triples: 50,
+            truncated: true,
+            documents: 1,
+            input_bytes: summary.input_bytes,
+            ..Default::default()
+        }
+    );
+
+    // The partial output is still valid N-Triples
+    let triples = RdfParser::from_format(RdfFormat::NTriples)
+        .for_reader(w.into_bytes().as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .expect("failed to parse truncated output");
+    assert_eq!(triples.len(), 50);
+
+    // A limit above the output size leaves the conversion untouched
+    let mut g = Graph::new();
+    let mut w = writer::GraphWriter::new(&mut g);
+    let summary = convert::parse_xml_with_options(
+        vec!["tests/resources/people.xml".to_string()],
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            max_triples: Some(273),
+            ..Default::default()
+        },
+    )
+    .expect("conversion failed");
+    assert!(!summary.truncated);
+    assert_eq!(g.len(), 273);
+}
+
+#[test]
+fn test_infer_datatypes() {
+    let typed = |options: &convert::ConversionOptions| {
+        let g = convert_to_graph("tests/resources/typed.xml", options);
+        let mut values: Vec<String> = g
+            .triples_for_predicate(HAS_VALUE)
+            .map(|t| t.object.to_string())
+            .collect();
+        values.sort();
+        values
+    };
+
+    assert_eq!(
+        typed(&convert::ConversionOptions {
+            infer_datatypes: true,
+            ..Default::default()
+        }),
+        vec![
+            "\"007\"",
+            "\"007\"",
+            "\"12\"^^<http://www.w3.org/2001/XMLSchema#integer>",
+            "\"2024-05-01\"^^<http://www.w3.org/2001/XMLSchema#date>",
+            "\"5\"^^<http://www.w3.org/2001/XMLSchema#integer>",
+            "\"9.99\"^^<http://www.w3.org/2001/XMLSchema#decimal>",
+            "\"true\"^^<http://www.w3.org/2001/XMLSchema#boolean>",
+        ]
+    );
+
+    // Without inference every value stays a plain string
+    assert!(typed(&convert::ConversionOptions::default())
+        .iter()
+        .all(|v| !v.contains("^^")));
+}
+
+#[test]
+fn test_infer_durations_and_years() {
+    use oxrdf::vocab::xsd;
+
+    let xml = "<r><span>P1Y2M</span><wait>PT1.5S</wait><year>2024</year><founded>1999</founded>\
+               <month>2024-03</month><bad>PT</bad><year>unknown</year></r>";
+    let typed = |datatypes: Vec<(&str, NamedNodeRef)>| {
+        let mut g = Graph::new();
+        let mut w = writer::GraphWriter::new(&mut g);
+        convert::parse_xml_reader(
+            xml.as_bytes(),
+            &mut w,
+            "https://decisym.ai/xml2rdf/data",
+            &convert::ConversionOptions {
+                infer_datatypes: true,
+                datatypes: datatypes
+                    .into_iter()
+                    .map(|(key, datatype)| (key.to_string(), datatype.into_owned()))
+                    .collect(),
+                ..Default::default()
+            },
+        )
+        .expect("conversion failed");
+        let mut values: Vec<String> = g
+            .triples_for_predicate(HAS_VALUE)
+            .map(|t| t.object.to_string())
+            .collect();
+        values.sort();
+        values
+    };
+
+    // A bare year is ambiguous, so it stays an integer unless hinted
+    assert_eq!(
+        typed(vec![]),
+        vec![
+            "\"1999\"^^<http://www.w3.org/2001/XMLSchema#integer>",
+            "\"2024\"^^<http://www.w3.org/2001/XMLSchema#integer>",
+            "\"2024-03\"^^<http://www.w3.org/2001/XMLSchema#gYearMonth>",
+            "\"P1Y2M\"^^<http://www.w3.org/2001/XMLSchema#duration>",
+            "\"PT\"",
+            "\"PT1.5S\"^^<http://www.w3.org/2001/XMLSchema#duration>",
+            "\"unknown\"",
+        ]
+    );
+
+    // Only the hinted field becomes a gYear, and only where its value is one
+    assert_eq!(
+        typed(vec![("r.year", xsd::G_YEAR)]),
+        vec![
+            "\"1999\"^^<http://www.w3.org/2001/XMLSchema#integer>",
+            "\"2024\"^^<http://www.w3.org/2001/XMLSchema#gYear>",
+            "\"2024-03\"^^<http://www.w3.org/2001/XMLSchema#gYearMonth>",
+            "\"P1Y2M\"^^<http://www.w3.org/2001/XMLSchema#duration>",
+            "\"PT\"",
+            "\"PT1.5S\"^^<http://www.w3.org/2001/XMLSchema#duration>",
+            "\"unknown\"",
+        ]
+    );
+}
+
+#[test]
+fn test_collect_warnings() {
+    let xml = "<catalog>\n  <item color=\"\" size=\"2\"/>\n</catalog>";
+    let convert = |collect_warnings| {
+        let mut g = Graph::new();
+        let mut w = writer::GraphWriter::new(&mut g);
+        convert::parse_xml_reader(
+            xml.as_bytes(),
+            &mut w,
+            "https://decisym.ai/xml2rdf/data",
+            &convert::ConversionOptions {
+                collect_warnings,
+                ..Default::default()
+            },
+        )
+        .expect("conversion failed")
+    };
+
+    let summary = convert(true);
+    assert_eq!(summary.warnings.len(), 1);
+    let warning = &summary.warnings[0];
+    assert_eq!(warning.kind, convert::WarningKind::EmptyAttribute);
+    assert_eq!(warning.path, "catalog.item.-color");
+    assert_eq!(warning.source, None);
+    assert_eq!(warning.position.map(|p| p.row), Some(1));
+    assert_eq!(warning.message, "attribute 'color' has an empty value");
+
+    assert!(convert(false).warnings.is_empty());
+}
+
+#[test]
+fn test_mismatched_end_tag() {
+    let mut g = Graph::new();
+    let mut w = writer::GraphWriter::new(&mut g);
+    let summary = convert::parse_xml_reader(
+        "<a><b><c>1</c></x><d>2</d></a>".as_bytes(),
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            collect_warnings: true,
+            ..Default::default()
+        },
+    )
+    .expect("conversion should stop at the error without failing");
+
+    assert_eq!(summary.warnings.len(), 1);
+    let warning = &summary.warnings[0];
+    assert_eq!(warning.kind, convert::WarningKind::MalformedXml);
+    assert_eq!(warning.path, "a.b");
+    assert!(warning.message.contains('x'), "{}", warning.message);
+
+    // Everything before the mismatch is kept, nothing after it is attached to a wrong parent
+    assert_eq!(literal_values(&g), vec!["1"]);
+    let mut names: Vec<_> = g
+        .triples_for_predicate(HAS_NAME)
+        .map(|t| t.object.to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["\"a\"", "\"b\"", "\"c\""]);
+}
+
+#[test]
+fn test_emit_qnames() {
+    let xml = r#"<root xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>T</dc:title><plain/></root>"#;
+    let qnames = |emit_qnames| {
+        let mut g = Graph::new();
+        let mut w = writer::GraphWriter::new(&mut g);
+        convert::parse_xml_reader(
+            xml.as_bytes(),
+            &mut w,
+            "https://decisym.ai/xml2rdf/data",
+            &convert::ConversionOptions {
+                emit_qnames,
+                ..Default::default()
+            },
+        )
+        .expect("conversion failed");
+        let mut pairs: Vec<(String, String)> = g
+            .triples_for_predicate(vocab::HAS_QNAME)
+            .map(|t| {
+                let name = match g.object_for_subject_predicate(t.subject, HAS_NAME) {
+                    Some(TermRef::Literal(l)) => l.value().to_string(),
+                    other => panic!("unexpected name {other:?}"),
+                };
+                match t.object {
+                    TermRef::Literal(l) => (name, l.value().to_string()),
+                    other => panic!("unexpected QName {other}"),
+                }
+            })
+            .collect();
+        pairs.sort();
+        pairs
+    };
+
+    // The prefix is kept where there is one, hasName stays the local name
+    assert_eq!(
+        qnames(true),
+        vec![
+            ("plain".to_string(), "plain".to_string()),
+            ("root".to_string(), "root".to_string()),
+            ("title".to_string(), "dc:title".to_string()),
+        ]
+    );
+    assert!(qnames(false).is_empty());
+}
+
+#[test]
+fn test_distinct_text_predicate() {
+    let g = convert_to_graph(
+        "tests/resources/attributes.xml",
+        &convert::ConversionOptions {
+            vocabulary: vocab::ModelVocabulary::distinct_values(),
+            ..Default::default()
+        },
+    );
+
+    // Element text hangs off elements, attribute values off attribute nodes
+    let text: Vec<_> = g.triples_for_predicate(vocab::HAS_TEXT).collect();
+    assert_eq!(text.len(), 1);
+    assert_eq!(
+        text[0].object,
+        oxrdf::LiteralRef::new_simple_literal("Widget").into()
+    );
+    assert!(g
+        .object_for_subject_predicate(text[0].subject, HAS_NAME)
+        .is_some());
+
+    let mut values = literal_values(&g);
+    values.dedup();
+    assert_eq!(values, vec!["1", "2", "red", "widget"]);
+    assert!(g.triples_for_predicate(HAS_VALUE).all(|t| g
+        .object_for_subject_predicate(t.subject, HAS_NAME)
+        .is_none()));
+}
+
+#[test]
+fn test_empty_documents() {
+    let files = vec![
+        "tests/resources/empty.xml".to_string(),
+        "tests/resources/comments_only.xml".to_string(),
+    ];
+    let mut g = Graph::new();
+    let mut w = writer::GraphWriter::new(&mut g);
+    let summary = convert::parse_xml(files.clone(), &mut w, "https://decisym.ai/xml2rdf/data")
+        .expect("empty documents should convert");
+    assert_eq!(summary.triples, 0);
+    assert!(g.is_empty());
+    assert_eq!(
+        summary.empty_documents,
+        vec![
+            convert::EmptyDocument {
+                source: Some("tests/resources/empty.xml".to_string()),
+                zero_bytes: true,
+            },
+            convert::EmptyDocument {
+                source: Some("tests/resources/comments_only.xml".to_string()),
+                zero_bytes: false,
+            },
+        ]
+    );
+
+    // With document nodes, each file still gets one, without a root
+    let mut g = Graph::new();
+    let mut w = writer::GraphWriter::new(&mut g);
+    convert::parse_xml_with_options(
+        files,
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            document_node: true,
+            ..Default::default()
+        },
+    )
+    .expect("empty documents should convert");
+    assert_eq!(
+        g.subjects_for_predicate_object(rdf::TYPE, vocab::XML_DOCUMENT)
+            .count(),
+        2
+    );
+    assert_eq!(g.triples_for_predicate(vocab::HAS_ROOT).count(), 0);
+}
+
+#[test]
+fn test_json_leaves() {
+    let g = convert_to_graph(
+        "tests/resources/json_leaves.xml",
+        &convert::ConversionOptions {
+            json_leaves: true,
+            ..Default::default()
+        },
+    );
+    let mut json: Vec<String> = g
+        .triples_for_predicate(HAS_VALUE)
+        .filter_map(|t| match t.object {
+            TermRef::Literal(l) if l.datatype() == vocab::RDF_JSON => Some(l.value().to_string()),
+            _ => None,
+        })
+        .collect();
+    json.sort();
+    assert_eq!(
+        json,
+        vec![
+            r#"{"@value":"9.99","currency":"USD"}"#,
+            r#"{"@value":"Say \"hi\"","lang":"en"}"#,
+        ]
+    );
+
+    // Only the root, which has child elements, keeps an attribute node
+    assert_eq!(g.triples_for_predicate(HAS_ATTRIBUTE).count(), 1);
+    assert_eq!(literal_values(&g).len(), 3);
+
+    // Attribute values are trimmed, and empty ones left out, as for attribute nodes
+    let mut g = Graph::new();
+    convert::parse_xml_reader(
+        r#"<price currency=" USD " note="">9.99</price>"#.as_bytes(),
+        &mut writer::GraphWriter::new(&mut g),
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            json_leaves: true,
+            ..Default::default()
+        },
+    )
+    .expect("conversion failed");
+    let json: Vec<String> = g
+        .triples_for_predicate(HAS_VALUE)
+        .map(|t| match t.object {
+            TermRef::Literal(l) => l.value().to_string(),
+            other => panic!("unexpected value {other}"),
+        })
+        .collect();
+    assert_eq!(json, vec![r#"{"@value":"9.99","currency":"USD"}"#]);
+}
+
+#[test]
+fn test_model_namespace_rejected() {
+    for namespace in [vocab::X2R, "https://decisym.ai/xml2rdf/model"] {
+        let mut g = Graph::new();
+        let mut w = writer::GraphWriter::new(&mut g);
+        let err = convert::parse_xml(
+            vec!["tests/resources/attributes.xml".to_string()],
+            &mut w,
+            namespace,
+        )
+        .expect_err("the model namespace must not be used for data");
+        assert!(matches!(err, error::ConvertError::ReservedNamespace { .. }));
+        assert!(err.to_string().contains("overlaps the model namespace"));
+        assert!(g.is_empty());
+    }
+}
+
+#[test]
+fn test_class_map() {
+    let person = oxrdf::NamedNode::new("http://xmlns.com/foaf/0.1/Person").unwrap();
+    let city = oxrdf::NamedNode::new("https://example.org/City").unwrap();
+    let g = convert_to_graph(
+        "tests/resources/people.xml",
+        &convert::ConversionOptions {
+            class_map: [
+                ("Person".to_string(), person.clone()),
+                ("People.Person.city".to_string(), city.clone()),
+            ]
+            .into(),
+            ..Default::default()
+        },
+    );
+    let people = g
+        .subjects_for_predicate_object(rdf::TYPE, person.as_ref())
+        .count();
+    assert!(people > 0);
+    assert_eq!(
+        g.subjects_for_predicate_object(rdf::TYPE, city.as_ref())
+            .count(),
+        people
+    );
+
+    // The structural class is kept
+    let structural = NamedNodeRef::new("https://decisym.ai/xml2rdf/model#People.Person").unwrap();
+    assert_eq!(
+        g.subjects_for_predicate_object(rdf::TYPE, structural)
+            .count(),
+        people
+    );
+}
+
+#[test]
+fn test_deterministic_node_ids() {
+    let options = convert::ConversionOptions {
+        node_ids: convert::NodeIds::Deterministic {
+            hash: convert::IdHash::default(),
+        },
+        attribute_bag: true,
+        document_node: true,
+        ..Default::default()
+    };
+    let first = convert_to_graph("tests/resources/attributes.xml", &options);
+    let second = convert_to_graph("tests/resources/attributes.xml", &options);
+
+    let attributes = |g: &Graph| {
+        let mut iris: Vec<String> = g
+            .triples_for_predicate(HAS_ATTRIBUTE)
+            .map(|t| t.object.to_string())
+            .collect();
+        iris.sort();
+        iris
+    };
+    assert_eq!(attributes(&first).len(), 4);
+    assert_eq!(attributes(&first), attributes(&second));
+    assert_eq!(first, second);
+
+    // Another source gets other nodes
+    let root = |g: &Graph| {
+        g.triples_for_predicate(vocab::HAS_ROOT)
+            .map(|t| t.object.into_owned())
+            .next()
+    };
+    let other = convert_to_graph("tests/resources/whitespace.xml", &options);
+    assert!(root(&first).is_some());
+    assert_ne!(root(&first), root(&other));
+}
+
+#[test]
+fn test_attribute_conditions() {
+    let condition: convert::AttributeCondition = "order@status=paid".parse().unwrap();
+    let g = convert_to_graph(
+        "tests/resources/orders.xml",
+        &convert::ConversionOptions {
+            conditions: vec![condition],
+            ..Default::default()
+        },
+    );
+    // Pending order 2 is skipped with its whole subtree, including the nested paid order 3
+    assert_eq!(
+        literal_values(&g),
+        vec!["1", "10", "4", "40", "Quarterly export", "paid", "paid"]
+    );
+
+    assert!("order@status"
+        .parse::<convert::AttributeCondition>()
+        .is_err());
+    assert!("@status=paid"
+        .parse::<convert::AttributeCondition>()
+        .is_err());
+}
+
+#[test]
+fn test_source_offsets() {
+    let source = fs::read_to_string("tests/resources/json_leaves.xml").unwrap();
+    let g = convert_to_graph(
+        "tests/resources/json_leaves.xml",
+        &convert::ConversionOptions {
+            source_offsets: true,
+            ..Default::default()
+        },
+    );
+    let offset = |element: oxrdf::SubjectRef, predicate| -> usize {
+        match g.object_for_subject_predicate(element, predicate) {
+            Some(TermRef::Literal(l)) => l.value().parse().unwrap(),
+            other => panic!("missing offset: {other:?}"),
+        }
+    };
+    let mut spans: Vec<&str> = g
+        .triples_for_predicate(vocab::START_OFFSET)
+        .map(|t| {
+            let element = t.subject;
+            &source[offset(element, vocab::START_OFFSET)..offset(element, vocab::END_OFFSET)]
+        })
+        .collect();
+    spans.sort();
+    assert_eq!(
+        spans,
+        vec![
+            "<catalog currency=\"EUR\">\n    <price currency=\"USD\">9.99</price>\n    <label lang=\"en\">Say \"hi\"</label>\n    <discontinued/>\n</catalog>",
+            "<discontinued/>",
+            "<label lang=\"en\">Say \"hi\"</label>",
+            "<price currency=\"USD\">9.99</price>",
+        ]
+    );
+}
+
+#[test]
+fn test_binary_content() {
+    use oxrdf::vocab::xsd;
+
+    let options = convert::ConversionOptions {
+        binary: [
+            ("signature".to_string(), datatype::BinaryEncoding::Base64),
+            (
+                "envelope.signature.-digest".to_string(),
+                datatype::BinaryEncoding::Hex,
+            ),
+        ]
+        .into(),
+        ..Default::default()
+    };
+    let g = convert_to_graph("tests/resources/binary.xml", &options);
+    let mut typed: Vec<(String, String)> = g
+        .triples_for_predicate(HAS_VALUE)
+        .filter_map(|t| match t.object {
+            TermRef::Literal(l) => Some((l.value().to_string(), l.datatype().to_string())),
+            _ => None,
+        })
+        .collect();
+    typed.sort();
+    assert_eq!(
+        typed,
+        vec![
+            ("3f2a9c".to_string(), xsd::HEX_BINARY.to_string()),
+            (
+                "U2lnbmVkIGJ5IERlY2lTeW0=".to_string(),
+                xsd::BASE_64_BINARY.to_string()
+            ),
+            ("plain text".to_string(), xsd::STRING.to_string()),
+        ]
+    );
+
+    let mut w = writer::StringWriter::new();
+    let err = convert::parse_xml_with_options(
+        vec!["tests/resources/binary_invalid.xml".to_string()],
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &options,
+    )
+    .expect_err("invalid base64 should be rejected");
+    assert!(matches!(
+        err,
+        error::ConvertError::InvalidBinary {
+            encoding: datatype::BinaryEncoding::Base64,
+            ..
+        }
+    ));
+    assert!(err.to_string().contains("'envelope.signature'"));
+}
+
+#[test]
+fn test_triple_stream() {
+    let options = convert::ConversionOptions::default();
+    let file = File::open("tests/resources/people.xml").unwrap();
+    let triples = convert::TripleStream::new(file, "https://decisym.ai/xml2rdf/data", &options)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .expect("conversion failed");
+
+    let g = convert_to_graph("tests/resources/people.xml", &options);
+    assert_eq!(triples.len(), g.len());
+
+    // The triples spanning the document come last, as from the writer entry points
+    let options = convert::ConversionOptions {
+        node_ids: convert::NodeIds::Deterministic {
+            hash: convert::IdHash::default(),
+        },
+        document_node: true,
+        record_options: true,
+        sample_per_path: true,
+        sample_counts: true,
+        instance_counts: true,
+        ..Default::default()
+    };
+    let xml = fs::read("tests/resources/people.xml").unwrap();
+    let streamed: Graph =
+        convert::TripleStream::new(xml.as_slice(), "https://decisym.ai/xml2rdf/data", &options)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .expect("conversion failed");
+    let mut written = Graph::new();
+    convert::parse_xml_reader(
+        xml.as_slice(),
+        &mut writer::GraphWriter::new(&mut written),
+        "https://decisym.ai/xml2rdf/data",
+        &options,
+    )
+    .expect("conversion failed");
+    // Sample occurrence counts
+    assert!(streamed
+        .triples_for_predicate(vocab::OCCURRENCES)
+        .next()
+        .is_some());
+    // Instance counts
+    assert!(streamed
+        .triples_for_predicate(vocab::INSTANCE_COUNT)
+        .next()
+        .is_some());
+    // The run node the document links to
+    let run = streamed
+        .subject_for_predicate_object(rdf::TYPE, vocab::CONVERSION_RUN)
+        .expect("no run node");
+    assert!(streamed
+        .subjects_for_predicate_object(vocab::CONVERTED_BY, run)
+        .next()
+        .is_some());
+    assert_eq!(streamed, written);
+}
+
+#[test]
+fn test_dedup_existing() {
+    let output = std::env::temp_dir().join("xml2rdf_dedup_existing.nt");
+    let _ = fs::remove_file(&output);
+
+    for _ in 0..2 {
+        let w = writer::FileWriter::to_file(output.to_string_lossy().into_owned()).unwrap();
+        let mut w = writer::DedupWriter::with_existing(w, &output).unwrap();
+        convert::parse_xml(
+            vec!["tests/resources/attributes.xml".to_string()],
+            &mut w,
+            "https://decisym.ai/xml2rdf/data",
+        )
+        .expect("conversion failed");
+    }
+
+    let content = fs::read_to_string(&output).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    let distinct: std::collections::HashSet<&str> = lines.iter().copied().collect();
+    assert_eq!(lines.len(), distinct.len());
+
+    // Both runs' elements are there, but the attribute classes only once
+    let class_triples = lines
+        .iter()
+        .filter(|l| l.ends_with("<https://decisym.ai/xml2rdf/model#XmlAttribute> ."))
+        .count();
+    assert_eq!(class_triples, 3);
+    let items = lines
+        .iter()
+        .filter(|l| l.contains("hasName") && l.contains("\"item\""))
+        .count();
+    assert_eq!(items, 4);
+}
+
+#[test]
+fn test_mixed_content() {
+    let xml = "<p>a <b>b</b> c</p>";
+    let mut g = Graph::new();
+    let mut w = writer::GraphWriter::new(&mut g);
+    convert::parse_xml_reader(
+        xml.as_bytes(),
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            branch_text: convert::BranchTextPolicy::Mixed,
+            ..Default::default()
+        },
+    )
+    .expect("conversion failed");
+
+    // Rebuilds an element from its name and its content list, or its text for leaves
+    fn reconstruct(g: &Graph, element: oxrdf::SubjectRef) -> String {
+        let name = match g.object_for_subject_predicate(element, HAS_NAME) {
+            Some(TermRef::Literal(l)) => l.value().to_string(),
+            _ => panic!("element without name"),
+        };
+        let mut inner = String::new();
+        match g.object_for_subject_predicate(element, vocab::HAS_CONTENT) {
+            Some(mut cell) => {
+                while let Some(item) =
+                    subject(cell).and_then(|c| g.object_for_subject_predicate(c, rdf::FIRST))
+                {
+                    match item {
+                        TermRef::Literal(l) => inner.push_str(l.value()),
+                        node => inner.push_str(&reconstruct(g, subject(node).unwrap())),
+                    }
+                    cell = g
+                        .object_for_subject_predicate(subject(cell).unwrap(), rdf::REST)
+                        .unwrap();
+                }
+            }
+            None => {
+                if let Some(TermRef::Literal(l)) =
+                    g.object_for_subject_predicate(element, HAS_VALUE)
+                {
+                    inner.push_str(l.value());
+                }
+            }
+        }
+        format!("<{name}>{inner}</{name}>")
+    }
+    fn subject(term: TermRef) -> Option<oxrdf::SubjectRef> {
+        match term {
+            TermRef::NamedNode(n) => Some(n.into()),
+            TermRef::BlankNode(b) => Some(b.into()),
+            _ => None,
+        }
+    }
+
+    let root = g
+        .subjects_for_predicate_object(HAS_NAME, oxrdf::LiteralRef::new_simple_literal("p"))
+        .next()
+        .unwrap();
+    assert_eq!(reconstruct(&g, root), xml);
+}
+
+#[test]
+fn test_rotating_file_writer() {
+    let mut w = writer::StringWriter::new();
+    let total = convert::parse_xml(
+        vec!["tests/resources/attributes.xml".to_string()],
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+    )
+    .expect("conversion failed")
+    .triples;
+
+    let dir = std::env::temp_dir().join(format!("xml2rdf_rotating_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let output = dir.join("out.nt");
+    let mut w = writer::RotatingFileWriter::new(&output, total.div_ceil(2));
+    convert::parse_xml(
+        vec!["tests/resources/attributes.xml".to_string()],
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+    )
+    .expect("conversion failed");
+
+    let mut sizes = Vec::new();
+    for index in 0..2 {
+        let chunk = w.chunk_path(index);
+        let triples = RdfParser::from_format(RdfFormat::NTriples)
+            .for_reader(File::open(&chunk).unwrap())
+            .collect::<Result<Vec<_>, _>>()
+            .expect("failed to parse chunk");
+        sizes.push(triples.len());
+        fs::remove_file(chunk).unwrap();
+    }
+    assert_eq!(w.chunk_path(1), dir.join("out.1.nt"));
+    assert!(!w.chunk_path(2).exists());
+    fs::remove_dir(dir).unwrap();
+    assert_eq!(sizes, vec![total.div_ceil(2), total / 2]);
+}
+
+#[test]
+fn test_sort_attributes() {
+    let xml = r#"<item xmlns:b="urn:b" xmlns:a="urn:a" zeta="1" b:x="2" alpha="3" a:y="4"/>"#;
+    let nt = convert::convert_bytes(
+        xml.as_bytes(),
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            sort_attributes: true,
+            ..Default::default()
+        },
+    )
+    .expect("conversion failed");
+    let values: Vec<&str> = nt
+        .lines()
+        .filter(|l| l.contains("#hasValue>"))
+        .map(|l| l.rsplit(' ').nth(1).unwrap())
+        .collect();
+    // Unqualified names first, then by namespace URI regardless of prefix
+    assert_eq!(values, vec!["\"3\"", "\"1\"", "\"4\"", "\"2\""]);
+}
+
+#[test]
+fn test_link_document() {
+    let mut g = Graph::new();
+    let mut w = writer::GraphWriter::new(&mut g);
+    convert::parse_xml_with_options(
+        vec![
+            "tests/resources/people_a.xml".to_string(),
+            "tests/resources/orders.xml".to_string(),
+        ],
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            document_node: true,
+            link_document: true,
+            ..Default::default()
+        },
+    )
+    .expect("conversion failed");
+
+    let orders = g
+        .subjects_for_predicate_object(
+            vocab::SOURCE_FILE,
+            oxrdf::LiteralRef::new_simple_literal(&format!(
+                "file://{}",
+                fs::canonicalize("tests/resources/orders.xml")
+                    .unwrap()
+                    .display()
+            )),
+        )
+        .next()
+        .expect("missing document node");
+    let nested = NamedNodeRef::new("https://decisym.ai/xml2rdf/model#orders.order.order").unwrap();
+    let nested = g
+        .subjects_for_predicate_object(rdf::TYPE, nested)
+        .next()
+        .expect("missing nested order");
+    let documents: Vec<_> = g
+        .objects_for_subject_predicate(nested, vocab::IN_DOCUMENT)
+        .collect();
+    assert_eq!(documents, vec![TermRef::from(orders)]);
+
+    // Every element and attribute node carries exactly one link
+    let nodes =
+        g.triples_for_predicate(HAS_NAME).count() + g.triples_for_predicate(HAS_ATTRIBUTE).count();
+    assert_eq!(g.triples_for_predicate(vocab::IN_DOCUMENT).count(), nodes);
+}
+
+#[test]
+fn test_namespaced_attributes() {
+    let g = convert_to_graph(
+        "tests/resources/namespaced_attributes.xml",
+        &convert::ConversionOptions {
+            preserve_namespaces: true,
+            ..Default::default()
+        },
+    );
+    let mut classes: Vec<String> = g
+        .triples_for_predicate(HAS_ATTRIBUTE)
+        .filter_map(|t| match t.object {
+            TermRef::NamedNode(n) => g.object_for_subject_predicate(n, rdf::TYPE),
+            _ => None,
+        })
+        .map(|class| class.to_string())
+        .collect();
+    classes.sort();
+    let link = "https://decisym.ai/xml2rdf/model#%7Burn%3Aexample%3Alinks%7Dlinks.%7Burn%3Aexample%3Alinks%7Dlink";
+    assert_eq!(
+        classes,
+        vec![
+            format!("<{link}.-%7Bhttp%3A%2F%2Fwww%2Ew3%2Eorg%2F1999%2Fxhtml%7Dhref>"),
+            format!("<{link}.-%7Bhttp%3A%2F%2Fwww%2Ew3%2Eorg%2F1999%2Fxlink%7Dhref>"),
+            // Unprefixed attributes are in no namespace, not the default one
+            format!("<{link}.-href>"),
+        ]
+    );
+}
+
+#[test]
+fn test_inverse_links() {
+    let g = convert_to_graph(
+        "tests/resources/attributes.xml",
+        &convert::ConversionOptions {
+            inverse_links: Some(convert::InverseLinks::Materialized),
+            ..Default::default()
+        },
+    );
+    let mut children: Vec<_> = g
+        .triples_for_predicate(vocab::HAS_CHILD)
+        .map(|t| (t.subject.to_string(), t.object.to_string()))
+        .collect();
+    let mut parents: Vec<_> = g
+        .triples_for_predicate(vocab::HAS_PARENT)
+        .map(|t| (t.object.to_string(), t.subject.to_string()))
+        .collect();
+    children.sort();
+    parents.sort();
+    assert!(!children.is_empty());
+    assert_eq!(parents, children);
+    assert_eq!(
+        g.triples_for_predicate(vocab::ATTRIBUTE_OF).count(),
+        g.triples_for_predicate(HAS_ATTRIBUTE).count()
+    );
+
+    let g = convert_to_graph(
+        "tests/resources/attributes.xml",
+        &convert::ConversionOptions {
+            inverse_links: Some(convert::InverseLinks::Declared),
+            ..Default::default()
+        },
+    );
+    assert_eq!(g.triples_for_predicate(vocab::HAS_PARENT).count(), 0);
+    assert!(g.contains(oxrdf::TripleRef::new(
+        vocab::HAS_PARENT,
+        vocab::OWL_INVERSE_OF,
+        vocab::HAS_CHILD
+    )));
+}
+
+#[cfg(feature = "oxigraph")]
+#[test]
+fn test_query_xml() {
+    let solutions = query::query_xml(
+        vec!["tests/resources/people.xml".to_string()],
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions::default(),
+        "PREFIX x2r: <https://decisym.ai/xml2rdf/model#>
+         SELECT ?first ?last WHERE {
+             ?person x2r:hasName \"Person\" ;
+                 x2r:hasChild [ x2r:hasName \"firstName\" ; x2r:hasValue ?first ] ;
+                 x2r:hasChild [ x2r:hasName \"lastName\" ; x2r:hasValue ?last ] .
+         } ORDER BY ?first",
+    )
+    .expect("query failed");
+
+    let mut csv = Vec::new();
+    solutions
+        .write(&mut csv, query::ResultFormat::Csv)
+        .expect("failed to write results");
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "first,last\r\n\
+         Avery,Dimuccio\r\n\
+         Derick,Lessner\r\n\
+         Eunice,Schrantz\r\n\
+         Jeffrey,Schill\r\n\
+         Lucinda,Ewan\r\n"
+    );
+}
+
+#[test]
+fn test_invalid_utf8() {
+    let g = convert_to_graph(
+        "tests/resources/invalid_utf8.xml",
+        &convert::ConversionOptions {
+            invalid_utf8: convert::Utf8Policy::Lossy,
+            ..Default::default()
+        },
+    );
+    let mut values: Vec<String> = g
+        .triples_for_predicate(HAS_VALUE)
+        .filter_map(|t| match t.object {
+            TermRef::Literal(l) => Some(l.value().to_string()),
+            _ => None,
+        })
+        .collect();
+    values.sort();
+    assert_eq!(values, vec!["caf\u{FFFD} au lait", "tea"]);
+
+    let mut w = writer::StringWriter::new();
+    let result = convert::parse_xml(
+        vec!["tests/resources/invalid_utf8.xml".to_string()],
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+    );
+    assert!(matches!(
+        result,
+        Err(error::ConvertError::InvalidUtf8 {
+            source: Some(_),
+            offset: 21
+        })
+    ));
+}
+
+#[test]
+fn test_collapse_wrappers() {
+    let name_count = |g: &Graph, name: &str| {
+        g.subjects_for_predicate_object(HAS_NAME, oxrdf::LiteralRef::new_simple_literal(name))
+            .count()
+    };
+    let value_of = |g: &Graph, name: &str| {
+        let node = g
+            .subject_for_predicate_object(HAS_NAME, oxrdf::LiteralRef::new_simple_literal(name))
+            .expect("missing element");
+        g.object_for_subject_predicate(node, HAS_VALUE)
+            .map(|v| v.to_string())
+    };
+
+    let g = convert_to_graph(
+        "tests/resources/wrappers.xml",
+        &convert::ConversionOptions::default(),
+    );
+    assert_eq!(name_count(&g, "value"), 5);
+    assert_eq!(value_of(&g, "name"), None);
+
+    let g = convert_to_graph(
+        "tests/resources/wrappers.xml",
+        &convert::ConversionOptions {
+            collapse_wrappers: true,
+            ..Default::default()
+        },
+    );
+    // <name><value>Alice</value></name> becomes name hasValue "Alice"
+    assert_eq!(value_of(&g, "name").as_deref(), Some("\"Alice\""));
+    // The wrapper keeps its own attributes
+    assert_eq!(value_of(&g, "age").as_deref(), Some("\"42\""));
+    assert_eq!(g.triples_for_predicate(HAS_ATTRIBUTE).count(), 2);
+    // A child with attributes, or one of several children, is kept
+    assert_eq!(name_count(&g, "value"), 3);
+    assert_eq!(value_of(&g, "email"), None);
+}
+
+#[cfg(feature = "digest")]
+#[test]
+fn test_source_digest() {
+    use sha2::{Digest, Sha256};
+
+    let file = "tests/resources/people.xml";
+    let g = convert_to_graph(
+        file,
+        &convert::ConversionOptions {
+            document_node: true,
+            digest: Some(digest::DigestAlgorithm::Sha256),
+            ..Default::default()
+        },
+    );
+    // people.xml is not well-formed, so the digest also covers the bytes never parsed
+    let expected = format!("{:x}", Sha256::digest(fs::read(file).unwrap()));
+    let digests: Vec<_> = g.triples_for_predicate(vocab::SHA256).collect();
+    assert_eq!(digests.len(), 1);
+    assert_eq!(
+        digests[0].object,
+        TermRef::from(oxrdf::LiteralRef::new_simple_literal(&expected))
+    );
+}
+
+#[test]
+fn test_namespace_overrides() {
+    let data = "https://decisym.ai/xml2rdf/data";
+    let partner = "https://partner.example/data";
+    let internal = "https://decisym.ai/xml2rdf/internal";
+    let g = convert_to_graph(
+        "tests/resources/partner.xml",
+        &convert::ConversionOptions {
+            namespace_overrides: [
+                ("external".to_string(), partner.to_string()),
+                (
+                    "catalog.external.internal".to_string(),
+                    internal.to_string(),
+                ),
+            ]
+            .into(),
+            ..Default::default()
+        },
+    );
+
+    // Element names and attributes with the namespace their node was minted under
+    let namespace_of = |node: String| {
+        [data, partner, internal]
+            .into_iter()
+            .find(|ns| node.starts_with(&format!("<{ns}/")))
+            .expect("node minted outside the expected namespaces")
+    };
+    let mut minted: Vec<(String, &str)> = g
+        .triples_for_predicate(HAS_NAME)
+        .map(|t| (t.object.to_string(), namespace_of(t.subject.to_string())))
+        .chain(
+            g.triples_for_predicate(HAS_ATTRIBUTE)
+                .map(|t| ("@sku".to_string(), namespace_of(t.object.to_string()))),
+        )
+        .collect();
+    minted.sort();
+    let expected = [
+        ("\"catalog\"", data),
+        ("\"external\"", partner),
+        ("\"internal\"", internal),
+        ("\"item\"", data),
+        ("\"item\"", partner),
+        ("\"name\"", data),
+        ("\"name\"", partner),
+        ("\"note\"", internal),
+        ("@sku", data),
+        ("@sku", partner),
+    ];
+    let expected: Vec<(String, &str)> = expected
+        .into_iter()
+        .map(|(name, ns)| (name.to_string(), ns))
+        .collect();
+    assert_eq!(minted, expected);
+}
+
+#[test]
+fn test_max_input_bytes() {
+    let xml = fs::read("tests/resources/people.xml").expect("failed to read fixture");
+    let convert = |max_input_bytes| {
+        let mut g = Graph::new();
+        let mut w = writer::GraphWriter::new(&mut g);
+        let result = convert::parse_xml_reader(
+            xml.as_slice(),
+            &mut w,
+            "https://decisym.ai/xml2rdf/data",
+            &convert::ConversionOptions {
+                max_input_bytes: Some(max_input_bytes),
+                ..Default::default()
+            },
+        );
+        (result, g)
+    };
+
+    let (result, g) = convert(500);
+    assert!(matches!(
+        result,
+        Err(error::ConvertError::InputTooLarge {
+            source: None,
+            limit: 500
+        })
+    ));
+    // The first person was converted before the limit was hit
+    assert!(g
+        .subjects_for_predicate_object(HAS_NAME, oxrdf::LiteralRef::new_simple_literal("firstName"))
+        .any(|first_name| g.contains(oxrdf::TripleRef::new(
+            first_name,
+            HAS_VALUE,
+            oxrdf::LiteralRef::new_simple_literal("Avery"),
+        ))));
+
+    let (result, g) = convert(xml.len() as u64);
+    assert!(result.is_ok());
+    assert_eq!(g.len(), 273);
+}
+
+#[test]
+fn test_sample_per_path() {
+    let mut xml = String::from("<people>");
+    for i in 0..1000 {
+        xml.push_str(&format!(
+            "<person id=\"{i}\"><name>P{i}</name><phone>1</phone><phone>2</phone></person>"
+        ));
+    }
+    xml.push_str("</people>");
+
+    let convert = |sample_counts| {
+        let mut g = Graph::new();
+        let mut w = writer::GraphWriter::new(&mut g);
+        convert::parse_xml_reader(
+            xml.as_bytes(),
+            &mut w,
+            "https://decisym.ai/xml2rdf/data",
+            &convert::ConversionOptions {
+                sample_per_path: true,
+                sample_counts,
+                ..Default::default()
+            },
+        )
+        .expect("conversion failed");
+        g
+    };
+    let named = |g: &Graph, name: &str| -> Vec<oxrdf::Subject> {
+        g.subjects_for_predicate_object(HAS_NAME, oxrdf::LiteralRef::new_simple_literal(name))
+            .map(|s| s.into_owned())
+            .collect()
+    };
+
+    let g = convert(false);
+    for name in ["people", "person", "name", "phone"] {
+        assert_eq!(named(&g, name).len(), 1, "{name}");
+    }
+    // The sample is the first person, with its attributes
+    let person = &named(&g, "person")[0];
+    let attribute = g
+        .object_for_subject_predicate(person, HAS_ATTRIBUTE)
+        .expect("missing attribute");
+    let TermRef::NamedNode(attribute) = attribute else {
+        panic!("unexpected attribute node {attribute}");
+    };
+    assert_eq!(
+        g.object_for_subject_predicate(attribute, HAS_VALUE),
+        Some(oxrdf::LiteralRef::new_simple_literal("0").into())
+    );
+    assert!(g.triples_for_predicate(vocab::OCCURRENCES).next().is_none());
+
+    let g = convert(true);
+    let occurrences = |name: &str| {
+        let sample = &named(&g, name)[0];
+        match g.object_for_subject_predicate(sample, vocab::OCCURRENCES) {
+            Some(TermRef::Literal(count)) => count.value().to_string(),
+            other => panic!("unexpected count {other:?}"),
+        }
+    };
+    assert_eq!(occurrences("people"), "1");
+    assert_eq!(occurrences("person"), "1000");
+    // Phones below the skipped persons are not counted
+    assert_eq!(occurrences("phone"), "2");
+}
+
+#[cfg(all(unix, feature = "ctrlc"))]
+#[test]
+fn test_sigint_flushes_output() {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+    use std::time::{Duration, Instant};
+
+    // Large enough that the conversion is still running when interrupted
+    let input = std::env::temp_dir().join("xml2rdf_sigint.xml");
+    let output = std::env::temp_dir().join("xml2rdf_sigint.nt");
+    let _ = fs::remove_file(&output);
+    let mut xml = std::io::BufWriter::new(File::create(&input).unwrap());
+    writeln!(xml, "<people>").unwrap();
+    for i in 0..300_000 {
+        writeln!(xml, "<person id=\"{i}\"><name>P{i}</name></person>").unwrap();
+    }
+    writeln!(xml, "</people>").unwrap();
+    xml.flush().unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_xml2rdf"))
+        .args(["convert", "--xml"])
+        .arg(&input)
+        .arg("--output-file")
+        .arg(&output)
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("failed to start xml2rdf");
+
+    let started = Instant::now();
+    while fs::metadata(&output).map_or(true, |m| m.len() == 0) {
+        assert!(started.elapsed() < Duration::from_secs(30), "no output");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    let killed = Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .expect("failed to run kill");
+    assert!(killed.success());
+    let status = child.wait().unwrap();
+    assert_eq!(status.code(), Some(130));
+
+    // The partial output ends with a complete triple and parses cleanly
+    let content = fs::read_to_string(&output).unwrap();
+    assert!(content.ends_with(" .\n"));
+    let triples = RdfParser::from_format(RdfFormat::NTriples)
+        .for_reader(content.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .expect("partial output does not parse");
+    assert_eq!(triples.len(), content.lines().count());
+    assert!(triples.len() < 300_000 * 8);
+}
+
+#[cfg(all(unix, feature = "syslog"))]
+#[test]
+fn test_log_target_syslog() {
+    use std::os::unix::net::UnixDatagram;
+    use std::process::{Command, Stdio};
+    use std::time::Duration;
+
+    let socket_path = std::env::temp_dir().join(format!("xml2rdf_syslog_{}", std::process::id()));
+    let _ = fs::remove_file(&socket_path);
+    let socket = UnixDatagram::bind(&socket_path).unwrap();
+    socket
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+
+    let child = Command::new(env!("CARGO_BIN_EXE_xml2rdf"))
+        .args([
+            "convert",
+            "--xml",
+            "tests/resources/people.xml",
+            "--warn-empty",
+        ])
+        .args([
+            "--xml",
+            "tests/resources/empty.xml",
+            "--log-target",
+            "syslog",
+        ])
+        .arg("--syslog-socket")
+        .arg(&socket_path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to start xml2rdf");
+    let tag = format!("xml2rdf[{}]: ", child.id());
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    // Nothing is reported on stderr while syslog is reachable
+    assert!(output.stderr.is_empty());
+
+    let mut records = Vec::new();
+    let mut buf = [0; 4096];
+    while let Ok(n) = socket.recv(&mut buf) {
+        let record = String::from_utf8(buf[..n].to_vec()).unwrap();
+        let converted = record.contains(": converted ");
+        records.push(record);
+        if converted {
+            break;
+        }
+    }
+    fs::remove_file(&socket_path).unwrap();
+
+    assert!(records.iter().all(|r| r.contains(&tag)));
+    // user.info progress and user.warning warnings, with structured fields
+    assert!(records[0].starts_with("<14>"));
+    assert!(records[0].ends_with(r#": converting file="tests/resources/people.xml""#));
+    assert!(records[1].ends_with(r#": converting file="tests/resources/empty.xml""#));
+    assert!(records
+        .iter()
+        .any(|r| r.starts_with("<12>") && r.ends_with(r#"file="tests/resources/empty.xml""#)));
+    assert!(records
+        .last()
+        .unwrap()
+        .ends_with(r#": converted triples="273""#));
+}
+
+#[test]
+fn test_embedded_json() {
+    let xml = r#"<record id="7"><data>{"a": 1, "b": {"c": "x", "d": [true, null]}}</data><note>{not json}</note></record>"#;
+    let convert = |mode| {
+        let mut g = Graph::new();
+        let mut w = writer::GraphWriter::new(&mut g);
+        convert::parse_xml_reader(
+            xml.as_bytes(),
+            &mut w,
+            "https://decisym.ai/xml2rdf/data",
+            &convert::ConversionOptions {
+                embedded_json: [
+                    ("record.data".to_string(), mode),
+                    ("note".to_string(), mode),
+                ]
+                .into(),
+                ..Default::default()
+            },
+        )
+        .expect("conversion failed");
+        g
+    };
+    let named = |g: &Graph, name: &str| -> Vec<oxrdf::Subject> {
+        g.subjects_for_predicate_object(HAS_NAME, oxrdf::LiteralRef::new_simple_literal(name))
+            .map(|s| s.into_owned())
+            .collect()
+    };
+    let value = |g: &Graph, node: &oxrdf::Subject| {
+        g.object_for_subject_predicate(node, HAS_VALUE)
+            .map(|v| v.to_string())
+    };
+
+    let g = convert(convert::EmbeddedJson::Expand);
+    let data = &named(&g, "data")[0];
+    assert_eq!(value(&g, data), None);
+    let a = &named(&g, "a")[0];
+    let b = &named(&g, "b")[0];
+    let c = &named(&g, "c")[0];
+    for (parent, child) in [(data, a), (data, b), (b, c)] {
+        assert!(g.contains(oxrdf::TripleRef::new(parent, vocab::HAS_CHILD, child)));
+    }
+    assert_eq!(
+        value(&g, a).as_deref(),
+        Some("\"1\"^^<http://www.w3.org/2001/XMLSchema#integer>")
+    );
+    assert_eq!(value(&g, c).as_deref(), Some("\"x\""));
+    // The array becomes repeated members, the null one without a value
+    let d = named(&g, "d");
+    assert_eq!(d.len(), 2);
+    let mut values: Vec<Option<String>> = d.iter().map(|d| value(&g, d)).collect();
+    values.sort();
+    assert_eq!(
+        values,
+        vec![
+            None,
+            Some("\"true\"^^<http://www.w3.org/2001/XMLSchema#boolean>".to_string())
+        ]
+    );
+    let Some(TermRef::NamedNode(class)) = g.object_for_subject_predicate(c, rdf::TYPE) else {
+        panic!("missing class");
+    };
+    assert_eq!(
+        class.as_str(),
+        "https://decisym.ai/xml2rdf/model#record.data.b.c"
+    );
+    assert!(g.contains(oxrdf::TripleRef::new(
+        class,
+        oxrdf::vocab::rdfs::SUB_CLASS_OF,
+        vocab::JSON_VALUE
+    )));
+    // Attributes and text that is no JSON are converted as usual
+    assert_eq!(
+        g.triples_for_predicate(HAS_ATTRIBUTE).count(),
+        1,
+        "the record's id attribute"
+    );
+    let note = &named(&g, "note")[0];
+    assert_eq!(value(&g, note).as_deref(), Some("\"{not json}\""));
+
+    let g = convert(convert::EmbeddedJson::Literal);
+    let data = &named(&g, "data")[0];
+    assert_eq!(
+        value(&g, data).as_deref(),
+        Some(
+            r#""{\"a\": 1, \"b\": {\"c\": \"x\", \"d\": [true, null]}}"^^<http://www.w3.org/1999/02/22-rdf-syntax-ns#JSON>"#
+        )
+    );
+    assert!(named(&g, "a").is_empty());
+
+    // Each class of a JSON value is declared once, even for the two members of the array
+    let mut out = Vec::new();
+    convert::parse_xml_reader(
+        xml.as_bytes(),
+        &mut writer::FileWriter::new(&mut out),
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            embedded_json: [("data".to_string(), convert::EmbeddedJson::Expand)].into(),
+            ..Default::default()
+        },
+    )
+    .expect("conversion failed");
+    let out = String::from_utf8(out).unwrap();
+    let declared = format!(
+        "> <{}> <{}> .",
+        oxrdf::vocab::rdfs::SUB_CLASS_OF.as_str(),
+        vocab::JSON_VALUE.as_str()
+    );
+    assert_eq!(out.lines().filter(|l| l.ends_with(&declared)).count(), 4);
+}
+
+/// A sink that fails its first flush, like a disk that was briefly full.
+#[derive(Default)]
+struct FlakySink {
+    flushes: usize,
+}
+
+impl std::io::Write for FlakySink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.flushes += 1;
+        if self.flushes == 1 {
+            Err(std::io::Error::other("no space left on device"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[test]
+fn test_writer_errors_propagate() {
+    let mut w = writer::FileWriter::new(FlakySink::default());
+    let result = convert::parse_xml(
+        vec!["tests/resources/attributes.xml".to_string()],
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+    );
+    assert!(matches!(result, Err(error::ConvertError::Io(_))));
+}
+
+#[test]
+fn test_triple_order() {
+    let mut w = writer::StringWriter::new();
+    convert::parse_xml_reader(
+        r#"<a y="2" x="1"><b>t</b>u</a>"#.as_bytes(),
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            node_ids: convert::NodeIds::Deterministic {
+                hash: convert::IdHash::default(),
+            },
+            sort_attributes: true,
+            ..Default::default()
+        },
+    )
+    .expect("conversion failed");
+    let output = w
+        .into_string()
+        .replace("https://decisym.ai/xml2rdf/", "")
+        .replace("http://www.w3.org/1999/02/22-rdf-syntax-ns#", "rdf:")
+        .replace("http://www.w3.org/2000/01/rdf-schema#", "rdfs:");
+    let a = "<data/93500990-f9e5-5639-84b5-11ce2115b17f>";
+    let x = "<data/97445cd8-6b60-5733-b3ce-d4a110d69176>";
+    let y = "<data/3f3d07c2-6e97-5804-bb4f-f651bd6f4512>";
+    let b = "<data/19ea8d07-01d8-574a-af1c-8d96a34579dc>";
+    let expected = [
+        // The element, then its attributes in name order, then its child, then its text
+        format!("{a} <rdf:type> <model#a> ."),
+        format!("{a} <model#hasName> \"a\" ."),
+        format!("{a} <rdfs:subClassOf> <model#XmlNode> ."),
+        format!("{a} <model#hasAttribute> {x} ."),
+        format!("{x} <rdf:type> <model#a.-x> ."),
+        "<model#a.-x> <rdfs:subClassOf> <model#XmlAttribute> .".to_string(),
+        format!("{x} <model#hasValue> \"1\" ."),
+        format!("{a} <model#hasAttribute> {y} ."),
+        format!("{y} <rdf:type> <model#a.-y> ."),
+        "<model#a.-y> <rdfs:subClassOf> <model#XmlAttribute> .".to_string(),
+        format!("{y} <model#hasValue> \"2\" ."),
+        format!("{a} <model#hasChild> {b} ."),
+        format!("{b} <rdf:type> <model#a.b> ."),
+        format!("{b} <model#hasName> \"b\" ."),
+        format!("{b} <rdfs:subClassOf> <model#XmlNode> ."),
+        format!("{b} <model#hasValue> \"t\" ."),
+        format!("{a} <model#hasValue> \"u\" ."),
+    ];
+    assert_eq!(output.lines().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn test_type_and_class_hierarchy_toggles() {
+    let xml = fs::read("tests/resources/attributes.xml").expect("failed to read fixture");
+    let triples = |type_triples, class_hierarchy| {
+        let mut w = writer::StringWriter::new();
+        convert::parse_xml_reader(
+            xml.as_slice(),
+            &mut w,
+            "https://decisym.ai/xml2rdf/data",
+            &convert::ConversionOptions {
+                type_triples,
+                class_hierarchy,
+                ..Default::default()
+            },
+        )
+        .expect("conversion failed");
+        w.into_string()
+    };
+
+    // Three elements and four attributes, each typed and placed in the hierarchy once
+    let all = triples(true, true);
+    assert_eq!(all.lines().count(), 28);
+
+    let untyped = triples(false, true);
+    assert_eq!(untyped.lines().count(), 21);
+    assert!(!untyped.contains("22-rdf-syntax-ns#type"));
+    assert!(untyped.contains("model#XmlAttribute"));
+
+    let flat = triples(true, false);
+    assert_eq!(flat.lines().count(), 21);
+    assert!(!flat.contains("rdf-schema#subClassOf"));
+    assert!(flat.contains("<https://decisym.ai/xml2rdf/model#catalog.item.-id>"));
+
+    let neither = triples(false, false);
+    assert_eq!(neither.lines().count(), 14);
+    assert!(!neither.contains("https://decisym.ai/xml2rdf/model#catalog"));
+}
+
+#[test]
+fn test_names_round_trip() {
+    // A namespace with a space, a non-ASCII name, and a JSON key with a space
+    let xml = r#"<x:record xmlns:x="urn:a b"><café>{"first name": "Ada"}</café></x:record>"#;
+    let mut g = Graph::new();
+    let mut w = writer::GraphWriter::new(&mut g);
+    convert::parse_xml_reader(
+        xml.as_bytes(),
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            preserve_namespaces: true,
+            embedded_json: [("café".to_string(), convert::EmbeddedJson::Expand)].into(),
+            ..Default::default()
+        },
+    )
+    .expect("conversion failed");
+
+    let expected = [
+        ("record", "%7Burn%3Aa%20b%7Drecord"),
+        ("café", "%7Burn%3Aa%20b%7Drecord.café"),
+        ("first name", "%7Burn%3Aa%20b%7Drecord.café.first%20name"),
+    ];
+    // hasName holds each name as written, the class IRI its encoded form
+    for (name, class) in expected {
+        let node = g
+            .subject_for_predicate_object(HAS_NAME, oxrdf::LiteralRef::new_simple_literal(name))
+            .unwrap_or_else(|| panic!("no node named {name}"));
+        let class = format!("https://decisym.ai/xml2rdf/model#{class}");
+        assert_eq!(
+            g.object_for_subject_predicate(node, rdf::TYPE),
+            Some(NamedNodeRef::new_unchecked(&class).into())
+        );
+    }
+}
+
+#[test]
+fn test_empty_markers() {
+    let xml = r#"<r xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><a xsi:nil="true"/><a/><a>x</a></r>"#;
+    let convert = |empty_markers| {
+        let mut g = Graph::new();
+        let mut w = writer::GraphWriter::new(&mut g);
+        convert::parse_xml_reader(
+            xml.as_bytes(),
+            &mut w,
+            "https://decisym.ai/xml2rdf/data",
+            &convert::ConversionOptions {
+                empty_markers,
+                ..Default::default()
+            },
+        )
+        .expect("conversion failed");
+        g
+    };
+    let yes = oxrdf::Literal::from(true);
+
+    let g = convert(true);
+    let nil: Vec<_> = g
+        .subjects_for_predicate_object(vocab::IS_NIL, &yes)
+        .collect();
+    let empty: Vec<_> = g
+        .subjects_for_predicate_object(vocab::IS_EMPTY, &yes)
+        .collect();
+    assert_eq!(nil.len(), 1);
+    assert_eq!(empty.len(), 1);
+    assert_ne!(nil[0], empty[0]);
+    // The nil element is not marked empty too, and only <a/> is empty
+    for marked in [nil[0], empty[0]] {
+        assert!(g.contains(oxrdf::TripleRef::new(
+            marked,
+            HAS_NAME,
+            oxrdf::LiteralRef::new_simple_literal("a")
+        )));
+        assert!(g.object_for_subject_predicate(marked, HAS_VALUE).is_none());
+    }
+    assert!(g
+        .triples_for_subject(nil[0])
+        .any(|t| t.predicate == HAS_ATTRIBUTE));
+    assert!(!g
+        .triples_for_subject(empty[0])
+        .any(|t| t.predicate == HAS_ATTRIBUTE));
+
+    let g = convert(false);
+    assert!(g.triples_for_predicate(vocab::IS_NIL).next().is_none());
+    assert!(g.triples_for_predicate(vocab::IS_EMPTY).next().is_none());
+}
+
+#[test]
+fn test_max_elements() {
+    let xml = "<root><a>1</a><b><c>2</c><d>3</d></b><e>4</e></root>";
+    let mut g = Graph::new();
+    let mut w = writer::GraphWriter::new(&mut g);
+    convert::parse_xml_reader(
+        xml.as_bytes(),
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            max_elements: Some(4),
+            ..Default::default()
+        },
+    )
+    .expect("conversion failed");
+
+    // Conversion stops at <d>, with the open <b> and <root> closed as usual
+    let mut names: Vec<String> = g
+        .triples_for_predicate(HAS_NAME)
+        .filter_map(|t| match t.object {
+            TermRef::Literal(l) => Some(l.value().to_string()),
+            _ => None,
+        })
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["a", "b", "c", "root"]);
+    assert_eq!(literal_values(&g), vec!["1", "2"]);
+}
+
+#[test]
+fn test_prefix_file() {
+    use oxrdf::vocab::xsd;
+
+    let prefixes =
+        prefixes::PrefixMap::load("tests/resources/prefixes.ttl").expect("failed to read prefixes");
+    let mut options = convert::ConversionOptions {
+        datatypes: [(
+            "year".to_string(),
+            oxrdf::NamedNode::new("xsd:gYear").unwrap(),
+        )]
+        .into(),
+        ..Default::default()
+    };
+    prefixes.expand_options(&mut options);
+    assert_eq!(options.datatypes["year"], xsd::G_YEAR);
+
+    let mut w = writer::TurtleWriter::new(Vec::new(), &prefixes);
+    convert::parse_xml_reader(
+        "<book><year>1999</year></book>".as_bytes(),
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &options,
+    )
+    .expect("conversion failed");
+    let turtle = String::from_utf8(w.into_inner().unwrap()).unwrap();
+
+    assert!(turtle.contains("@prefix x2r: <https://decisym.ai/xml2rdf/model#> ."));
+    assert!(turtle.contains("@prefix xsd: <http://www.w3.org/2001/XMLSchema#> ."));
+    assert!(turtle.contains("x2r:hasValue"));
+    assert!(!turtle.contains("<https://decisym.ai/xml2rdf/model#hasValue>"));
+
+    // The same prefixes as a JSON object
+    let json = prefixes::PrefixMap::parse(
+        r#"{"x2r": "https://decisym.ai/xml2rdf/model#",
+            "data": "https://decisym.ai/xml2rdf/data/",
+            "xsd": "http://www.w3.org/2001/XMLSchema#"}"#,
+    )
+    .unwrap();
+    assert_eq!(json, prefixes);
+    assert!(prefixes::PrefixMap::parse("@prefix x2r <https://decisym.ai/> .").is_err());
+}
+
+#[test]
+fn test_conversion_time() {
+    use oxrdf::vocab::xsd;
+    use oxrdf::Literal;
+
+    let converted_at = |conversion_time| {
+        let g = convert_to_graph(
+            "tests/resources/people.xml",
+            &convert::ConversionOptions {
+                document_node: true,
+                conversion_time,
+                ..Default::default()
+            },
+        );
+        g.triples_for_predicate(vocab::CONVERTED_AT)
+            .map(|t| match t.object {
+                TermRef::Literal(l) => l.into_owned(),
+                other => panic!("unexpected conversion time {other}"),
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        converted_at(convert::ConversionTime::Fixed(
+            "2024-01-01T00:00:00Z".to_string()
+        )),
+        vec![Literal::new_typed_literal(
+            "2024-01-01T00:00:00Z",
+            xsd::DATE_TIME
+        )]
+    );
+    assert!(converted_at(convert::ConversionTime::Omit).is_empty());
+
+    let now = converted_at(convert::ConversionTime::Now);
+    assert_eq!(now.len(), 1);
+    assert_eq!(datatype::infer(now[0].value()), Some(xsd::DATE_TIME));
+}
+
+#[test]
+fn test_promote_attribute() {
+    let xml = r#"<refs><ref id="x1" kind="book"/><ref id="x2">text</ref><other id="x3"/></refs>"#;
+    let convert = |predicate: Option<oxrdf::NamedNode>| {
+        let mut g = Graph::new();
+        let mut w = writer::GraphWriter::new(&mut g);
+        convert::parse_xml_reader(
+            xml.as_bytes(),
+            &mut w,
+            "https://decisym.ai/xml2rdf/data",
+            &convert::ConversionOptions {
+                promoted_attributes: [(
+                    "ref".to_string(),
+                    convert::PromotedAttribute {
+                        attribute: "id".to_string(),
+                        predicate,
+                    },
+                )]
+                .into(),
+                ..Default::default()
+            },
+        )
+        .expect("conversion failed");
+        g
+    };
+    let attribute_values = |g: &Graph| {
+        let mut names: Vec<String> = g
+            .triples_for_predicate(HAS_ATTRIBUTE)
+            .filter_map(|t| match t.object {
+                TermRef::NamedNode(n) => Some(n),
+                _ => None,
+            })
+            .filter_map(
+                |attr| match g.object_for_subject_predicate(attr, HAS_VALUE) {
+                    Some(TermRef::Literal(l)) => Some(l.value().to_string()),
+                    _ => None,
+                },
+            )
+            .collect();
+        names.sort();
+        names
+    };
+
+    // The first ref's id becomes its value; the second keeps its text, and its id as a node
+    let g = convert(None);
+    assert_eq!(literal_values(&g), vec!["book", "text", "x1", "x2", "x3"]);
+    assert_eq!(attribute_values(&g), vec!["book", "x2", "x3"]);
+
+    // With its own predicate, the id is promoted alongside the text
+    let identifier = oxrdf::NamedNode::new("https://schema.org/identifier").unwrap();
+    let g = convert(Some(identifier.clone()));
+    let mut ids: Vec<String> = g
+        .triples_for_predicate(&identifier)
+        .filter_map(|t| match t.object {
+            TermRef::Literal(l) => Some(l.value().to_string()),
+            _ => None,
+        })
+        .collect();
+    ids.sort();
+    assert_eq!(ids, vec!["x1", "x2"]);
+    assert_eq!(attribute_values(&g), vec!["book", "x3"]);
+}
+
+#[test]
+fn test_validate_options() {
+    use error::ConfigError;
+
+    assert_eq!(convert::ConversionOptions::default().validate(), Ok(()));
+
+    // Provenance without a document node to attach it to
+    let options = convert::ConversionOptions {
+        link_document: true,
+        conversion_time: convert::ConversionTime::Now,
+        ..Default::default()
+    };
+    assert_eq!(
+        options.validate(),
+        Err(vec![
+            ConfigError::Requires {
+                option: "link_document",
+                requires: "document_node",
+            },
+            ConfigError::Requires {
+                option: "conversion_time",
+                requires: "document_node",
+            },
+        ])
+    );
+
+    // A datatype hint shadowed by a binary encoding
+    let options = convert::ConversionOptions {
+        binary: [("signature".to_string(), datatype::BinaryEncoding::Base64)].into(),
+        datatypes: [(
+            "signature".to_string(),
+            oxrdf::NamedNode::new("http://www.w3.org/2001/XMLSchema#string").unwrap(),
+        )]
+        .into(),
+        ..Default::default()
+    };
+    assert_eq!(
+        options.validate(),
+        Err(vec![ConfigError::BinaryAndDatatype {
+            key: "signature".to_string(),
+        }])
+    );
+
+    // Conditions no element can satisfy, and an override in the model namespace
+    let options = convert::ConversionOptions {
+        conditions: vec![
+            "order@status=paid".parse().unwrap(),
+            "order@status=open".parse().unwrap(),
+            "order@currency=EUR".parse().unwrap(),
+        ],
+        namespace_overrides: [(
+            "partner".to_string(),
+            "https://decisym.ai/xml2rdf/model#data/".to_string(),
+        )]
+        .into(),
+        ..Default::default()
+    };
+    assert_eq!(
+        options.validate(),
+        Err(vec![
+            ConfigError::ReservedNamespace {
+                key: "partner".to_string(),
+                namespace: "https://decisym.ai/xml2rdf/model#data/".to_string(),
+            },
+            ConfigError::ContradictoryConditions {
+                element: "order".to_string(),
+                attribute: "status".to_string(),
+            },
+        ])
+    );
+
+    // A fixed conversion time that is not an xsd:dateTime
+    let options = convert::ConversionOptions {
+        document_node: true,
+        conversion_time: convert::ConversionTime::Fixed("yesterday".to_string()),
+        ..Default::default()
+    };
+    let errors = options.validate().unwrap_err();
+    assert!(matches!(
+        errors.as_slice(),
+        [ConfigError::InvalidValue {
+            option: "conversion_time",
+            ..
+        }]
+    ));
+}
+
+#[cfg(feature = "rdf-star")]
+#[test]
+fn test_value_annotations() {
+    use oxrdf::vocab::xsd;
+    use oxrdf::{Literal, Subject, Term};
+
+    let xml = "<books>\n  <book year=\"1999\">Dune</book>\n</books>";
+    let mut w = writer::StringWriter::new();
+    convert::parse_xml_reader(
+        xml.as_bytes(),
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            infer_datatypes: true,
+            value_annotations: true,
+            ..Default::default()
+        },
+    )
+    .expect("conversion failed");
+
+    // The N-Triples-star output parses back with the quoted value triples intact
+    let mut annotations = Vec::new();
+    for quad in RdfParser::from_format(RdfFormat::NTriples).for_reader(w.as_str().as_bytes()) {
+        let quad = quad.expect("invalid N-Triples-star");
+        let Subject::Triple(quoted) = quad.subject else {
+            continue;
+        };
+        assert_eq!(quoted.predicate, HAS_VALUE);
+        let Term::Literal(value) = quoted.object else {
+            panic!("unexpected value {}", quoted.object);
+        };
+        annotations.push((
+            value.value().to_string(),
+            quad.predicate.as_str().to_string(),
+            quad.object,
+        ));
+    }
+    annotations.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+
+    let integer = |n: u64| Term::from(Literal::from(n));
+    let annotation = |value: &str, predicate: NamedNodeRef, object: Term| {
+        (value.to_string(), predicate.as_str().to_string(), object)
+    };
+    assert_eq!(
+        annotations,
+        vec![
+            annotation("1999", vocab::SOURCE_COLUMN, integer(3)),
+            annotation("1999", vocab::SOURCE_LINE, integer(2)),
+            annotation(
+                "1999",
+                vocab::TYPED_BY,
+                Literal::new_simple_literal("inference").into()
+            ),
+            annotation("Dune", vocab::SOURCE_COLUMN, integer(3)),
+            annotation("Dune", vocab::SOURCE_LINE, integer(2)),
+        ]
+    );
+    assert!(w
+        .as_str()
+        .contains(&format!("\"1999\"^^<{}>", xsd::INTEGER.as_str())));
+}
+
+#[test]
+fn test_branch_text_policies() {
+    let xml = "<order>stray<id>7</id></order>";
+    let convert = |policy| {
+        let mut g = Graph::new();
+        let mut w = writer::GraphWriter::new(&mut g);
+        convert::parse_xml_reader(
+            xml.as_bytes(),
+            &mut w,
+            "https://decisym.ai/xml2rdf/data",
+            &convert::ConversionOptions {
+                branch_text: policy,
+                ..Default::default()
+            },
+        )
+        .expect("conversion failed");
+        g
+    };
+
+    let g = convert(convert::BranchTextPolicy::Keep);
+    assert_eq!(literal_values(&g), vec!["7", "stray"]);
+    assert_eq!(g.triples_for_predicate(vocab::HAS_CONTENT).count(), 0);
+
+    // The leaf keeps its text
+    let g = convert(convert::BranchTextPolicy::DropWhenHasChildren);
+    assert_eq!(literal_values(&g), vec!["7"]);
+    assert_eq!(g.triples_for_predicate(vocab::HAS_CONTENT).count(), 0);
+
+    let g = convert(convert::BranchTextPolicy::Mixed);
+    assert_eq!(literal_values(&g), vec!["7", "stray"]);
+    assert_eq!(g.triples_for_predicate(vocab::HAS_CONTENT).count(), 1);
+    assert!(g
+        .triples_for_predicate(rdf::FIRST)
+        .any(|t| matches!(t.object, TermRef::Literal(l) if l.value() == "stray")));
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn test_prometheus_metrics() {
+    let mut w = writer::StringWriter::new();
+    let started = std::time::Instant::now();
+    let result = convert::parse_xml_with_options(
+        vec!["tests/resources/people.xml".to_string()],
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions::default(),
+    );
+    let mut metrics = metrics::Metrics::default();
+    metrics.record(&result, started.elapsed());
+
+    let mut out = Vec::new();
+    metrics.write(&mut out).expect("failed to write metrics");
+    let text = String::from_utf8(out).expect("metrics are not UTF-8");
+    let triples = w.as_str().lines().count();
+    let bytes = fs::metadata("tests/resources/people.xml").unwrap().len();
+    for line in [
+        "# TYPE xml2rdf_documents_total counter".to_string(),
+        "xml2rdf_documents_total 1".to_string(),
+        format!("xml2rdf_triples_total {triples}"),
+        "xml2rdf_errors_total 0".to_string(),
+        format!("xml2rdf_input_bytes_total {bytes}"),
+    ] {
+        assert!(
+            text.lines().any(|l| l == line),
+            "missing '{line}' in {text}"
+        );
+    }
+    assert!(text.contains("\nxml2rdf_duration_seconds_total "));
+
+    // Failed conversions count as errors
+    let result = convert::parse_xml_with_options(
+        vec!["tests/resources/missing.xml".to_string()],
+        &mut writer::StringWriter::new(),
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions::default(),
+    );
+    metrics.record(&result, std::time::Duration::ZERO);
+    assert_eq!(metrics.errors, 1);
+    assert_eq!(metrics.triples, triples as u64);
+}
+
+#[test]
+fn test_flag_elements() {
+    let xml = "<people>\
+        <person><name>Ann</name><premium/></person>\
+        <person><name>Bob</name><premium> </premium></person>\
+        <person><name>Cy</name><premium>no</premium></person>\
+        </people>";
+    let premium = NamedNodeRef::new("https://example.org/premium").unwrap();
+    let mut g = Graph::new();
+    let mut w = writer::GraphWriter::new(&mut g);
+    convert::parse_xml_reader(
+        xml.as_bytes(),
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            flag_elements: [("premium".to_string(), premium.into_owned())].into(),
+            empty_markers: true,
+            ..Default::default()
+        },
+    )
+    .expect("conversion failed");
+
+    let person = TermRef::Literal(oxrdf::LiteralRef::new_simple_literal("person"));
+    let yes = oxrdf::Literal::from(true);
+    let flagged: Vec<_> = g
+        .triples_for_predicate(premium)
+        .map(|t| {
+            assert_eq!(t.object, TermRef::Literal(yes.as_ref()));
+            assert_eq!(
+                g.object_for_subject_predicate(t.subject, HAS_NAME),
+                Some(person)
+            );
+            t.subject
+        })
+        .collect();
+    // Whitespace-only text counts as empty, other text does not
+    assert_eq!(flagged.len(), 2);
+    let no = g
+        .subject_for_predicate_object(HAS_VALUE, oxrdf::LiteralRef::new_simple_literal("no"))
+        .unwrap();
+    let cy = g
+        .subject_for_predicate_object(vocab::HAS_CHILD, no)
+        .unwrap();
+    assert!(!flagged.contains(&cy));
+
+    // The flag elements are still converted, with their empty markers
+    assert_eq!(g.triples_for_predicate(vocab::IS_EMPTY).count(), 2);
+}
+
+#[test]
+fn test_split_values() {
+    let xml = "<post><tags>rust, rdf,,xml </tags><title>a, b</title></post>";
+    let convert = |policy| {
+        let mut g = Graph::new();
+        let mut w = writer::GraphWriter::new(&mut g);
+        convert::parse_xml_reader(
+            xml.as_bytes(),
+            &mut w,
+            "https://decisym.ai/xml2rdf/data",
+            &convert::ConversionOptions {
+                split_values: [("tags".to_string(), ",".to_string())].into(),
+                multi_value_policy: policy,
+                ..Default::default()
+            },
+        )
+        .expect("conversion failed");
+        g
+    };
+
+    // Pieces are trimmed and empty ones dropped; other elements are not split
+    let g = convert(convert::MultiValuePolicy::Concatenate);
+    assert_eq!(literal_values(&g), vec!["a, b", "rdf", "rust", "xml"]);
+    let tags = g
+        .subject_for_predicate_object(HAS_VALUE, oxrdf::LiteralRef::new_simple_literal("rust"))
+        .unwrap();
+    assert_eq!(g.objects_for_subject_predicate(tags, HAS_VALUE).count(), 3);
+
+    let g = convert(convert::MultiValuePolicy::List);
+    let lists = g.triples_for_predicate(HAS_VALUE).count();
+    let mut items: Vec<_> = g
+        .triples_for_predicate(rdf::FIRST)
+        .map(|t| t.object.to_string())
+        .collect();
+    items.sort();
+    assert_eq!(lists, 2);
+    assert_eq!(items, vec!["\"a, b\"", "\"rdf\"", "\"rust\"", "\"xml\""]);
+
+    let options = convert::ConversionOptions {
+        split_values: [("tags".to_string(), String::new())].into(),
+        ..Default::default()
+    };
+    assert!(options.validate().is_err());
+}
+
+#[test]
+fn test_error_display() {
+    let err = convert::parse_xml(
+        vec!["tests/resources/invalid_name.xml".to_string()],
+        &mut writer::StringWriter::new(),
+        "https://decisym.ai/xml2rdf/data",
+    )
+    .expect_err("the element name is not valid in an IRI");
+    let message = err.to_string();
+    assert!(
+        message.starts_with("tests/resources/invalid_name.xml: invalid IRI <"),
+        "{message}"
+    );
+    assert!(message.contains(" at 3:3; "), "{message}");
+
+    let err = convert::parse_xml(
+        vec!["tests/resources/invalid_utf8.xml".to_string()],
+        &mut writer::StringWriter::new(),
+        "https://decisym.ai/xml2rdf/data",
+    )
+    .expect_err("invalid UTF-8 is rejected by default");
+    assert_eq!(
+        err.to_string(),
+        "tests/resources/invalid_utf8.xml: invalid UTF-8 sequence at byte offset 21; \
+         re-encode the input as UTF-8, or convert with the lossy UTF-8 policy"
+    );
+
+    let err = convert::parse_xml(
+        vec!["tests/resources/does_not_exist.xml".to_string()],
+        &mut writer::StringWriter::new(),
+        "https://decisym.ai/xml2rdf/data",
+    )
+    .expect_err("the file does not exist");
+    assert!(matches!(err, error::ConvertError::Open { .. }));
+    assert!(err
+        .to_string()
+        .starts_with("cannot open tests/resources/does_not_exist.xml: "));
+
+    // Documents read from a reader have no file to name
+    let err = convert::convert_bytes(
+        b"<a><b/></a>",
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            max_input_bytes: Some(4),
+            ..Default::default()
+        },
+    )
+    .expect_err("the input is over the limit");
+    assert!(err
+        .to_string()
+        .starts_with("input exceeds the maximum of 4 bytes; "));
+}
+
+#[test]
+fn test_atomic_file_writer() {
+    let output = std::env::temp_dir().join("xml2rdf_atomic.nt");
+    let _ = fs::remove_file(&output);
+    let convert = |w: &mut dyn writer::RdfWriter| {
+        convert::parse_xml_with_options(
+            vec!["tests/resources/attributes.xml".to_string()],
+            w,
+            "https://decisym.ai/xml2rdf/data",
+            &convert::ConversionOptions {
+                node_ids: convert::NodeIds::Deterministic {
+                    hash: convert::IdHash::default(),
+                },
+                ..Default::default()
+            },
+        )
+        .expect("conversion failed");
+    };
+    let mut expected = writer::StringWriter::new();
+    convert(&mut expected);
+
+    let mut w = writer::FileWriter::to_file_atomic(output.to_string_lossy().into_owned())
+        .expect("failed to create the temporary file");
+    convert(&mut w);
+    assert!(!output.exists(), "output appeared before finishing");
+    w.finish().expect("failed to finish the output");
+    assert_eq!(fs::read_to_string(&output).unwrap(), expected.as_str());
+
+    // An unfinished output leaves the existing file alone and no temporary file behind
+    let atomic = writer::AtomicFile::create(&output).unwrap();
+    let temp = atomic.temp_path().to_path_buf();
+    let mut w = writer::FileWriter::new(atomic);
+    convert(&mut w);
+    assert!(temp.exists());
+    drop(w);
+    assert!(!temp.exists());
+    assert_eq!(fs::read_to_string(&output).unwrap(), expected.as_str());
+    fs::remove_file(&output).unwrap();
+}
+
+#[test]
+fn test_owl_safe() {
+    let options = convert::ConversionOptions {
+        owl_safe: true,
+        ..Default::default()
+    };
+    let g = convert_to_graph("tests/resources/attributes.xml", &options);
+    let item = NamedNodeRef::new_unchecked("https://decisym.ai/xml2rdf/model#catalog.item");
+    let id = NamedNodeRef::new_unchecked("https://decisym.ai/xml2rdf/model#catalog.item.-id");
+    for (term, kind) in [
+        (item, vocab::OWL_CLASS),
+        (id, vocab::OWL_CLASS),
+        (vocab::XML_ELEMENT, vocab::OWL_CLASS),
+        (vocab::HAS_CHILD, vocab::OWL_OBJECT_PROPERTY),
+        (vocab::HAS_ATTRIBUTE, vocab::OWL_OBJECT_PROPERTY),
+        (HAS_NAME, vocab::OWL_DATATYPE_PROPERTY),
+        (HAS_VALUE, vocab::OWL_DATATYPE_PROPERTY),
+    ] {
+        assert!(
+            g.contains(oxrdf::TripleRef::new(term, rdf::TYPE, kind)),
+            "{term} is not declared {kind}"
+        );
+    }
+    let sub_class_of = oxrdf::vocab::rdfs::SUB_CLASS_OF;
+    assert!(g.contains(oxrdf::TripleRef::new(
+        item,
+        sub_class_of,
+        vocab::XML_ELEMENT
+    )));
+    assert!(g.contains(oxrdf::TripleRef::new(
+        id,
+        sub_class_of,
+        vocab::XML_ATTRIBUTE
+    )));
+
+    // Only classes are placed in the hierarchy, and every type is an IRI
+    for triple in g.triples_for_predicate(sub_class_of) {
+        assert!(
+            g.contains(oxrdf::TripleRef::new(
+                triple.subject,
+                rdf::TYPE,
+                vocab::OWL_CLASS
+            )),
+            "{} is not a declared class",
+            triple.subject
+        );
+    }
+    assert!(g
+        .triples_for_predicate(rdf::TYPE)
+        .all(|t| matches!(t.object, TermRef::NamedNode(_))));
+
+    // List values would turn hasValue into an object property
+    let options = convert::ConversionOptions {
+        owl_safe: true,
+        multi_value_policy: convert::MultiValuePolicy::List,
+        ..Default::default()
+    };
+    assert!(matches!(
+        options.validate().unwrap_err().as_slice(),
+        [error::ConfigError::InvalidValue {
+            option: "multi_value_policy",
+            ..
+        }]
+    ));
+    // So would mixed content, which is linked as an rdf:List
+    let options = convert::ConversionOptions {
+        owl_safe: true,
+        branch_text: convert::BranchTextPolicy::Mixed,
+        ..Default::default()
+    };
+    assert!(matches!(
+        options.validate().unwrap_err().as_slice(),
+        [error::ConfigError::InvalidValue {
+            option: "branch_text",
+            ..
+        }]
+    ));
+}
+
+#[test]
+fn test_document_graph_upsert() {
+    let dir = std::env::temp_dir().join("xml2rdf_upsert");
+    fs::create_dir_all(&dir).unwrap();
+    let (orders, other) = (dir.join("orders.xml"), dir.join("other.xml"));
+    fs::write(&other, "<order id=\"o-2\"><item>pear</item></order>").unwrap();
+    let values = |dataset: &oxrdf::Dataset, graph: oxrdf::GraphNameRef| {
+        let mut values: Vec<String> = dataset
+            .quads_for_graph_name(graph)
+            .filter(|q| q.predicate == HAS_VALUE)
+            .filter_map(|q| match q.object {
+                TermRef::Literal(l) => Some(l.value().to_string()),
+                _ => None,
+            })
+            .collect();
+        values.sort();
+        values
+    };
+
+    for identity in [
+        convert::DocumentIdentity::Source,
+        convert::DocumentIdentity::FileName,
+        convert::DocumentIdentity::RootAttribute("id".to_string()),
+    ] {
+        let options = convert::ConversionOptions {
+            document_graphs: Some(identity.clone()),
+            ..Default::default()
+        };
+        let mut dataset = oxrdf::Dataset::new();
+        let convert = |xml: &str, dataset: &mut oxrdf::Dataset| {
+            fs::write(&orders, xml).unwrap();
+            let files = vec![
+                orders.to_string_lossy().into_owned(),
+                other.to_string_lossy().into_owned(),
+            ];
+            let mut w = writer::DatasetWriter::new(dataset).replacing_graphs();
+            convert::parse_xml_with_options(
+                files,
+                &mut w,
+                "https://decisym.ai/xml2rdf/data",
+                &options,
+            )
+            .expect("conversion failed");
+        };
+        convert("<order id=\"o-1\"><item>apple</item></order>", &mut dataset);
+        convert(
+            "<order id=\"o-1\"><item>banana</item><item>cherry</item></order>",
+            &mut dataset,
+        );
+
+        let graph = match identity {
+            convert::DocumentIdentity::Source => {
+                let path = fs::canonicalize(&orders).unwrap();
+                oxrdf::NamedNode::new(format!("file://{}", path.display())).unwrap()
+            }
+            convert::DocumentIdentity::FileName => {
+                oxrdf::NamedNode::new("https://decisym.ai/xml2rdf/data/orders.xml").unwrap()
+            }
+            convert::DocumentIdentity::RootAttribute(_) => {
+                oxrdf::NamedNode::new("https://decisym.ai/xml2rdf/data/o-1").unwrap()
+            }
+        };
+        assert_eq!(
+            values(&dataset, graph.as_ref().into()),
+            ["banana", "cherry", "o-1"]
+        );
+        assert!(values(&dataset, oxrdf::GraphNameRef::DefaultGraph).is_empty());
+        // The unchanged document converted alongside is replaced by an identical copy
+        let graphs: std::collections::BTreeSet<_> =
+            dataset.iter().map(|q| q.graph_name.to_string()).collect();
+        assert_eq!(graphs.len(), 2, "{identity:?}: {graphs:?}");
+    }
+
+    // A document lacking its identity is rejected
+    let mut dataset = oxrdf::Dataset::new();
+    let err = convert::parse_xml_with_options(
+        vec!["tests/resources/attributes.xml".to_string()],
+        &mut writer::DatasetWriter::new(&mut dataset),
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            document_graphs: Some(convert::DocumentIdentity::RootAttribute("id".to_string())),
+            ..Default::default()
+        },
+    )
+    .expect_err("the root element has no id");
+    assert!(matches!(
+        err,
+        error::ConvertError::MissingIdentity {
+            attribute: Some(_),
+            ..
+        }
+    ));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_graph_diff() {
+    let old_xml = fs::read_to_string("tests/resources/people.xml").unwrap();
+    let new_xml = old_xml.replace("<city>Mikrevo</city>", "<city>Sofia</city>");
+    let options = convert::ConversionOptions {
+        node_ids: convert::NodeIds::Deterministic {
+            hash: convert::IdHash::default(),
+        },
+        ..Default::default()
+    };
+    let dir = std::env::temp_dir();
+    let load = |xml: &str, name: &str| {
+        let path = dir.join(name);
+        let nt =
+            convert::convert_bytes(xml.as_bytes(), "https://decisym.ai/xml2rdf/data", &options)
+                .expect("conversion failed");
+        fs::write(&path, nt).unwrap();
+        let graph = stats::load_graph(&path.to_string_lossy()).expect("failed to load graph");
+        fs::remove_file(&path).unwrap();
+        graph
+    };
+    let (old, new) = (load(&old_xml, "diff_old.nt"), load(&new_xml, "diff_new.nt"));
+    assert!(diff::diff_graphs(&old, &old).is_empty());
+
+    let diff = diff::diff_graphs(&old, &new);
+    let city = old
+        .subject_for_predicate_object(HAS_VALUE, &oxrdf::Literal::new_simple_literal("Mikrevo"))
+        .expect("no city element")
+        .into_owned();
+    let value = |v: &str| {
+        oxrdf::Triple::new(
+            city.clone(),
+            HAS_VALUE,
+            oxrdf::Literal::new_simple_literal(v),
+        )
+    };
+    assert_eq!(diff.removed, [value("Mikrevo")]);
+    assert_eq!(diff.added, [value("Sofia")]);
+    assert_eq!(diff.summary(), "1 removed, 1 added, 1 changed");
+    assert_eq!(
+        diff.to_string(),
+        format!("- {} .\n+ {} .\n", value("Mikrevo"), value("Sofia"))
+    );
+}
+
+#[test]
+fn test_only_predicates() {
+    let all = convert_to_graph("tests/resources/people.xml", &Default::default());
+    let options = convert::ConversionOptions {
+        only_predicates: Some(vec![HAS_VALUE.into_owned()]),
+        ..Default::default()
+    };
+    let mut g = Graph::new();
+    let summary = convert::parse_xml_with_options(
+        vec!["tests/resources/people.xml".to_string()],
+        &mut writer::GraphWriter::new(&mut g),
+        "https://decisym.ai/xml2rdf/data",
+        &options,
+    )
+    .expect("conversion failed");
+
+    assert!(g.iter().all(|t| t.predicate == HAS_VALUE));
+    assert_eq!(g.len(), all.triples_for_predicate(HAS_VALUE).count());
+    assert_eq!(literal_values(&g), literal_values(&all));
+    // Dropped triples are not counted
+    assert_eq!(summary.triples, g.len());
+}
+
+#[test]
+fn test_windows_file_iris() {
+    for (path, iri) in [
+        (r"C:\data\file.xml", "file:///C:/data/file.xml"),
+        (
+            r"c:\My Data\Ünïcode.xml",
+            "file:///c:/My%20Data/%C3%9Cn%C3%AFcode.xml",
+        ),
+        ("D:/mixed/slashes.xml", "file:///D:/mixed/slashes.xml"),
+        (r"\\?\C:\data\file.xml", "file:///C:/data/file.xml"),
+        (
+            r"\\server\share\dir\file.xml",
+            "file://server/share/dir/file.xml",
+        ),
+        (
+            r"\\?\UNC\server\share\file.xml",
+            "file://server/share/file.xml",
+        ),
+        ("/home/me/data/file.xml", "file:///home/me/data/file.xml"),
+        (
+            r"/home/me/back\slash.xml",
+            "file:///home/me/back%5Cslash.xml",
+        ),
+    ] {
+        let minted = convert::absolute_file_iri(path);
+        assert_eq!(minted, iri, "{path}");
+        assert!(oxrdf::NamedNode::new(&minted).is_ok(), "{minted}");
+    }
+}
+
+#[test]
+fn test_conversion_cache() {
+    let dir = std::env::temp_dir().join("xml2rdf_cache");
+    fs::create_dir_all(&dir).unwrap();
+    let (first, second, path) = (
+        dir.join("first.xml"),
+        dir.join("second.xml"),
+        dir.join("cache.json"),
+    );
+    fs::write(&first, "<order><item>apple</item></order>").unwrap();
+    fs::write(&second, "<order><item>pear</item></order>").unwrap();
+    let _ = fs::remove_file(&path);
+    let files = vec![
+        first.to_string_lossy().into_owned(),
+        second.to_string_lossy().into_owned(),
+    ];
+    let run = |expected: &[&String]| {
+        let mut cache = cache::ConversionCache::open(&path);
+        let changed = cache.changed(files.clone());
+        assert_eq!(changed.iter().collect::<Vec<_>>(), expected);
+        let mut g = Graph::new();
+        convert::parse_xml_with_options(
+            changed,
+            &mut writer::GraphWriter::new(&mut g),
+            "https://decisym.ai/xml2rdf/data",
+            &Default::default(),
+        )
+        .expect("conversion failed");
+        cache.save().unwrap();
+        cache
+    };
+
+    assert!(!run(&[&files[0], &files[1]]).discarded());
+    run(&[]);
+    // Touched without changing its size
+    File::options()
+        .write(true)
+        .open(&second)
+        .unwrap()
+        .set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(60))
+        .unwrap();
+    run(&[&files[1]]);
+    run(&[]);
+
+    // A corrupt cache is ignored, so every file is converted again
+    fs::write(&path, "{\"files\": {\"first.xml\": ").unwrap();
+    assert!(run(&[&files[0], &files[1]]).discarded());
+    assert!(!run(&[]).discarded());
+}
+
+#[test]
+fn test_element_kinds() {
+    let options = convert::ConversionOptions {
+        element_kinds: true,
+        ..Default::default()
+    };
+    let g = convert_to_graph("tests/resources/people_a.xml", &options);
+    let avery = g
+        .subjects_for_predicate_object(HAS_VALUE, &oxrdf::Literal::new_simple_literal("Avery"))
+        .next()
+        .expect("no name node");
+    let person = g
+        .subject_for_predicate_object(vocab::HAS_CHILD, avery)
+        .expect("no parent node");
+    let kinds = |node| -> Vec<_> {
+        g.objects_for_subject_predicate(node, rdf::TYPE)
+            .filter(|&class| {
+                class == vocab::LEAF_ELEMENT.into() || class == vocab::BRANCH_ELEMENT.into()
+            })
+            .collect()
+    };
+    assert_eq!(kinds(avery), [vocab::LEAF_ELEMENT.into()]);
+    assert_eq!(kinds(person), [vocab::BRANCH_ELEMENT.into()]);
+
+    // Each element, the root and every child, gets exactly one kind
+    let elements = 1 + g.triples_for_predicate(vocab::HAS_CHILD).count();
+    let kinds = |kind| g.subjects_for_predicate_object(rdf::TYPE, kind).count();
+    assert_eq!(
+        kinds(vocab::LEAF_ELEMENT) + kinds(vocab::BRANCH_ELEMENT),
+        elements
+    );
+}
+
+#[test]
+fn test_document_iri() {
+    let convert = |files: &[&str], document_iri: convert::DocumentIri| {
+        let options = convert::ConversionOptions {
+            document_node: true,
+            document_iri,
+            ..Default::default()
+        };
+        let mut g = Graph::new();
+        convert::parse_xml_with_options(
+            files.iter().map(|f| f.to_string()).collect(),
+            &mut writer::GraphWriter::new(&mut g),
+            "https://decisym.ai/xml2rdf/data",
+            &options,
+        )
+        .map(|_| g)
+    };
+    let documents = |g: &Graph| -> Vec<String> {
+        let mut documents: Vec<String> = g
+            .subjects_for_predicate_object(rdf::TYPE, vocab::XML_DOCUMENT)
+            .map(|d| d.to_string())
+            .collect();
+        documents.sort();
+        documents
+    };
+
+    let iri = oxrdf::NamedNode::new("https://example.com/documents/orders").unwrap();
+    let g = convert(
+        &["tests/resources/orders.xml"],
+        convert::DocumentIri::Explicit(iri.clone()),
+    )
+    .expect("conversion failed");
+    assert_eq!(documents(&g), [iri.to_string()]);
+    assert!(g
+        .triples_for_subject(&iri)
+        .any(|t| t.predicate == vocab::HAS_ROOT));
+
+    // Each file gets its own IRI
+    let files = [
+        "tests/resources/people_a.xml",
+        "tests/resources/people_b.xml",
+    ];
+    let g = convert(&files, convert::DocumentIri::FileName).expect("conversion failed");
+    assert_eq!(
+        documents(&g),
+        [
+            "<https://decisym.ai/xml2rdf/data/people_a.xml>",
+            "<https://decisym.ai/xml2rdf/data/people_b.xml>"
+        ]
+    );
+    // An explicit IRI cannot name two documents
+    let e = convert(&files, convert::DocumentIri::Explicit(iri.clone())).unwrap_err();
+    assert!(matches!(
+        e,
+        error::ConvertError::InvalidDocumentIri {
+            source: Some(source),
+            iri: Some(_),
+        } if source == "tests/resources/people_b.xml"
+    ));
+}
+
+#[test]
+fn test_check_well_formed() {
+    assert_eq!(
+        check::check_file("tests/resources/orders.xml").unwrap(),
+        Ok(())
+    );
+
+    let e = check::check_file("tests/resources/malformed.xml")
+        .unwrap()
+        .unwrap_err();
+    // The parser reports the end of the mismatched closing tag, 1-based when displayed
+    assert_eq!((e.position.row, e.position.column), (2, 26));
+    assert_eq!(e.to_string(), "3:27: Unexpected closing tag: itm != item");
+
+    // A document cut short is not well-formed, though it can still be converted
+    let e = check::check_file("tests/resources/people.xml")
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(e.position.row, 60);
+    assert!(e.message.contains("still inside the root element"));
+
+    assert!(check::check_file("tests/resources/missing.xml").is_err());
+}
+
+#[test]
+fn test_node_id_hash() {
+    use std::process::Command;
+
+    let root = |hash: convert::IdHash| {
+        let options = convert::ConversionOptions {
+            node_ids: convert::NodeIds::Deterministic { hash },
+            document_node: true,
+            ..Default::default()
+        };
+        let g = convert_to_graph("tests/resources/people_a.xml", &options);
+        let root = g
+            .triples_for_predicate(vocab::HAS_ROOT)
+            .next()
+            .expect("no document node")
+            .object
+            .to_string();
+        root
+    };
+
+    let sha1 = root(convert::IdHash::Sha1);
+    let fnv1a = root(convert::IdHash::Fnv1a);
+    assert_ne!(sha1, fnv1a);
+    assert_eq!(sha1, root(convert::IdHash::Sha1));
+    assert_eq!(fnv1a, root(convert::IdHash::Fnv1a));
+    // FNV-1a of the root's key "tests/resources/people_a.xml/0", as a UUIDv8
+    assert_eq!(
+        fnv1a,
+        "<https://decisym.ai/xml2rdf/data/4fdc0757-d632-85b0-9be3-15203bfd9196>"
+    );
+    #[cfg(feature = "digest")]
+    assert_eq!(
+        root(convert::IdHash::Sha256),
+        "<https://decisym.ai/xml2rdf/data/8c66ccb6-ce67-87b5-9f0c-4610746b9e9d>"
+    );
+
+    // The command line names the hash after the strategy
+    let convert = |node_ids: &str| {
+        Command::new(env!("CARGO_BIN_EXE_xml2rdf"))
+            .args(["convert", "--xml", "tests/resources/people_a.xml"])
+            .args(["--document-node", "--node-ids", node_ids])
+            .output()
+            .expect("failed to run xml2rdf")
+    };
+    let output = convert("deterministic:fnv1a");
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout).unwrap().contains(&fnv1a));
+    assert!(!convert("deterministic:md5").status.success());
+    assert!(!convert("uuid:fnv1a").status.success());
+}
+
+#[test]
+fn test_text_as_node() {
+    let input = std::env::temp_dir().join("xml2rdf_text_as_node.xml");
+    fs::write(&input, "<p>a <b>bold</b> c<![CDATA[d]]> <i/>\n</p>").unwrap();
+    let graph = oxrdf::NamedNode::new_unchecked("https://example.com/reconstruction");
+    let mut dataset = oxrdf::Dataset::new();
+    convert::parse_xml_with_options(
+        vec![input.to_string_lossy().into_owned()],
+        &mut writer::DatasetWriter::new(&mut dataset),
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            text_as_node: true,
+            reconstruction_graph: Some(graph.clone()),
+            ..Default::default()
+        },
+    )
+    .expect("conversion failed");
+
+    let named = |name: &str| {
+        dataset
+            .quads_for_predicate(HAS_NAME)
+            .find(|q| q.object == oxrdf::Literal::new_simple_literal(name).as_ref().into())
+            .map(|q| q.subject)
+            .expect("no element")
+    };
+    let p = named("p");
+    // The children of <p> in document order, the trailing whitespace left out
+    let mut children: Vec<(u64, String)> = dataset
+        .quads_for_subject(p)
+        .filter(|q| q.predicate == vocab::HAS_CHILD)
+        .map(|q| {
+            let child = match q.object {
+                TermRef::NamedNode(n) => n,
+                _ => panic!("unexpected child {}", q.object),
+            };
+            let index = dataset
+                .quads_for_subject(child)
+                .find(|q| {
+                    q.predicate == vocab::SIBLING_INDEX && q.graph_name == graph.as_ref().into()
+                })
+                .map(|q| match q.object {
+                    TermRef::Literal(l) => l.value().parse().unwrap(),
+                    _ => panic!("unexpected index"),
+                })
+                .expect("no sibling index");
+            let text = dataset
+                .quads_for_subject(child)
+                .find(|q| q.predicate == HAS_VALUE || q.predicate == HAS_NAME)
+                .map(|q| match q.object {
+                    TermRef::Literal(l) => l.value().to_string(),
+                    _ => panic!("unexpected value"),
+                })
+                .unwrap();
+            (index, text)
+        })
+        .collect();
+    children.sort();
+    assert_eq!(
+        children,
+        [
+            (0, "a".to_string()),
+            (1, "b".to_string()),
+            (2, "cd".to_string()),
+            (3, "i".to_string())
+        ]
+    );
+
+    // Text nodes hold the values, elements hold none
+    let text_nodes = dataset
+        .quads_for_predicate(rdf::TYPE)
+        .filter(|q| q.object == vocab::TEXT_NODE.into())
+        .count();
+    assert_eq!(text_nodes, 3);
+    assert_eq!(
+        dataset
+            .quads_for_subject(p)
+            .filter(|q| q.predicate == HAS_VALUE)
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn test_name_encoding() {
+    let xml = r#"<record><data>{"my field": 1, "my-field": 2}</data></record>"#;
+    let convert = |name_encoding| {
+        let mut g = Graph::new();
+        let mut w = writer::GraphWriter::new(&mut g);
+        convert::parse_xml_reader(
+            xml.as_bytes(),
+            &mut w,
+            "https://decisym.ai/xml2rdf/data",
+            &convert::ConversionOptions {
+                embedded_json: [("data".to_string(), convert::EmbeddedJson::Expand)].into(),
+                name_encoding,
+                ..Default::default()
+            },
+        )
+        .map(|_| g)
+    };
+    // hasName holds each key as written, the class IRI its encoded form
+    let assert_class = |g: &Graph, name: &str, class: &str| {
+        let node = g
+            .subject_for_predicate_object(HAS_NAME, oxrdf::LiteralRef::new_simple_literal(name))
+            .unwrap_or_else(|| panic!("no node named {name}"));
+        let class = format!("https://decisym.ai/xml2rdf/model#record.data.{class}");
+        assert_eq!(
+            g.object_for_subject_predicate(node, rdf::TYPE),
+            Some(NamedNodeRef::new_unchecked(&class).into())
+        );
+    };
+
+    let g = convert(convert::NameEncoding::Percent).expect("conversion failed");
+    assert_class(&g, "my field", "my%20field");
+    assert_class(&g, "my-field", "my-field");
+
+    // The key written later gets a disambiguator
+    let g = convert(convert::NameEncoding::Slug).expect("conversion failed");
+    assert_class(&g, "my field", "my-field");
+    assert_class(&g, "my-field", "my-field-2");
+
+    match convert(convert::NameEncoding::Reject) {
+        Err(error::ConvertError::UnsafeName { name, path, .. }) => {
+            assert_eq!(name, "my field");
+            assert_eq!(path, "record.data");
+        }
+        other => panic!("expected an unsafe name error, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_ndjson_writer() {
+    use oxrdf::vocab::xsd;
+    use writer::RdfWriter;
+
+    let xml = "<item><count>5</count></item>";
+    let mut w = writer::NdJsonWriter::new(Vec::new());
+    convert::parse_xml_reader(
+        xml.as_bytes(),
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            infer_datatypes: true,
+            ..Default::default()
+        },
+    )
+    .expect("conversion failed");
+    // The conversion writes no language-tagged literals
+    let label = oxrdf::Literal::new_language_tagged_literal_unchecked("Anzahl", "de");
+    w.add_triple(oxrdf::TripleRef::new(
+        NamedNodeRef::new_unchecked("https://decisym.ai/xml2rdf/model#item.count"),
+        oxrdf::vocab::rdfs::LABEL,
+        &label,
+    ))
+    .unwrap();
+    let out = String::from_utf8(w.into_inner().unwrap()).unwrap();
+
+    // Each line is a JSON object, as a map of its string members
+    let lines: Vec<std::collections::HashMap<String, String>> = out
+        .lines()
+        .map(|line| match json::parse(line) {
+            Some(json::Value::Object(members)) => members
+                .into_iter()
+                .map(|(key, value)| match value {
+                    json::Value::String(value) => (key, value),
+                    other => panic!("unexpected member {key}: {other:?}"),
+                })
+                .collect(),
+            _ => panic!("not a JSON object: {line}"),
+        })
+        .collect();
+    let find = |p: &str, o: &str| {
+        lines
+            .iter()
+            .find(|line| line["p"] == p && line["o"] == o)
+            .unwrap_or_else(|| panic!("no line with {p} {o}"))
+    };
+
+    let class = find(
+        rdf::TYPE.as_str(),
+        "https://decisym.ai/xml2rdf/model#item.count",
+    );
+    assert_eq!(class["ot"], "iri");
+    assert!(!class.contains_key("dt") && !class.contains_key("lang"));
+
+    let value = find(HAS_VALUE.as_str(), "5");
+    assert_eq!(value["ot"], "literal");
+    assert_eq!(value["dt"], xsd::INTEGER.as_str());
+    assert!(!value.contains_key("lang"));
+
+    let label = find(oxrdf::vocab::rdfs::LABEL.as_str(), "Anzahl");
+    assert_eq!(label["ot"], "literal");
+    assert_eq!(label["dt"], rdf::LANG_STRING.as_str());
+    assert_eq!(label["lang"], "de");
+    assert_eq!(label["s"], "https://decisym.ai/xml2rdf/model#item.count");
+}
+
+#[test]
+fn test_infer_namespace() {
+    use std::process::Command;
+
+    let sample = "tests/resources/default_ns_a.xml";
+    assert_eq!(
+        convert::infer_namespace(File::open(sample).unwrap()).as_deref(),
+        Some("http://a")
+    );
+    assert_eq!(
+        convert::infer_namespace(File::open("tests/resources/people_a.xml").unwrap()),
+        None
+    );
+
+    // Returns the namespaces of the nodes described in the N-Triples output
+    let namespaces = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_xml2rdf"))
+            .args(["convert", "--xml", sample])
+            .args(args)
+            .output()
+            .expect("failed to run xml2rdf");
+        assert!(output.status.success());
+        let mut namespaces: Vec<String> = String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .filter_map(|line| line.strip_prefix('<'))
+            .filter(|subject| !subject.starts_with(vocab::X2R))
+            .filter_map(|subject| Some(subject.split_once('>')?.0.rsplit_once('/')?.0.to_string()))
+            .collect();
+        namespaces.sort();
+        namespaces.dedup();
+        namespaces
+    };
+    assert_eq!(namespaces(&["--infer-namespace"]), ["http://a"]);
+    // An explicit namespace takes precedence
+    assert_eq!(
+        namespaces(&[
+            "--infer-namespace",
+            "--namespace",
+            "https://example.com/data"
+        ]),
+        ["https://example.com/data"]
+    );
+    assert_eq!(namespaces(&[]), ["https://decisym.ai/xml2rdf/data"]);
+}
+
+#[test]
+fn test_instance_counts() {
+    let options = convert::ConversionOptions {
+        instance_counts: true,
+        ..Default::default()
+    };
+    let count = |g: &Graph, class: &str| {
+        let class = format!("https://decisym.ai/xml2rdf/model#{class}");
+        match g.object_for_subject_predicate(
+            NamedNodeRef::new_unchecked(&class),
+            vocab::INSTANCE_COUNT,
+        ) {
+            Some(TermRef::Literal(l)) => l.value().parse::<usize>().unwrap(),
+            other => panic!("unexpected count for {class}: {other:?}"),
+        }
+    };
+
+    let file = "tests/resources/people.xml";
+    let g = convert_to_graph(file, &options);
+    let persons = fs::read_to_string(file)
+        .unwrap()
+        .matches("<Person>")
+        .count();
+    assert!(persons > 1);
+    assert_eq!(count(&g, "People.Person"), persons);
+    assert_eq!(count(&g, "People.Person.firstName"), persons);
+    assert_eq!(count(&g, "People"), 1);
+
+    // Counts span all documents of a conversion
+    let mut g = Graph::new();
+    convert::parse_xml_with_options(
+        vec![
+            "tests/resources/people_a.xml".to_string(),
+            "tests/resources/people_a.xml".to_string(),
+        ],
+        &mut writer::GraphWriter::new(&mut g),
+        "https://decisym.ai/xml2rdf/data",
+        &options,
+    )
+    .expect("conversion failed");
+    assert_eq!(count(&g, "people.person"), 2);
+    assert_eq!(
+        g.triples_for_predicate(vocab::INSTANCE_COUNT)
+            .filter(|t| t.subject
+                == NamedNodeRef::new_unchecked("https://decisym.ai/xml2rdf/model#people.person")
+                    .into())
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn test_corpus_node() {
+    let corpus = NamedNodeRef::new_unchecked("https://example.com/corpus");
+    let convert = |options: &convert::ConversionOptions| {
+        let mut g = Graph::new();
+        convert::parse_xml_with_options(
+            vec![
+                "tests/resources/people_a.xml".to_string(),
+                "tests/resources/people_b.xml".to_string(),
+            ],
+            &mut writer::GraphWriter::new(&mut g),
+            "https://decisym.ai/xml2rdf/data",
+            options,
+        )
+        .expect("conversion failed");
+        g
+    };
+    let root = |g: &Graph, name: &str| {
+        g.subject_for_predicate_object(HAS_NAME, oxrdf::LiteralRef::new_simple_literal(name))
+            .unwrap_or_else(|| panic!("no root named {name}"))
+            .into_owned()
+    };
+
+    let g = convert(&convert::ConversionOptions {
+        corpus_node: Some(corpus.into_owned()),
+        ..Default::default()
+    });
+    let children: Vec<_> = g
+        .objects_for_subject_predicate(corpus, vocab::HAS_CHILD)
+        .map(|o| o.into_owned())
+        .collect();
+    assert_eq!(children.len(), 2);
+    for name in ["people", "staff"] {
+        assert!(children.contains(&root(&g, name).into()));
+    }
+
+    // A configured predicate, with the inverse link
+    let member = NamedNodeRef::new_unchecked("https://example.com/member");
+    let g = convert(&convert::ConversionOptions {
+        corpus_node: Some(corpus.into_owned()),
+        corpus_predicate: Some(member.into_owned()),
+        inverse_links: Some(convert::InverseLinks::Materialized),
+        ..Default::default()
+    });
+    assert_eq!(
+        g.objects_for_subject_predicate(corpus, vocab::HAS_CHILD)
+            .count(),
+        0
+    );
+    for name in ["people", "staff"] {
+        let root = root(&g, name);
+        assert!(g.contains(oxrdf::TripleRef::new(corpus, member, &root)));
+        assert!(g.contains(oxrdf::TripleRef::new(&root, vocab::HAS_PARENT, corpus)));
+    }
+
+    // Without a corpus node roots have no parent
+    let g = convert(&Default::default());
+    assert_eq!(
+        g.subject_for_predicate_object(vocab::HAS_CHILD, &root(&g, "people")),
+        None
+    );
+}
+
+#[test]
+fn test_whitespace_only_attributes() {
+    let xml = r#"<r><e a="  ">  </e><f b=" x "> x </f></r>"#;
+    // Values of the element named `name`, or of its attribute
+    let values = |options: &convert::ConversionOptions, name: &str, attribute: bool| {
+        let mut g = Graph::new();
+        let summary = convert::parse_xml_reader(
+            xml.as_bytes(),
+            &mut writer::GraphWriter::new(&mut g),
+            "https://decisym.ai/xml2rdf/data",
+            &convert::ConversionOptions {
+                collect_warnings: true,
+                ..options.clone()
+            },
+        )
+        .expect("conversion failed");
+        let node = g
+            .subject_for_predicate_object(HAS_NAME, oxrdf::LiteralRef::new_simple_literal(name))
+            .unwrap_or_else(|| panic!("no node named {name}"));
+        let node = if attribute {
+            match g.object_for_subject_predicate(node, HAS_ATTRIBUTE) {
+                Some(TermRef::NamedNode(n)) => n.into(),
+                _ => panic!("no attribute on {name}"),
+            }
+        } else {
+            node
+        };
+        let values: Vec<String> = g
+            .objects_for_subject_predicate(node, HAS_VALUE)
+            .map(|o| match o {
+                TermRef::Literal(l) => l.value().to_string(),
+                _ => panic!("unexpected value"),
+            })
+            .collect();
+        let empty = summary
+            .warnings
+            .iter()
+            .any(|w| w.kind == convert::WarningKind::EmptyAttribute);
+        (values, empty)
+    };
+
+    // Attributes follow the element text policy
+    let policies = [
+        (convert::ConversionOptions::default(), vec![], vec!["x"]),
+        (
+            convert::ConversionOptions {
+                emit_whitespace_only: true,
+                ..Default::default()
+            },
+            vec![""],
+            vec!["x"],
+        ),
+        (
+            convert::ConversionOptions {
+                emit_whitespace_only: true,
+                trim_text: false,
+                ..Default::default()
+            },
+            vec!["  "],
+            vec![" x "],
+        ),
+    ];
+    for (options, blank, padded) in policies {
+        let (e, _) = values(&options, "e", false);
+        let (a, empty) = values(&options, "e", true);
+        assert_eq!(e, blank);
+        assert_eq!(a, blank);
+        // A dropped whitespace-only value is reported like an empty one
+        assert_eq!(empty, blank.is_empty());
+        assert_eq!(values(&options, "f", false).0, padded);
+        assert_eq!(values(&options, "f", true).0, padded);
+    }
+}
+
+#[test]
+fn test_record_options() {
+    let convert = |record_options| {
+        let mut g = Graph::new();
+        convert::parse_xml_with_options(
+            vec![
+                "tests/resources/people_a.xml".to_string(),
+                "tests/resources/people_b.xml".to_string(),
+            ],
+            &mut writer::GraphWriter::new(&mut g),
+            "https://example.com/data",
+            &convert::ConversionOptions {
+                document_node: true,
+                node_ids: convert::NodeIds::Deterministic {
+                    hash: convert::IdHash::default(),
+                },
+                record_options,
+                ..Default::default()
+            },
+        )
+        .expect("conversion failed");
+        g
+    };
+
+    let g = convert(true);
+    let runs: Vec<_> = g
+        .subjects_for_predicate_object(rdf::TYPE, vocab::CONVERSION_RUN)
+        .collect();
+    assert_eq!(runs.len(), 1);
+    let run = runs[0];
+    // Both documents link the shared run node
+    let documents: Vec<_> = g
+        .subjects_for_predicate_object(rdf::TYPE, vocab::XML_DOCUMENT)
+        .collect();
+    assert_eq!(documents.len(), 2);
+    for document in documents {
+        assert_eq!(
+            g.object_for_subject_predicate(document, vocab::CONVERTED_BY),
+            Some(run.into())
+        );
+    }
+    let value = |predicate| match g.object_for_subject_predicate(run, predicate) {
+        Some(TermRef::Literal(l)) => l.value().to_string(),
+        other => panic!("unexpected value {other:?}"),
+    };
+    assert_eq!(value(vocab::DATA_NAMESPACE), "https://example.com/data");
+    assert_eq!(value(vocab::NODE_IDS), "deterministic");
+    assert_eq!(value(vocab::NODE_ID_HASH), "sha1");
+    assert_eq!(value(vocab::TOOL_VERSION), env!("CARGO_PKG_VERSION"));
+
+    // Deterministic run IRIs depend on what the run records
+    let run = |class_key| {
+        let g = convert_to_graph(
+            "tests/resources/people_a.xml",
+            &convert::ConversionOptions {
+                document_node: true,
+                node_ids: convert::NodeIds::Deterministic {
+                    hash: convert::IdHash::default(),
+                },
+                record_options: true,
+                class_key,
+                ..Default::default()
+            },
+        );
+        let run = g
+            .subject_for_predicate_object(rdf::TYPE, vocab::CONVERSION_RUN)
+            .expect("no run node")
+            .to_string();
+        run
+    };
+    assert_eq!(run(convert::ClassKey::Path), run(convert::ClassKey::Path));
+    assert_ne!(run(convert::ClassKey::Path), run(convert::ClassKey::Name));
+
+    // Off by default
+    let g = convert(false);
+    assert_eq!(
+        g.subjects_for_predicate_object(rdf::TYPE, vocab::CONVERSION_RUN)
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn test_property_style() {
+    let file = "tests/resources/default_ns_a.xml";
+    let g = convert_to_graph(
+        file,
+        &convert::ConversionOptions {
+            property_style: true,
+            ..Default::default()
+        },
+    );
+    let item = g
+        .subject_for_predicate_object(HAS_NAME, oxrdf::LiteralRef::new_simple_literal("item"))
+        .expect("no item element");
+    let property = |name: &str| {
+        let predicate = format!("https://decisym.ai/xml2rdf/model#-{name}");
+        match g.object_for_subject_predicate(item, NamedNodeRef::new_unchecked(&predicate)) {
+            Some(TermRef::Literal(l)) => l.value().to_string(),
+            other => panic!("unexpected {name} property {other:?}"),
+        }
+    };
+    // Attributes are properties of the element, namespaced ones by their local name
+    assert_eq!(property("id"), "1");
+    assert_eq!(property("code"), "7");
+    assert_eq!(g.triples_for_predicate(HAS_ATTRIBUTE).count(), 0);
+    assert_eq!(
+        g.subjects_for_predicate_object(rdf::TYPE, vocab::XML_ATTRIBUTE)
+            .count(),
+        0
+    );
+
+    // Preserved namespaces key the property on the namespace URI
+    let g = convert_to_graph(
+        file,
+        &convert::ConversionOptions {
+            property_style: true,
+            preserve_namespaces: true,
+            ..Default::default()
+        },
+    );
+    let code = NamedNodeRef::new_unchecked(
+        "https://decisym.ai/xml2rdf/model#-%7Bhttp%3A%2F%2Fx%2F%7Dcode",
+    );
+    assert_eq!(g.triples_for_predicate(code).count(), 1);
+}
+
+#[test]
+fn test_turtle_format() {
+    use std::process::Command;
+
+    // Returns the output of converting people_a.xml with deterministic node IDs
+    let convert = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_xml2rdf"))
+            .args(["convert", "--xml", "tests/resources/people_a.xml"])
+            .args(["--node-ids", "deterministic"])
+            .args(args)
+            .output()
+            .expect("failed to run xml2rdf");
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let parse = |text: &str, format: RdfFormat| {
+        RdfParser::from_format(format)
+            .for_reader(text.as_bytes())
+            .map(|q| oxrdf::Triple::from(q.expect("invalid output")))
+            .collect::<Graph>()
+    };
+
+    // Turtle goes to stdout too, with the declared prefixes
+    let turtle = convert(&[
+        "--format",
+        "turtle",
+        "--prefixes",
+        "tests/resources/prefixes.ttl",
+    ]);
+    let declared: std::collections::HashSet<&str> = turtle
+        .lines()
+        .filter(|line| line.starts_with("@prefix"))
+        .collect();
+    assert_eq!(
+        declared,
+        [
+            "@prefix x2r: <https://decisym.ai/xml2rdf/model#> .",
+            "@prefix data: <https://decisym.ai/xml2rdf/data/> .",
+            "@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .",
+        ]
+        .into()
+    );
+    let ntriples = convert(&[]);
+    assert_eq!(
+        parse(&turtle, RdfFormat::Turtle),
+        parse(&ntriples, RdfFormat::NTriples)
+    );
+}
+
+#[test]
+fn test_file_name_graphs() {
+    use std::process::Command;
+
+    let output = Command::new(env!("CARGO_BIN_EXE_xml2rdf"))
+        .args(["convert", "--document-graph", "file-name", "--xml"])
+        .args([
+            "tests/resources/people_a.xml",
+            "tests/resources/people_b.xml",
+        ])
+        .output()
+        .expect("failed to run xml2rdf");
+    assert!(output.status.success());
+    let nquads = String::from_utf8(output.stdout).unwrap();
+    let mut dataset = oxrdf::Dataset::new();
+    for quad in RdfParser::from_format(RdfFormat::NQuads).for_reader(nquads.as_bytes()) {
+        dataset.insert(&quad.expect("invalid N-Quads"));
+    }
+
+    // Each file gets a graph named after it, holding its root element
+    for (file, root) in [("people_a.xml", "people"), ("people_b.xml", "staff")] {
+        let graph =
+            oxrdf::NamedNode::new(format!("https://decisym.ai/xml2rdf/data/{file}")).unwrap();
+        assert!(
+            dataset
+                .quads_for_graph_name(graph.as_ref())
+                .any(|q| q.predicate == HAS_NAME
+                    && q.object == oxrdf::LiteralRef::new_simple_literal(root).into()),
+            "{file}"
+        );
+    }
+
+    // Files of the same name cannot share a graph
+    let err = convert::parse_xml_with_options(
+        vec![
+            "tests/resources/people_a.xml".to_string(),
+            "tests/resources/people_a.xml".to_string(),
+        ],
+        &mut writer::DatasetWriter::new(&mut oxrdf::Dataset::new()),
+        "https://decisym.ai/xml2rdf/data",
+        &convert::ConversionOptions {
+            document_graphs: Some(convert::DocumentIdentity::FileName),
+            ..Default::default()
+        },
+    )
+    .expect_err("the second document reuses the graph of the first");
+    assert!(matches!(
+        err,
+        error::ConvertError::DuplicateGraph { ref graph, .. }
+            if graph == "https://decisym.ai/xml2rdf/data/people_a.xml"
+    ));
+}
+
+#[test]
+fn test_json_ld_writer() {
+    let options = convert::ConversionOptions {
+        node_ids: convert::NodeIds::Deterministic {
+            hash: convert::IdHash::default(),
+        },
+        reconstruction_graph: Some(oxrdf::NamedNode::new("https://example.com/order").unwrap()),
+        ..Default::default()
+    };
+    let mut dataset = oxrdf::Dataset::new();
+    convert::parse_xml_with_options(
+        vec!["tests/resources/people_a.xml".to_string()],
+        &mut writer::DatasetWriter::new(&mut dataset),
+        "https://decisym.ai/xml2rdf/data",
+        &options,
+    )
+    .expect("conversion failed");
+    let mut prefixes = prefixes::PrefixMap::default();
+    prefixes.insert("data", "https://decisym.ai/xml2rdf/data/");
+    let mut w = writer::JsonLdWriter::new(Vec::new(), &prefixes);
+    convert::parse_xml_with_options(
+        vec!["tests/resources/people_a.xml".to_string()],
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+        &options,
+    )
+    .expect("conversion failed");
+    let text = String::from_utf8(w.into_inner().unwrap()).unwrap();
+    let Some(json::Value::Object(document)) = json::parse(&text) else {
+        panic!("not a JSON object: {text}");
+    };
+    let member = |members: &[(String, json::Value)], key: &str| {
+        members
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+            .unwrap_or_else(|| panic!("no {key}"))
+    };
+    let json::Value::Object(context) = member(&document, "@context") else {
+        panic!("the context is not an object");
+    };
+    assert_eq!(
+        member(&context, "data"),
+        json::Value::String("https://decisym.ai/xml2rdf/data/".to_string())
+    );
+    assert_eq!(
+        member(&context, "hasName"),
+        json::Value::String(HAS_NAME.as_str().to_string())
+    );
+
+    // A node object per subject of the default graph, then one for the reconstruction graph
+    let json::Value::Array(nodes) = member(&document, "@graph") else {
+        panic!("@graph is not an array");
+    };
+    let subjects = |graph: oxrdf::GraphNameRef| {
+        dataset
+            .quads_for_graph_name(graph)
+            .map(|q| q.subject)
+            .collect::<std::collections::HashSet<_>>()
+            .len()
+    };
+    assert_eq!(nodes.len(), subjects(oxrdf::GraphNameRef::DefaultGraph) + 1);
+    let Some(json::Value::Object(graph)) = nodes.last() else {
+        panic!("the last node is not an object");
+    };
+    assert_eq!(
+        member(graph, "@id"),
+        json::Value::String("https://example.com/order".to_string())
+    );
+    let json::Value::Array(ordered) = member(graph, "@graph") else {
+        panic!("the named graph has no @graph");
+    };
+    assert_eq!(
+        ordered.len(),
+        subjects(
+            oxrdf::NamedNodeRef::new("https://example.com/order")
+                .unwrap()
+                .into()
+        )
+    );
+
+    // Values are compacted with the context
+    let root = nodes
+        .iter()
+        .find_map(|node| match node {
+            json::Value::Object(node)
+                if member(node, "@type")
+                    == json::Value::Array(vec![json::Value::String("x2r:people".to_string())]) =>
+            {
+                Some(node.clone())
+            }
+            _ => None,
+        })
+        .expect("no root element node");
+    let json::Value::String(id) = member(&root, "@id") else {
+        panic!("@id is not a string");
+    };
+    assert!(id.starts_with("data:"), "{id}");
+    let json::Value::Array(children) = member(&root, "hasChild") else {
+        panic!("hasChild is not an array");
+    };
+    assert!(children
+        .iter()
+        .all(|c| matches!(c, json::Value::String(c) if c.starts_with("data:"))));
+}
+
+#[test]
+fn test_rdf_xml_writer() {
+    use writer::RdfWriter;
+
+    let options = convert::ConversionOptions {
+        node_ids: convert::NodeIds::Deterministic {
+            hash: convert::IdHash::default(),
+        },
+        property_style: true,
+        ..Default::default()
+    };
+    let convert = |w: &mut dyn writer::RdfWriter| {
+        convert::parse_xml_with_options(
+            vec!["tests/resources/default_ns_a.xml".to_string()],
+            w,
+            "https://decisym.ai/xml2rdf/data",
+            &options,
+        )
+        .expect("conversion failed");
+    };
+    let mut expected = Graph::new();
+    convert(&mut writer::GraphWriter::new(&mut expected));
+    let mut prefixes = prefixes::PrefixMap::default();
+    prefixes.insert("x2r", vocab::X2R);
+    let mut w = writer::RdfXmlWriter::new(Vec::new(), &prefixes);
+    convert(&mut w);
+    let xml = String::from_utf8(w.into_inner().unwrap()).unwrap();
+    assert!(xml.contains("xmlns:x2r=\"https://decisym.ai/xml2rdf/model#\""));
+    assert!(xml.contains("<x2r:hasValue>Widget</x2r:hasValue>"));
+    let parsed: Graph = RdfParser::from_format(RdfFormat::RdfXml)
+        .for_reader(xml.as_bytes())
+        .map(|q| oxrdf::Triple::from(q.expect("invalid RDF/XML")))
+        .collect();
+    assert_eq!(parsed, expected);
+
+    // A predicate without a local name cannot be written
+    let node = NamedNodeRef::new("https://example.com/n").unwrap();
+    let predicate = NamedNodeRef::new("https://example.com/flags/").unwrap();
+    let mut w = writer::RdfXmlWriter::new(Vec::new(), &prefixes);
+    let err = w
+        .add_triple(oxrdf::TripleRef::new(node, predicate, node))
+        .expect_err("the predicate has no local name");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_trig_writer() {
+    let options = convert::ConversionOptions {
+        node_ids: convert::NodeIds::Deterministic {
+            hash: convert::IdHash::default(),
+        },
+        document_graphs: Some(convert::DocumentIdentity::FileName),
+        ..Default::default()
+    };
+    let convert = |w: &mut dyn writer::RdfWriter| {
+        convert::parse_xml_with_options(
+            vec![
+                "tests/resources/people_a.xml".to_string(),
+                "tests/resources/people_b.xml".to_string(),
+            ],
+            w,
+            "https://decisym.ai/xml2rdf/data",
+            &options,
+        )
+        .expect("conversion failed");
+    };
+    let mut expected = oxrdf::Dataset::new();
+    convert(&mut writer::DatasetWriter::new(&mut expected));
+    let mut prefixes = prefixes::PrefixMap::default();
+    prefixes.insert("data", "https://decisym.ai/xml2rdf/data/");
+    let mut w = writer::TriGWriter::new(Vec::new(), &prefixes);
+    convert(&mut w);
+    let trig = String::from_utf8(w.into_inner().unwrap()).unwrap();
+    assert!(trig.contains("data:people_a.xml {"));
+    assert!(trig.contains("data:people_b.xml {"));
+    let mut parsed = oxrdf::Dataset::new();
+    for quad in RdfParser::from_format(RdfFormat::TriG).for_reader(trig.as_bytes()) {
+        parsed.insert(&quad.expect("invalid TriG"));
+    }
+    assert_eq!(parsed, expected);
+}
+
+#[cfg(feature = "compress")]
+#[test]
+fn test_compressed_output() {
+    use std::io::Read;
+    use writer::{CompressedWriter, Compression};
+
+    let options = convert::ConversionOptions {
+        node_ids: convert::NodeIds::Deterministic {
+            hash: convert::IdHash::default(),
+        },
+        ..Default::default()
+    };
+    let convert = |w: &mut dyn writer::RdfWriter| {
+        convert::parse_xml_with_options(
+            vec!["tests/resources/people_a.xml".to_string()],
+            w,
+            "https://decisym.ai/xml2rdf/data",
+            &options,
+        )
+        .expect("conversion failed");
+    };
+    let mut expected = writer::StringWriter::new();
+    convert(&mut expected);
+    let expected = expected.into_string();
+
+    let compress = |compression| {
+        let out = CompressedWriter::new(Vec::new(), compression).unwrap();
+        let mut w = writer::FileWriter::new(out);
+        convert(&mut w);
+        w.finish().unwrap()
+    };
+    let mut gzip = String::new();
+    flate2::read::GzDecoder::new(&compress(Compression::Gzip)[..])
+        .read_to_string(&mut gzip)
+        .unwrap();
+    assert_eq!(gzip, expected);
+    let zstd = zstd::decode_all(&compress(Compression::Zstd)[..]).unwrap();
+    assert_eq!(String::from_utf8(zstd).unwrap(), expected);
+
+    // A failed conversion still ends the stream, keeping the triples written before the error
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_xml2rdf"))
+        .args([
+            "convert",
+            "--compress",
+            "gzip",
+            "--node-ids",
+            "deterministic",
+            "--xml",
+        ])
+        .args([
+            "tests/resources/people_a.xml",
+            "tests/resources/invalid_utf8.xml",
+        ])
+        .output()
+        .expect("failed to run xml2rdf");
+    assert_eq!(output.status.code(), Some(65));
+    let mut partial = String::new();
+    flate2::read::GzDecoder::new(&output.stdout[..])
+        .read_to_string(&mut partial)
+        .expect("the compressed stream is incomplete");
+    assert!(partial.starts_with(&expected));
+}
+
+#[cfg(not(feature = "zip"))]
+#[test]
+fn test_zip_archive_without_zip_feature() {
+    let mut g = Graph::new();
+    let mut w = writer::GraphWriter::new(&mut g);
+    let e = convert::parse_xml(
+        vec!["tests/resources/archive.zip".to_string()],
+        &mut w,
+        "https://decisym.ai/xml2rdf/data",
+    )
+    .unwrap_err();
+    assert!(e.to_string().contains("built without zip support"));
+    assert!(g.is_empty());
+}
+
+#[test]
+fn test_invalid_binary_warn() {
+    use oxrdf::vocab::xsd;
+
+    let options = convert::ConversionOptions {
+        binary: [("signature".to_string(), datatype::BinaryEncoding::Base64)].into(),
+        invalid_binary: convert::BinaryPolicy::Warn,
+        collect_warnings: true,
+        ..Default::default()
+    };
+    let mut g = Graph::new();
+    let summary = convert::parse_xml_with_options(
+        vec!["tests/resources/binary_invalid.xml".to_string()],
+        &mut writer::GraphWriter::new(&mut g),
+        "https://decisym.ai/xml2rdf/data",
+        &options,
+    )
+    .expect("invalid base64 should only be warned about");
+
+    let values: Vec<_> = g
+        .triples_for_predicate(HAS_VALUE)
+        .filter_map(|t| match t.object {
+            TermRef::Literal(l) => Some((l.value().to_string(), l.datatype().into_owned())),
+            _ => None,
+        })
+        .filter(|(value, _)| value == "not base64!")
+        .collect();
+    assert_eq!(
+        values,
+        vec![("not base64!".to_string(), xsd::STRING.into_owned())]
+    );
+    assert_eq!(summary.warnings.len(), 1);
+    let warning = &summary.warnings[0];
+    assert_eq!(warning.kind, convert::WarningKind::InvalidBinary);
+    assert_eq!(warning.path, "envelope.signature");
+    assert_eq!(
+        warning.source.as_deref(),
+        Some("tests/resources/binary_invalid.xml")
+    );
+}
+
+#[test]
+fn test_max_triples_per_file_zero() {
+    use std::process::Command;
+
+    let output = Command::new(env!("CARGO_BIN_EXE_xml2rdf"))
+        .args(["convert", "--xml", "tests/resources/attributes.xml"])
+        .args(["--output-file", "never-written.nt"])
+        .args(["--max-triples-per-file", "0"])
+        .output()
+        .expect("failed to run xml2rdf");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--max-triples-per-file"));
+    assert!(!std::path::Path::new("never-written.0.nt").exists());
+}
+
+#[test]
+fn test_child_predicates() {
+    use oxrdf::vocab::rdfs;
+
+    let file = "tests/resources/attributes.xml";
+    let options = convert::ConversionOptions {
+        child_predicates: true,
+        ..Default::default()
+    };
+    let has_item = NamedNodeRef::new_unchecked("https://decisym.ai/xml2rdf/model#has-item");
+    let g = convert_to_graph(file, &options);
+    assert_eq!(g.triples_for_predicate(has_item).count(), 2);
+    assert_eq!(g.triples_for_predicate(vocab::HAS_CHILD).count(), 0);
+    // The hierarchy is part of the ontology
+    assert_eq!(g.triples_for_predicate(rdfs::SUB_PROPERTY_OF).count(), 0);
+
+    let g = convert_to_graph(
+        file,
+        &convert::ConversionOptions {
+            embed_ontology: true,
+            property_style: true,
+            ..options
+        },
+    );
+    let mut sub_properties: Vec<_> = g
+        .triples_for_predicate(rdfs::SUB_PROPERTY_OF)
+        .filter(|t| t.subject.to_string().contains("model#-") || t.subject == has_item.into())
+        .map(|t| (t.subject.to_string(), t.object.to_string()))
+        .collect();
+    let model = |name: &str| format!("<https://decisym.ai/xml2rdf/model#{name}>");
+    let mut expected = vec![(model("has-item"), model("hasChild"))];
+    for attribute in ["-id", "-name", "-color"] {
+        expected.push((model(attribute), model("hasValue")));
+    }
+    sub_properties.sort();
+    expected.sort();
+    assert_eq!(sub_properties, expected);
+}
+
+#[test]
+fn test_setup_failure_exit_status() {
+    use std::process::Command;
+
+    let status = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_xml2rdf"))
+            .args(["convert", "--xml", "tests/resources/attributes.xml"])
+            .args(args)
+            .output()
+            .expect("failed to run xml2rdf")
+            .status
+            .code()
+    };
+    assert_eq!(
+        status(&["--prefixes", "tests/resources/does_not_exist.json"]),
+        Some(66)
+    );
+    assert_eq!(
+        status(&["--output-file", "tests/resources/does_not_exist/out.nt"]),
+        Some(73)
+    );
+}
+
+#[test]
+fn test_document_output_replaced() {
+    use std::process::Command;
+
+    for extension in ["jsonld", "rdf"] {
+        let output = std::env::temp_dir().join(format!(
+            "xml2rdf_replaced_{}.{extension}",
+            std::process::id()
+        ));
+        for atomic in [false, true] {
+            for _ in 0..2 {
+                let status = Command::new(env!("CARGO_BIN_EXE_xml2rdf"))
+                    .args([
+                        "convert",
+                        "--xml",
+                        "tests/resources/people_a.xml",
+                        "--output-file",
+                    ])
+                    .arg(&output)
+                    .args(atomic.then_some("--atomic"))
+                    .status()
+                    .expect("failed to run xml2rdf");
+                assert!(status.success());
+            }
+            // A second run replaces the document rather than appending another one
+            let text = fs::read_to_string(&output).unwrap();
+            match extension {
+                "jsonld" => assert!(
+                    matches!(json::parse(&text), Some(json::Value::Object(_))),
+                    "{text}"
+                ),
+                _ => assert_eq!(text.matches("<rdf:RDF").count(), 1, "{text}"),
+            }
+        }
+        fs::remove_file(&output).unwrap();
+    }
+}